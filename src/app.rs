@@ -1,7 +1,10 @@
 use anyhow::Result;
 use crossbeam_channel::{Receiver, Sender, unbounded};
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+        MouseButton, MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
@@ -10,23 +13,40 @@ use ratatui::{
     backend::CrosstermBackend,
     layout::{Alignment, Constraint, Direction, Layout},
     style::Style,
+    text::{Line, Span},
     widgets::Paragraph,
 };
 use std::collections::{HashMap, HashSet};
-use std::path::Path;
-use std::{io, thread, time::Duration};
+use std::path::{Path, PathBuf};
+use std::{fs, io, thread, time::Duration};
 
 use crate::components::{
-    Component, file_browser::FileBrowserState, hotspot_editor::HotspotEditorState, logs::LogsState,
-    mapping_editor::MappingEditorState, runner::RunnerState, settings::SettingsState,
-    theme_overrides::ThemeOverridesState,
+    Component,
+    confirm::ConfirmState,
+    file_browser::FileBrowserState,
+    hotspot_editor::HotspotEditorState,
+    logs::LogsState,
+    mapping_editor::MappingEditorState,
+    results::ResultsState,
+    runner::RunnerState,
+    settings::SettingsState,
+    theme_overrides::{BuildOption, ThemeOverridesState},
 };
 use crate::config::Config;
 use crate::event::AppMsg;
 use crate::model::cursor;
-use crate::pipeline::cursor_io::{load_cursor_folder, load_cursor_folder_from_pngs};
-use crate::pipeline_worker::PipelineWorker;
-use crate::widgets::theme::get_theme;
+use crate::pipeline::cursor_io::{
+    extract_cursor_archive, is_cursor_archive, load_cursor_folder, load_cursor_folder_from_hlc,
+    load_cursor_folder_from_pngs,
+};
+use crate::pipeline::limits;
+use crate::pipeline::log::LogLevel;
+use crate::pipeline::theme_actions::ThemeAction;
+use crate::pipeline::theme_build::written_dirs;
+use crate::pipeline::win2xcur::AniParser;
+use crate::pipeline::win2xcur::converter::resize_filter_name;
+use crate::pipeline_worker::{PipelineWorker, ThemeVariant};
+use crate::widgets::theme::{ThemeType, get_theme, set_theme};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Focus {
@@ -104,6 +124,15 @@ impl Focus {
             _ => None,
         }
     }
+
+    // Last panel reached by repeating `step`, i.e. the far edge in that direction
+    fn furthest(self, step: fn(&Self) -> Option<Self>) -> Self {
+        let mut focus = self;
+        while let Some(next) = step(&focus) {
+            focus = next;
+        }
+        focus
+    }
 }
 
 pub struct App {
@@ -114,57 +143,152 @@ pub struct App {
     pub logs: LogsState,
     pub settings: SettingsState,
     pub theme_overrides: ThemeOverridesState,
+    pub results: ResultsState,
+    pub confirm: ConfirmState,
+    // Directories the user already agreed to overwrite this session
+    pub confirmed_overwrites: HashSet<PathBuf>,
     pub pipeline_worker: PipelineWorker,
     pub tx: Sender<AppMsg>,
     pub rx: Receiver<AppMsg>,
     pub focus: Focus,
     pub modified_cursors: HashSet<String>,
+    // Folder behind the editor's cursors, reloaded with F5
+    pub last_cursor_dir: Option<PathBuf>,
+    // Page of the status bar hints shown on narrow terminals, cycled with F1
+    pub status_page: usize,
+    // Panel shown alone full-screen, toggled with Ctrl+Space
+    pub maximized_focus: Option<Focus>,
+    // Ctrl+hjkl at an edge wraps to the opposite side instead of doing nothing
+    pub wrap_navigation: bool,
+    // Largest cursor image width or height the loaders and pipeline accept
+    pub max_cursor_dimension: u32,
+    // Zip picked as the input and the temp directory its cursors were extracted to; the
+    // directory is removed when this is replaced or the app exits
+    pub input_archive: Option<(PathBuf, tempfile::TempDir)>,
+    // Images unpacked from the .hlc files of the last Hyprcursor theme opened in the editor
+    pub hlc_unpack_dir: Option<tempfile::TempDir>,
+    // Color theme read from the config, kept unless one is picked in Settings
+    pub saved_theme: ThemeType,
+    // Cleared when the config file could not be parsed, so quitting leaves it for the user to fix
+    pub save_config_on_quit: bool,
 }
 
 impl App {
-    pub fn new_with_picker(picker: ratatui_image::picker::Picker) -> Self {
+    /// `theme` overrides the saved color theme, e.g. from `--theme`
+    pub fn new_with_picker(
+        picker: ratatui_image::picker::Picker,
+        theme: Option<ThemeType>,
+    ) -> Self {
+        match Config::load() {
+            Ok(config) => Self::from_config(picker, config, theme),
+            Err(e) => {
+                let mut app = Self::from_config(picker, Config::default(), theme);
+                app.save_config_on_quit = false;
+                let _ = app.tx.send(AppMsg::LogMessageLevel(
+                    LogLevel::Error,
+                    format!(
+                        "Could not read config, using defaults; it won't be saved on quit: {}",
+                        e
+                    ),
+                ));
+                app
+            }
+        }
+    }
+
+    fn from_config(
+        picker: ratatui_image::picker::Picker,
+        config: Config,
+        theme: Option<ThemeType>,
+    ) -> Self {
         let (tx, rx) = unbounded();
-        let config = Config::default();
+        set_theme(theme.unwrap_or(config.theme));
 
         let mut file_browser = FileBrowserState::default();
         file_browser.set_sender(tx.clone());
+        file_browser.set_bookmarks(config.bookmarks.clone());
 
         let mut runner = RunnerState::default();
         runner.set_sender(tx.clone());
 
         // Only set input dir if it's not the default ".", so mapping editor starts hidden
         if config.input_dir.as_path() != Path::new(".") {
-            runner.set_input_dir(config.input_dir.clone());
+            // Routed through the message loop so sources get scanned like a manual selection
+            let _ = tx.send(AppMsg::InputDirSelected(config.input_dir.clone()));
         }
         runner.set_output_dir(config.output_dir.clone());
 
-        let mapping_editor = MappingEditorState::new(config.mapping.clone());
+        let mut mapping_editor = MappingEditorState::new(config.mapping.clone());
+        mapping_editor.set_min_match_score(config.auto_match_min_score);
 
-        let pipeline_worker = PipelineWorker::new(tx.clone(), config.thread_count);
+        let mut pipeline_worker = PipelineWorker::new(tx.clone(), config.thread_count);
+        pipeline_worker.set_essential_cursors(config.essential_cursors.clone());
+        pipeline_worker.set_verbosity(config.log_verbosity);
+        pipeline_worker.set_resize_filter(config.resize_filter);
+        pipeline_worker.set_max_dimension(config.max_cursor_dimension);
+        pipeline_worker.set_hlc_compression(config.hlc_compression);
+        pipeline_worker.set_hyprcursor_resize_algorithm(config.hyprcursor_resize_algorithm);
+        pipeline_worker.set_hyprcursor_resize_overrides(config.hyprcursor_resize_overrides.clone());
+        pipeline_worker.set_hyprcursor_manifest_format(config.hyprcursor_manifest_format);
+        pipeline_worker.set_output_layout(config.output_layout);
 
         let mut settings = SettingsState::default();
         settings.set_thread_count(config.thread_count);
+        settings.set_log_verbosity(config.log_verbosity);
+        settings.set_resize_filter(config.resize_filter);
+        settings.set_hlc_compression(config.hlc_compression);
+        settings.set_hypr_resize(config.hyprcursor_resize_algorithm);
+        settings.set_manifest_format(config.hyprcursor_manifest_format);
+        settings.set_output_layout(config.output_layout);
+        settings.set_shadow(config.shadow_enabled, config.shadow.clone());
+        pipeline_worker.set_shadow(settings.shadow_config());
+
+        let mut theme_overrides = ThemeOverridesState::default();
+        theme_overrides.set_selected_sizes(&config.selected_sizes);
+        theme_overrides.inherits = config.theme_inherits.clone();
+        theme_overrides.description = config.theme_description.clone();
+
+        let mut cursor_editor = HotspotEditorState::new_with_picker(picker);
+        cursor_editor
+            .preview
+            .set_cache_capacity(config.preview_cache_capacity);
+        cursor_editor
+            .preview
+            .set_grid_min_scale(config.preview_grid_min_scale);
+        cursor_editor.preview.set_shadow(settings.shadow_config());
 
         Self {
             file_browser,
-            cursor_editor: HotspotEditorState::new_with_picker(picker),
+            cursor_editor,
             mapping_editor,
             runner,
             logs: LogsState::default(),
             settings,
-            theme_overrides: ThemeOverridesState::default(),
+            theme_overrides,
+            results: ResultsState::default(),
+            confirm: ConfirmState::default(),
+            confirmed_overwrites: HashSet::new(),
             pipeline_worker,
             tx,
             rx,
             focus: Focus::FileBrowser,
             modified_cursors: HashSet::new(),
+            last_cursor_dir: None,
+            status_page: 0,
+            maximized_focus: None,
+            wrap_navigation: config.wrap_navigation,
+            max_cursor_dimension: limits::effective_max_dimension(config.max_cursor_dimension),
+            input_archive: None,
+            hlc_unpack_dir: None,
+            saved_theme: config.theme,
+            save_config_on_quit: true,
         }
     }
 
     pub fn run(&mut self) -> Result<()> {
         enable_raw_mode()?;
         let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen)?;
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
         let backend = CrosstermBackend::new(stdout);
         let mut terminal = Terminal::new(backend)?;
         terminal.hide_cursor()?;
@@ -187,9 +311,18 @@ impl App {
                     .constraints([Constraint::Min(1), Constraint::Length(1)])
                     .split(area);
 
-                if self.cursor_editor.maximized {
-                    self.cursor_editor
-                        .render(main_chunks[0], f.buffer_mut(), true);
+                if let Some(focus) = self.maximized_focus {
+                    let area = main_chunks[0];
+                    let buf = f.buffer_mut();
+                    match focus {
+                        Focus::FileBrowser => self.file_browser.render(area, buf, true),
+                        Focus::Runner => self.runner.render(area, buf, true),
+                        Focus::Overrides => self.theme_overrides.render(area, buf, true),
+                        Focus::Editor => self.cursor_editor.render(area, buf, true),
+                        Focus::Logs => self.logs.render(area, buf, true),
+                        Focus::Mapping => self.mapping_editor.render(area, buf, true),
+                        Focus::Settings => self.settings.render(area, buf, true),
+                    }
                 } else {
                     // Always show all three columns
                     let columns = Layout::default()
@@ -266,24 +399,39 @@ impl App {
                 // Status bar
                 let focus_str = format!("{:?}", self.focus);
                 let status_text = format!(
-                    "q: Quit | Ctrl+hjkl: Navigate | Focus: {} | {}",
+                    "q: Quit | Ctrl+hjkl: Navigate | Ctrl+Space: Maximize | Ctrl+S: Save all | F5: Reload | Focus: {} | {}",
                     focus_str,
                     match self.focus {
-                        Focus::FileBrowser => "i/o: Set In/Out | Enter: Select | l: Load",
-                        Focus::Runner => "c: Full Convert | x: XCur | p: PNG",
-                        Focus::Overrides => "Tab: Switch Field | Type to edit",
-                        Focus::Editor => "Space: Play | ,/.: Frame | Arrows: Hotspot | S: Save",
-                        Focus::Logs => "Logs View",
+                        Focus::FileBrowser => "i/o: Set In/Out | Enter: Select | l: Load | /: Filter | .: Hidden | b/1-9: Bookmarks",
+                        Focus::Runner => "c: Full Convert | x: XCur | p: PNG | l: Symlinks | h: HTML gallery | t: .tar.gz | Esc: Cancel",
+                        Focus::Overrides => "←/→: Name/Inherits/Description | Type to edit | ↑↓: Select | Enter: Toggle",
+                        Focus::Editor =>
+                            "Space: Play | ,/.: Frame | Arrows: Hotspot | 1-9: Size | g: Go to | +/-: Zoom | </>: Speed | c: Crosshair | m: Grid | b: Checkerboard | d: Shadow | a: All sizes | y/p: Copy/Paste hotspot | t: Tip | C: Center | G: GIF | e: Sheet | i: ANI timing | x: Exclude | S: Save",
+                        Focus::Logs => "↑↓/jk: Scroll | f: Filter level",
                         Focus::Settings => "↑↓/jk: Select | Enter: Apply | ←→/hl: Quick Switch",
-                        Focus::Mapping => "Enter: Edit | s: Save",
+                        Focus::Mapping => "Enter: Edit | n/d: Add/Remove | v: Check | u: Unused | m: Re-match missing | s: Save | w: Write TOML",
                     }
                 );
 
+                // Page the hints when the terminal is too narrow to show them all
+                let pages = paginate_hints(&status_text, main_chunks[1].width as usize);
+                let page = self.status_page % pages.len();
+                let mut spans = vec![Span::styled(
+                    pages[page].clone(),
+                    Style::default().fg(theme.text_secondary),
+                )];
+                if pages.len() > 1 {
+                    spans.push(Span::styled(
+                        format!(" ({}/{}) F1: More", page + 1, pages.len()),
+                        Style::default().fg(theme.text_highlight),
+                    ));
+                }
 
-                let status = Paragraph::new(status_text)
-                    .style(Style::default().fg(theme.text_secondary))
-                    .alignment(Alignment::Center);
+                let status = Paragraph::new(Line::from(spans)).alignment(Alignment::Center);
                 f.render_widget(status, main_chunks[1]);
+
+                self.results.render(area, f.buffer_mut(), true);
+                self.confirm.render(area, f.buffer_mut(), true);
             })?;
 
             // Check for messages from tick thread or other sources
@@ -301,6 +449,7 @@ impl App {
                             break 'outer;
                         }
                     }
+                    Event::Mouse(mouse) => self.handle_mouse(mouse),
                     Event::Resize(_, _) => {}
                     _ => {}
                 }
@@ -331,15 +480,46 @@ impl App {
             AppMsg::Tick => {
                 // Tick is handled by Editor component for animation
             }
-            AppMsg::MappingChanged(x11_name, _win_name) => {
+            AppMsg::MappingChanged(x11_name, win_name) => {
+                if win_name.is_none() {
+                    let _ = self.tx.send(AppMsg::LogMessage(format!(
+                        "Removed {} from the mapping",
+                        x11_name
+                    )));
+                }
                 self.modified_cursors.insert(x11_name.clone());
             }
+            AppMsg::RematchMissing => {
+                // Pick up files added to the input directory since it was selected
+                let sources = match &self.runner.input_dir {
+                    Some(dir) => scan_sources(dir),
+                    None => self.mapping_editor.available_sources.clone(),
+                };
+                let matched = self.mapping_editor.rematch_missing(sources, &self.tx);
+                let _ = self.tx.send(AppMsg::LogMessage(format!(
+                    "Auto-match: {} entries matched, {} still missing",
+                    matched.len(),
+                    self.mapping_editor.missing_sources().len()
+                )));
+                for (x11_name, win_name) in matched {
+                    let _ = self
+                        .tx
+                        .send(AppMsg::MappingChanged(x11_name, Some(win_name)));
+                }
+            }
+            AppMsg::AniTimingRequested(x11_name) => {
+                self.load_ani_timing(x11_name);
+            }
             AppMsg::InputDirSelected(_) | AppMsg::OutputDirSelected(_) => {
                 self.handle_dir_selection(&msg);
             }
             AppMsg::PipelineStarted
             | AppMsg::ConvertXCursorOnly
             | AppMsg::ConvertPNGOnly
+            | AppMsg::RefreshSymlinks
+            | AppMsg::GenerateGallery
+            | AppMsg::PackageTarGz
+            | AppMsg::CancelPipeline
             | AppMsg::PipelineCompleted(_)
             | AppMsg::XCursorGenerated(_) => {
                 self.handle_pipeline_msg(&msg);
@@ -361,6 +541,72 @@ impl App {
                     }
                 )));
             }
+            AppMsg::ResizeFilterChanged(filter) => {
+                self.pipeline_worker.set_resize_filter(*filter);
+                let _ = self.tx.send(AppMsg::LogMessage(format!(
+                    "Resize filter set to {}",
+                    resize_filter_name(*filter)
+                )));
+            }
+            AppMsg::LogVerbosityChanged(verbosity) => {
+                self.pipeline_worker.set_verbosity(*verbosity);
+                let _ = self.tx.send(AppMsg::LogMessage(format!(
+                    "Log level set to {}",
+                    verbosity.name()
+                )));
+            }
+            AppMsg::HlcCompressionChanged(compression) => {
+                self.pipeline_worker.set_hlc_compression(*compression);
+                let _ = self.tx.send(AppMsg::LogMessage(format!(
+                    ".hlc compression set to {}",
+                    compression.name()
+                )));
+            }
+            AppMsg::HyprResizeAlgorithmChanged(algorithm) => {
+                self.pipeline_worker
+                    .set_hyprcursor_resize_algorithm(*algorithm);
+                let _ = self.tx.send(AppMsg::LogMessage(format!(
+                    "Hyprcursor resize algorithm set to {}",
+                    algorithm.name()
+                )));
+            }
+            AppMsg::HyprManifestFormatChanged(format) => {
+                self.pipeline_worker.set_hyprcursor_manifest_format(*format);
+                let _ = self.tx.send(AppMsg::LogMessage(format!(
+                    "Hyprcursor manifests will be written as {}",
+                    format.name()
+                )));
+            }
+            AppMsg::OutputLayoutChanged(layout) => {
+                self.pipeline_worker.set_output_layout(*layout);
+                let _ = self.tx.send(AppMsg::LogMessage(format!(
+                    "Output layout set to {}",
+                    layout.name()
+                )));
+            }
+            AppMsg::ShadowChanged(shadow) => {
+                self.pipeline_worker.set_shadow(shadow.clone());
+                self.cursor_editor.preview.set_shadow(shadow.clone());
+                let _ = self.tx.send(AppMsg::LogMessage(match shadow {
+                    Some(s) => format!(
+                        "Shadow: {}% opacity, offset ({:.2}, {:.2}), blur {:.2}",
+                        s.opacity as u32 * 100 / 255,
+                        s.x_offset,
+                        s.y_offset,
+                        s.sigma
+                    ),
+                    None => "Shadow disabled".to_string(),
+                }));
+            }
+            AppMsg::ConfirmOverwrite(path) => {
+                self.confirm.show(path.clone());
+            }
+            AppMsg::BuildFinished(summaries) => {
+                self.results.show(summaries.clone());
+            }
+            AppMsg::ThemeActionRequested(action, theme_dir) => {
+                self.run_theme_action(*action, theme_dir.clone());
+            }
             AppMsg::ErrorOccurred(err) => {
                 eprintln!("Error: {}", err);
             }
@@ -374,22 +620,24 @@ impl App {
     fn handle_dir_selection(&mut self, msg: &AppMsg) {
         match msg {
             AppMsg::InputDirSelected(path) => {
-                self.runner.set_input_dir(path.clone());
-                // Scan directory for available sources (.ani/.cur files)
-                let mut sources = Vec::new();
-                if let Ok(entries) = std::fs::read_dir(path) {
-                    for entry in entries.flatten() {
-                        let path = entry.path();
-                        if let Some(ext) = path.extension() {
-                            let ext_str = ext.to_string_lossy().to_lowercase();
-                            if (ext_str == "ani" || ext_str == "cur")
-                                && let Some(stem) = path.file_stem()
-                            {
-                                sources.push(stem.to_string_lossy().to_string());
-                            }
+                let path = if is_cursor_archive(path) {
+                    match self.open_input_archive(path) {
+                        Ok(dir) => dir,
+                        Err(e) => {
+                            let _ = self.tx.send(AppMsg::ErrorOccurred(format!(
+                                "Failed to open {}: {}",
+                                path.display(),
+                                e
+                            )));
+                            return;
                         }
                     }
-                }
+                } else {
+                    self.input_archive = None;
+                    path.clone()
+                };
+                self.runner.set_input_dir(path.clone());
+                let sources = scan_sources(&path);
                 self.mapping_editor.set_available_sources(sources, &self.tx);
             }
             AppMsg::OutputDirSelected(path) => {
@@ -399,6 +647,26 @@ impl App {
         }
     }
 
+    // Extracts the archive's cursors to a fresh temp directory named after it, so theme
+    // names still come from the archive, and returns that directory
+    fn open_input_archive(&mut self, archive: &Path) -> Result<PathBuf> {
+        let temp = tempfile::tempdir()?;
+        let name = archive
+            .file_stem()
+            .map(|stem| stem.to_os_string())
+            .unwrap_or_else(|| "archive".into());
+        let dir = temp.path().join(name);
+        let count = extract_cursor_archive(archive, &dir)?;
+        let _ = self.tx.send(AppMsg::LogMessage(format!(
+            "Extracted {} cursor files from {}",
+            count,
+            archive.display()
+        )));
+
+        self.input_archive = Some((archive.to_path_buf(), temp));
+        Ok(dir)
+    }
+
     fn handle_pipeline_msg(&mut self, msg: &AppMsg) {
         match msg {
             AppMsg::PipelineStarted => {
@@ -408,19 +676,33 @@ impl App {
                 ) {
                     let theme_name = self.get_theme_name(&input_dir);
                     let mapping = self.mapping_editor.mapping.clone();
-                    let selected_sizes: Vec<u32> = self
-                        .theme_overrides
-                        .selected_sizes
-                        .iter()
-                        .cloned()
-                        .collect();
+                    let selected_sizes = self.selected_sizes();
+
+                    let variants = self.build_variants();
 
+                    self.pipeline_worker.set_install_to_user_icons(
+                        !self.theme_overrides.is_enabled(BuildOption::SkipInstall),
+                    );
+                    self.pipeline_worker.set_keep_intermediate(
+                        self.theme_overrides
+                            .is_enabled(BuildOption::KeepIntermediate),
+                    );
+                    self.pipeline_worker.set_link_duplicates(
+                        self.theme_overrides.is_enabled(BuildOption::LinkDuplicates),
+                    );
+                    self.pipeline_worker
+                        .set_inherits(self.theme_overrides.inherits.trim().to_string());
+                    self.pipeline_worker
+                        .set_description(self.theme_overrides.description.trim().to_string());
+                    self.pipeline_worker
+                        .set_excluded_cursors(self.cursor_editor.excluded.clone());
                     self.pipeline_worker.start_full_theme_conversion(
                         input_dir.clone(),
                         output_dir.clone(),
                         theme_name,
                         mapping,
                         selected_sizes,
+                        variants,
                     );
                 }
             }
@@ -429,8 +711,12 @@ impl App {
                     self.runner.input_dir.clone(),
                     self.runner.output_dir.clone(),
                 ) {
-                    self.pipeline_worker
-                        .start_ani_to_xcur_conversion(input_dir, output_dir);
+                    let selected_sizes = self.selected_sizes();
+                    self.pipeline_worker.start_ani_to_xcur_conversion(
+                        input_dir,
+                        output_dir,
+                        selected_sizes,
+                    );
                 }
             }
             AppMsg::ConvertPNGOnly => {
@@ -438,13 +724,68 @@ impl App {
                     self.runner.input_dir.clone(),
                     self.runner.output_dir.clone(),
                 ) {
+                    self.pipeline_worker.set_keep_intermediate(
+                        self.theme_overrides
+                            .is_enabled(BuildOption::KeepIntermediate),
+                    );
+                    let selected_sizes = self.selected_sizes();
+                    // Lands where full builds of the current layout put png_intermediate
+                    let theme_name = self.get_theme_name(&input_dir);
+                    let png_root = self
+                        .pipeline_worker
+                        .output_layout()
+                        .intermediate_root(&output_dir, &theme_name);
+                    self.pipeline_worker.start_ani_to_png_conversion(
+                        input_dir,
+                        png_root,
+                        selected_sizes,
+                    );
+                }
+            }
+            AppMsg::RefreshSymlinks => {
+                if let (Some(input_dir), Some(output_dir)) = (
+                    self.runner.input_dir.clone(),
+                    self.runner.output_dir.clone(),
+                ) {
+                    let theme_name = self.get_theme_name(&input_dir);
+                    let mapping = self.mapping_editor.mapping.clone();
                     self.pipeline_worker
-                        .start_ani_to_png_conversion(input_dir, output_dir);
+                        .start_symlink_refresh(output_dir, theme_name, mapping);
+                }
+            }
+            AppMsg::GenerateGallery => {
+                if let (Some(input_dir), Some(output_dir)) = (
+                    self.runner.input_dir.clone(),
+                    self.runner.output_dir.clone(),
+                ) {
+                    let theme_name = self.get_theme_name(&input_dir);
+                    self.pipeline_worker.start_gallery(output_dir, theme_name);
+                }
+            }
+            AppMsg::PackageTarGz => {
+                if let (Some(input_dir), Some(output_dir)) = (
+                    self.runner.input_dir.clone(),
+                    self.runner.output_dir.clone(),
+                ) {
+                    let theme_name = self.get_theme_name(&input_dir);
+                    self.pipeline_worker.start_tar_gz(output_dir, theme_name);
                 }
             }
+            AppMsg::CancelPipeline => {
+                self.pipeline_worker.cancel();
+                let _ = self
+                    .tx
+                    .send(AppMsg::LogMessage("Cancelling pipeline...".to_string()));
+            }
             AppMsg::PipelineCompleted(_count) => {
-                if let Some(output_dir) = &self.runner.output_dir {
-                    let png_dir = output_dir.join("png_intermediate");
+                if let (Some(input_dir), Some(output_dir)) =
+                    (&self.runner.input_dir, &self.runner.output_dir)
+                {
+                    let theme_name = self.get_theme_name(input_dir);
+                    let png_dir = self
+                        .pipeline_worker
+                        .output_layout()
+                        .png_dir(output_dir, &theme_name);
                     if png_dir.exists() {
                         let _ = self.tx.send(AppMsg::CursorSelected(png_dir));
                     }
@@ -468,61 +809,80 @@ impl App {
                 }
                 let _ = self.tx.send(AppMsg::MappingSaved);
             }
-            AppMsg::MappingSaved => {
-                let _ = self.tx.send(AppMsg::LogMessage(
-                    "Saving changes. Triggering incremental update...".to_string(),
-                ));
+            AppMsg::MappingSaved => self.start_incremental_update(),
+            _ => {}
+        }
+    }
 
-                if let (Some(input_dir), Some(output_dir)) = (
-                    self.runner.input_dir.clone(),
-                    self.runner.output_dir.clone(),
-                ) {
-                    let theme_name = self.get_theme_name(&input_dir);
-                    let mapping = self.mapping_editor.mapping.clone();
+    // Ctrl+S: unsaved hotspots and mapping changes go out in one incremental update, instead
+    // of one per panel
+    fn save_all(&mut self) {
+        let hotspots: Vec<String> = self.cursor_editor.modified_hotspots.drain().collect();
+        let mapped = self.modified_cursors.len();
+        self.modified_cursors.extend(hotspots.iter().cloned());
+        let _ = self.tx.send(AppMsg::LogMessage(format!(
+            "Saving {} hotspot and {} mapping changes together",
+            hotspots.len(),
+            mapped
+        )));
+        self.start_incremental_update();
+    }
 
-                    if self.modified_cursors.is_empty() {
-                        let _ = self.tx.send(AppMsg::LogMessage(
-                            "No changes detected since last save.".to_string(),
-                        ));
-                    } else {
-                        let modified: Vec<String> = self.modified_cursors.drain().collect();
-                        let _ = self.tx.send(AppMsg::LogMessage(format!(
-                            "Updating {} modified cursors...",
-                            modified.len()
-                        )));
+    // Rebuilds the cursors in `modified_cursors` with the current mapping and their hotspots
+    fn start_incremental_update(&mut self) {
+        let _ = self.tx.send(AppMsg::LogMessage(
+            "Saving changes. Triggering incremental update...".to_string(),
+        ));
 
-                        let mut hotspot_overrides = HashMap::new();
-                        for cursor_name in &modified {
-                            if let Some(cursor) = self
-                                .cursor_editor
-                                .cursors
-                                .iter()
-                                .find(|c| c.x11_name == *cursor_name)
-                            {
-                                let mut variants_map = HashMap::new();
-                                for variant in &cursor.variants {
-                                    variants_map.insert(variant.size, variant.hotspot);
-                                }
-                                hotspot_overrides.insert(cursor_name.clone(), variants_map);
-                            }
-                        }
+        if let (Some(input_dir), Some(output_dir)) = (
+            self.runner.input_dir.clone(),
+            self.runner.output_dir.clone(),
+        ) {
+            let theme_name = self.get_theme_name(&input_dir);
+            let mapping = self.mapping_editor.mapping.clone();
+            self.pipeline_worker
+                .set_excluded_cursors(self.cursor_editor.excluded.clone());
 
-                        self.pipeline_worker.start_incremental_theme_update(
-                            input_dir,
-                            output_dir,
-                            theme_name,
-                            mapping,
-                            modified,
-                            hotspot_overrides,
-                        );
+            if self.modified_cursors.is_empty() {
+                let _ = self.tx.send(AppMsg::LogMessage(
+                    "No changes detected since last save.".to_string(),
+                ));
+            } else {
+                let modified: Vec<String> = self.modified_cursors.drain().collect();
+                let _ = self.tx.send(AppMsg::LogMessage(format!(
+                    "Updating {} modified cursors...",
+                    modified.len()
+                )));
+
+                let mut hotspot_overrides = HashMap::new();
+                for cursor_name in &modified {
+                    if let Some(cursor) = self
+                        .cursor_editor
+                        .cursors
+                        .iter()
+                        .find(|c| c.x11_name == *cursor_name)
+                    {
+                        let mut variants_map = HashMap::new();
+                        for variant in &cursor.variants {
+                            variants_map.insert(variant.size, variant.hotspot);
+                        }
+                        hotspot_overrides.insert(cursor_name.clone(), variants_map);
                     }
-                } else {
-                    let _ = self.tx.send(AppMsg::LogMessage(
-                        "Cannot update theme: Input or Output directory not set.".to_string(),
-                    ));
                 }
+
+                self.pipeline_worker.start_incremental_theme_update(
+                    input_dir,
+                    output_dir,
+                    theme_name,
+                    mapping,
+                    modified,
+                    hotspot_overrides,
+                );
             }
-            _ => {}
+        } else {
+            let _ = self.tx.send(AppMsg::LogMessage(
+                "Cannot update theme: Input or Output directory not set.".to_string(),
+            ));
         }
     }
 
@@ -533,14 +893,41 @@ impl App {
                     "Loading cursors from: {}",
                     path.display()
                 )));
+                self.last_cursor_dir = Some(path.clone());
 
-                let cursors = load_cursor_folder_from_pngs(path).or_else(|e| {
-                    let _ = self.tx.send(AppMsg::LogMessage(format!(
-                        "PNG load failed: {}, trying binary...",
-                        e
-                    )));
-                    load_cursor_folder(path)
-                });
+                // An empty result falls through to the next loader as well
+                let non_empty = |cursors: Vec<_>| {
+                    if cursors.is_empty() {
+                        anyhow::bail!("no cursors found");
+                    }
+                    Ok(cursors)
+                };
+                let tx = self.tx.clone();
+                let log = |level, msg| {
+                    let _ = tx.send(AppMsg::LogMessageLevel(level, msg));
+                };
+                let cursors: Result<_> = load_cursor_folder_from_pngs(path, log)
+                    .and_then(non_empty)
+                    .or_else(|e| {
+                        let _ = self.tx.send(AppMsg::LogMessage(format!(
+                            "PNG load failed: {}, trying binary...",
+                            e
+                        )));
+                        load_cursor_folder(path, self.max_cursor_dimension, log).and_then(non_empty)
+                    })
+                    .or_else(|e| {
+                        let _ = self.tx.send(AppMsg::LogMessage(format!(
+                            "Binary load failed: {}, trying Hyprcursor...",
+                            e
+                        )));
+                        let unpacked = tempfile::Builder::new()
+                            .prefix("ani2hyprtui-hlc-")
+                            .tempdir()?;
+                        let cursors = load_cursor_folder_from_hlc(path, unpacked.path(), log)?;
+                        // Replaces, and so removes, the images of the theme opened before
+                        self.hlc_unpack_dir = Some(unpacked);
+                        Ok(cursors)
+                    });
 
                 match cursors {
                     Ok(cursors) => {
@@ -557,12 +944,14 @@ impl App {
                                     .into_iter()
                                     .map(|v| cursor::SizeVariant {
                                         size: v.size,
+                                        pixel_size: v.pixel_size,
                                         frames: v
                                             .frames
                                             .into_iter()
                                             .map(|f| cursor::Frame {
                                                 png_path: f.png_path,
                                                 delay_ms: f.delay_ms,
+                                                hotspot: f.hotspot,
                                             })
                                             .collect(),
                                         hotspot: v.hotspot,
@@ -573,6 +962,7 @@ impl App {
                                 cursor::CursorMeta {
                                     x11_name: c.x11_name,
                                     variants,
+                                    ani_sequence: c.ani_sequence,
                                 }
                             })
                             .collect();
@@ -606,6 +996,42 @@ impl App {
         }
     }
 
+    // Reads the frame order and rates straight from the cursor's .ani source in the input
+    // directory, for cursors the editor loaded from converted output
+    fn load_ani_timing(&self, x11_name: &str) {
+        let source = self.runner.input_dir.as_ref().and_then(|dir| {
+            let win_name = self.mapping_editor.mapping.get_win_name(x11_name)?;
+            Some(dir.join(format!("{}.ani", win_name)))
+        });
+        let Some(source) = source.filter(|path| path.is_file()) else {
+            let _ = self.tx.send(AppMsg::LogMessageLevel(
+                LogLevel::Warn,
+                format!("{} has no .ani source in the input directory", x11_name),
+            ));
+            return;
+        };
+
+        let parsed = std::fs::read(&source)
+            .map_err(anyhow::Error::from)
+            .and_then(|data| {
+                AniParser::parse_detailed(&data, self.max_cursor_dimension, |_, _| {})
+            });
+        match parsed {
+            Ok((_, sequence)) => {
+                let _ = self
+                    .tx
+                    .send(AppMsg::AniTimingLoaded(x11_name.to_string(), sequence));
+            }
+            Err(e) => {
+                let _ = self.tx.send(AppMsg::ErrorOccurred(format!(
+                    "Failed to read timing from {}: {}",
+                    source.display(),
+                    e
+                )));
+            }
+        }
+    }
+
     fn update_components(&mut self, msg: &AppMsg) {
         match msg {
             AppMsg::Key(_) => {}
@@ -621,6 +1047,109 @@ impl App {
         }
     }
 
+    // Snapshot of the session state that is worth restoring on the next launch
+    fn current_config(&self) -> Config {
+        let defaults = Config::default();
+        let mut selected_sizes: Vec<u32> = self
+            .theme_overrides
+            .selected_sizes
+            .iter()
+            .copied()
+            .collect();
+        selected_sizes.sort_unstable();
+
+        Config {
+            // The extracted copy is gone next session, the archive is not
+            input_dir: match &self.input_archive {
+                Some((archive, _)) => archive.clone(),
+                None => self.runner.input_dir.clone().unwrap_or(defaults.input_dir),
+            },
+            output_dir: self
+                .runner
+                .output_dir
+                .clone()
+                .unwrap_or(defaults.output_dir),
+            thread_count: self.settings.thread_count,
+            // A --theme override lasts for this session only
+            theme: self.settings.chosen_theme.unwrap_or(self.saved_theme),
+            selected_sizes,
+            essential_cursors: self.pipeline_worker.essential_cursors().to_vec(),
+            log_verbosity: self.settings.log_verbosity,
+            resize_filter: self.settings.resize_filter,
+            hlc_compression: self.settings.hlc_compression,
+            hyprcursor_resize_algorithm: self.settings.hypr_resize,
+            hyprcursor_resize_overrides: self.pipeline_worker.hyprcursor_resize_overrides().clone(),
+            hyprcursor_manifest_format: self.settings.manifest_format,
+            output_layout: self.settings.output_layout,
+            theme_inherits: self.theme_overrides.inherits.clone(),
+            theme_description: self.theme_overrides.description.clone(),
+            shadow_enabled: self.settings.shadow_enabled,
+            shadow: self.settings.shadow.clone(),
+            max_cursor_dimension: self.max_cursor_dimension,
+            preview_cache_capacity: self.cursor_editor.preview.cache_capacity(),
+            preview_grid_min_scale: self.cursor_editor.preview.grid_min_scale(),
+            auto_match_min_score: self.mapping_editor.min_match_score(),
+            bookmarks: self.file_browser.bookmarks.clone(),
+            wrap_navigation: self.wrap_navigation,
+            ..Config::default()
+        }
+    }
+
+    // Starts a full build, first asking before it replaces a directory that already has files
+    fn request_full_build(&mut self) {
+        if let (Some(input_dir), Some(output_dir)) =
+            (&self.runner.input_dir, &self.runner.output_dir)
+        {
+            let theme_name = self.get_theme_name(input_dir);
+            let at_risk = written_dirs(
+                output_dir,
+                &theme_name,
+                self.pipeline_worker.output_layout(),
+                &self.build_variants(),
+                !self.theme_overrides.is_enabled(BuildOption::SkipInstall),
+            );
+
+            let populated = at_risk.into_iter().find(|dir| {
+                !self.confirmed_overwrites.contains(dir)
+                    && fs::read_dir(dir)
+                        .map(|mut entries| entries.next().is_some())
+                        .unwrap_or(false)
+            });
+            if let Some(dir) = populated {
+                let _ = self.tx.send(AppMsg::ConfirmOverwrite(dir));
+                return;
+            }
+        }
+        let _ = self.tx.send(AppMsg::PipelineStarted);
+    }
+
+    // Themes a full build makes: the base one plus the variants ticked in the overrides panel
+    fn build_variants(&self) -> Vec<ThemeVariant> {
+        let mut variants = vec![ThemeVariant::Base];
+        if self.theme_overrides.is_enabled(BuildOption::DarkVariant) {
+            variants.push(ThemeVariant::Dark);
+        }
+        if self
+            .theme_overrides
+            .is_enabled(BuildOption::StraightAlphaVariant)
+        {
+            variants.push(ThemeVariant::StraightAlpha);
+        }
+        variants
+    }
+
+    // Sizes ticked in the overrides panel, ascending
+    fn selected_sizes(&self) -> Vec<u32> {
+        let mut sizes: Vec<u32> = self
+            .theme_overrides
+            .selected_sizes
+            .iter()
+            .cloned()
+            .collect();
+        sizes.sort_unstable();
+        sizes
+    }
+
     fn get_theme_name(&self, input_dir: &Path) -> String {
         if !self.theme_overrides.output_name.trim().is_empty() {
             self.theme_overrides.output_name.trim().to_string()
@@ -633,54 +1162,177 @@ impl App {
         }
     }
 
+    // Post-build actions can shell out or copy whole themes, so keep them off the UI thread
+    fn run_theme_action(&self, action: ThemeAction, theme_dir: PathBuf) {
+        let sizes = &self.theme_overrides.selected_sizes;
+        let cursor_size = if sizes.contains(&24) || sizes.is_empty() {
+            24
+        } else {
+            sizes.iter().copied().min().unwrap_or(24)
+        };
+
+        let tx = self.tx.clone();
+        thread::spawn(move || {
+            let msg = match action.run(&theme_dir, cursor_size) {
+                Ok(msg) => AppMsg::LogMessage(msg),
+                Err(e) => AppMsg::LogMessageLevel(
+                    LogLevel::Error,
+                    format!("{} failed: {}", action.label(), e),
+                ),
+            };
+            let _ = tx.send(msg);
+        });
+    }
+
+    // Follows `step` from the focused panel; at an edge, optionally wraps by walking `opposite`
+    fn move_focus(
+        &mut self,
+        step: fn(&Focus) -> Option<Focus>,
+        opposite: fn(&Focus) -> Option<Focus>,
+    ) {
+        let target = step(&self.focus)
+            .or_else(|| self.wrap_navigation.then(|| self.focus.furthest(opposite)));
+        if let Some(focus) = target.filter(|&focus| focus != self.focus) {
+            self.set_focus(focus);
+        }
+    }
+
+    // Moving to another panel restores the layout, the maximized panel would hide it
+    fn set_focus(&mut self, focus: Focus) {
+        self.focus = focus;
+        // Each panel has its own hints, so start them from the first page
+        self.status_page = 0;
+        self.set_maximized(None);
+    }
+
+    fn set_maximized(&mut self, focus: Option<Focus>) {
+        self.maximized_focus = focus;
+        // The editor drops its cursor list for a larger preview when it has the screen
+        self.cursor_editor.maximized = focus == Some(Focus::Editor);
+    }
+
+    // Clicking or dragging over the editor preview moves the hotspot
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if self.focus != Focus::Editor || self.results.visible || self.confirm.visible() {
+            return;
+        }
+        let log = match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => true,
+            MouseEventKind::Drag(MouseButton::Left) => false,
+            _ => return,
+        };
+        if let Some(msg) = self
+            .cursor_editor
+            .click_hotspot(mouse.column, mouse.row, log)
+        {
+            let _ = self.tx.send(msg);
+        }
+    }
+
     fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if self.confirm.visible() {
+            let path = self.confirm.path.clone();
+            if let Some(msg) = self.confirm.update(&AppMsg::Key(key)) {
+                if matches!(msg, AppMsg::PipelineStarted) {
+                    self.confirmed_overwrites.extend(path);
+                    // Other populated directories still get their own prompt
+                    self.request_full_build();
+                } else {
+                    let _ = self.tx.send(msg);
+                }
+            }
+            return false;
+        }
+
+        if self.results.visible {
+            if let Some(msg) = self.results.update(&AppMsg::Key(key)) {
+                let _ = self.tx.send(msg);
+            }
+            return false;
+        }
+
         match (key.code, key.modifiers) {
             (KeyCode::Char('q'), _) | (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
-                if self.focus == Focus::Mapping && self.mapping_editor.show_popup {
+                if self.focus == Focus::Mapping && self.mapping_editor.has_popup() {
                     if let Some(msg) = self.mapping_editor.update(&AppMsg::Key(key)) {
                         let _ = self.tx.send(msg);
                     }
                     return false;
                 }
+                if self.focus == Focus::FileBrowser && self.file_browser.filtering {
+                    self.file_browser.update(&AppMsg::Key(key));
+                    return false;
+                }
+                if self.focus == Focus::Editor && self.cursor_editor.hotspot_input.is_some() {
+                    if let Some(msg) = self.cursor_editor.update(&AppMsg::Key(key)) {
+                        let _ = self.tx.send(msg);
+                    }
+                    return false;
+                }
+                if self.save_config_on_quit {
+                    let _ = self.current_config().save();
+                }
                 return true;
             }
             // Window Navigation (Ctrl+hjkl or Ctrl+Arrows)
             (KeyCode::Left, KeyModifiers::CONTROL)
             | (KeyCode::Char('h'), KeyModifiers::CONTROL) => {
-                if let Some(focus) = self.focus.left() {
-                    self.focus = focus;
-                }
+                self.move_focus(Focus::left, Focus::right);
             }
             (KeyCode::Right, KeyModifiers::CONTROL)
             | (KeyCode::Char('l'), KeyModifiers::CONTROL) => {
-                if let Some(focus) = self.focus.right() {
-                    self.focus = focus;
-                }
+                self.move_focus(Focus::right, Focus::left);
             }
             (KeyCode::Up, KeyModifiers::CONTROL) | (KeyCode::Char('k'), KeyModifiers::CONTROL) => {
-                if let Some(focus) = self.focus.up() {
-                    self.focus = focus;
-                }
+                self.move_focus(Focus::up, Focus::down);
             }
             (KeyCode::Down, KeyModifiers::CONTROL)
             | (KeyCode::Char('j'), KeyModifiers::CONTROL) => {
-                if let Some(focus) = self.focus.down() {
-                    self.focus = focus;
-                }
+                self.move_focus(Focus::down, Focus::up);
             }
+            (KeyCode::F(1), _) => {
+                self.status_page = self.status_page.wrapping_add(1);
+            }
+            (KeyCode::F(5), _) => match &self.last_cursor_dir {
+                Some(dir) => {
+                    self.cursor_editor.preview.clear_cache();
+                    let _ = self.tx.send(AppMsg::CursorSelected(dir.clone()));
+                }
+                None => {
+                    let _ = self.tx.send(AppMsg::LogMessage(
+                        "No cursor folder loaded yet, nothing to reload".to_string(),
+                    ));
+                }
+            },
             (KeyCode::Tab, _) => {
-                self.focus = self.focus.next();
+                self.set_focus(self.focus.next());
             }
             (KeyCode::BackTab, _) => {
-                self.focus = self.focus.prev();
+                self.set_focus(self.focus.prev());
+            }
+            (KeyCode::Char('s'), KeyModifiers::CONTROL) => self.save_all(),
+            (KeyCode::Char(' '), KeyModifiers::CONTROL) => {
+                let maximized = match self.maximized_focus {
+                    Some(_) => None,
+                    None => Some(self.focus),
+                };
+                self.set_maximized(maximized);
             }
             _ => {
                 let msg = AppMsg::Key(key);
                 match self.focus {
+                    Focus::FileBrowser if self.file_browser.filtering => {
+                        self.file_browser.update(&msg);
+                    }
                     Focus::FileBrowser => match key.code {
                         KeyCode::Char('i') => {
-                            let current_dir = self.file_browser.current_dir.clone();
-                            let _ = self.tx.send(AppMsg::InputDirSelected(current_dir));
+                            // A highlighted zip is used in place of the directory
+                            let input = self
+                                .file_browser
+                                .selected_path()
+                                .filter(|path| is_cursor_archive(path))
+                                .unwrap_or_else(|| self.file_browser.current_dir.clone());
+                            let _ = self.tx.send(AppMsg::InputDirSelected(input));
                         }
                         KeyCode::Char('o') => {
                             let current_dir = self.file_browser.current_dir.clone();
@@ -691,15 +1343,25 @@ impl App {
                         }
                     },
                     Focus::Runner => match key.code {
-                        KeyCode::Char('c') => {
-                            let _ = self.tx.send(AppMsg::PipelineStarted);
-                        }
+                        KeyCode::Char('c') => self.request_full_build(),
                         KeyCode::Char('x') => {
                             let _ = self.tx.send(AppMsg::ConvertXCursorOnly);
                         }
                         KeyCode::Char('p') => {
                             let _ = self.tx.send(AppMsg::ConvertPNGOnly);
                         }
+                        KeyCode::Char('l') => {
+                            let _ = self.tx.send(AppMsg::RefreshSymlinks);
+                        }
+                        KeyCode::Char('h') => {
+                            let _ = self.tx.send(AppMsg::GenerateGallery);
+                        }
+                        KeyCode::Char('t') => {
+                            let _ = self.tx.send(AppMsg::PackageTarGz);
+                        }
+                        KeyCode::Esc => {
+                            let _ = self.tx.send(AppMsg::CancelPipeline);
+                        }
                         _ => {
                             self.runner.update(&msg);
                         }
@@ -732,10 +1394,256 @@ impl App {
     }
 }
 
+// Splits " | "-separated hints into lines that fit `width`, leaving room for the page marker
+fn paginate_hints(text: &str, width: usize) -> Vec<String> {
+    const SEPARATOR: &str = " | ";
+    const MARKER_WIDTH: usize = " (10/10) F1: More".len();
+
+    if text.chars().count() <= width {
+        return vec![text.to_string()];
+    }
+
+    let budget = width.saturating_sub(MARKER_WIDTH).max(1);
+    let mut pages: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for hint in text.split(SEPARATOR) {
+        let needed = current.chars().count() + SEPARATOR.len() + hint.chars().count();
+        if !current.is_empty() && needed > budget {
+            pages.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push_str(SEPARATOR);
+        }
+        current.push_str(hint);
+    }
+    if !current.is_empty() {
+        pages.push(current);
+    }
+    pages
+}
+
+// Stems of the .ani and .cur files directly inside `dir`, the sources a mapping can use
+fn scan_sources(dir: &Path) -> Vec<String> {
+    let mut sources = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if let Some(ext) = path.extension() {
+                let ext_str = ext.to_string_lossy().to_lowercase();
+                if (ext_str == "ani" || ext_str == "cur")
+                    && let Some(stem) = path.file_stem()
+                {
+                    sources.push(stem.to_string_lossy().to_string());
+                }
+            }
+        }
+    }
+    sources
+}
+
 fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyhow::Result<()> {
     terminal.show_cursor().ok();
     disable_raw_mode().ok();
     let mut out = io::stdout();
-    execute!(out, LeaveAlternateScreen)?;
+    execute!(out, LeaveAlternateScreen, DisableMouseCapture)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui_image::picker::Picker;
+    use tempfile::tempdir;
+
+    fn test_app() -> App {
+        App::from_config(Picker::halfblocks(), Config::default(), None)
+    }
+
+    #[test]
+    fn test_removed_mapping_is_logged_and_marked_modified() {
+        let mut app = test_app();
+        app.handle_message(AppMsg::MappingChanged("text".to_string(), None));
+
+        assert!(app.modified_cursors.contains("text"));
+        let logged = app.rx.try_iter().any(
+            |msg| matches!(msg, AppMsg::LogMessage(text) if text == "Removed text from the mapping"),
+        );
+        assert!(logged);
+    }
+
+    #[test]
+    fn test_paginate_hints_fits_on_one_page() {
+        assert_eq!(paginate_hints("a | b", 80), vec!["a | b"]);
+    }
+
+    #[test]
+    fn test_paginate_hints_splits_on_separators() {
+        let text = "first hint | second hint | third hint | fourth hint";
+        let pages = paginate_hints(text, 40);
+
+        assert!(pages.len() > 1);
+        assert_eq!(pages.join(" | "), text);
+        let budget = 40 - " (10/10) F1: More".len();
+        assert!(pages.iter().all(|page| page.chars().count() <= budget));
+    }
+
+    #[test]
+    fn test_paginate_hints_keeps_a_long_hint_whole() {
+        let pages = paginate_hints("a very long hint that does not fit | b", 20);
+        assert_eq!(pages, vec!["a very long hint that does not fit", "b"]);
+    }
+
+    #[test]
+    fn test_focus_change_resets_the_hint_page() {
+        let mut app = test_app();
+        app.handle_key(KeyEvent::new(KeyCode::F(1), KeyModifiers::NONE));
+        assert_eq!(app.status_page, 1);
+
+        app.handle_key(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::CONTROL));
+        assert_eq!(app.status_page, 0);
+    }
+
+    fn ctrl(app: &mut App, code: KeyCode) {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::CONTROL));
+    }
+
+    #[test]
+    fn test_furthest_walks_to_the_edge() {
+        assert_eq!(Focus::FileBrowser.furthest(Focus::down), Focus::Overrides);
+        assert_eq!(Focus::Settings.furthest(Focus::left), Focus::Overrides);
+        assert_eq!(Focus::Mapping.furthest(Focus::right), Focus::Mapping);
+    }
+
+    #[test]
+    fn test_ctrl_space_maximizes_and_restores() {
+        let mut app = test_app();
+        app.set_focus(Focus::Editor);
+
+        ctrl(&mut app, KeyCode::Char(' '));
+        assert_eq!(app.maximized_focus, Some(Focus::Editor));
+        assert!(app.cursor_editor.maximized);
+
+        ctrl(&mut app, KeyCode::Char(' '));
+        assert_eq!(app.maximized_focus, None);
+        assert!(!app.cursor_editor.maximized);
+    }
+
+    #[test]
+    fn test_moving_focus_restores_the_layout() {
+        let mut app = test_app();
+        ctrl(&mut app, KeyCode::Char(' '));
+        assert_eq!(app.maximized_focus, Some(Focus::FileBrowser));
+
+        // No panel to the left, so nothing moves and the panel stays maximized
+        ctrl(&mut app, KeyCode::Char('h'));
+        assert_eq!(app.focus, Focus::FileBrowser);
+        assert_eq!(app.maximized_focus, Some(Focus::FileBrowser));
+
+        ctrl(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.focus, Focus::Runner);
+        assert_eq!(app.maximized_focus, None);
+    }
+
+    #[test]
+    fn test_navigation_stops_at_edges_without_wrapping() {
+        let mut app = test_app();
+        ctrl(&mut app, KeyCode::Char('h'));
+        ctrl(&mut app, KeyCode::Char('k'));
+        assert_eq!(app.focus, Focus::FileBrowser);
+    }
+
+    #[test]
+    fn test_navigation_wraps_to_the_opposite_edge() {
+        let mut app = test_app();
+        app.wrap_navigation = true;
+
+        ctrl(&mut app, KeyCode::Char('h'));
+        assert_eq!(app.focus, Focus::Mapping);
+        ctrl(&mut app, KeyCode::Char('l'));
+        assert_eq!(app.focus, Focus::FileBrowser);
+
+        ctrl(&mut app, KeyCode::Char('k'));
+        assert_eq!(app.focus, Focus::Overrides);
+        ctrl(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.focus, Focus::FileBrowser);
+    }
+
+    #[test]
+    fn test_resize_filter_is_restored_and_saved() {
+        let config = Config {
+            resize_filter: image::imageops::FilterType::Nearest,
+            ..Config::default()
+        };
+        let app = App::from_config(Picker::halfblocks(), config, None);
+        assert_eq!(
+            app.settings.resize_filter,
+            image::imageops::FilterType::Nearest
+        );
+        assert_eq!(
+            app.current_config().resize_filter,
+            image::imageops::FilterType::Nearest
+        );
+    }
+
+    #[test]
+    fn test_theme_override_is_not_saved() {
+        let config = Config {
+            theme: ThemeType::Dracula,
+            ..Config::default()
+        };
+        let mut app = App::from_config(Picker::halfblocks(), config, Some(ThemeType::Nord));
+        assert_eq!(app.current_config().theme, ThemeType::Dracula);
+
+        app.settings.selected_index = app
+            .settings
+            .themes
+            .iter()
+            .position(|t| *t == ThemeType::OneDark)
+            .unwrap();
+        app.settings.apply_theme();
+        assert_eq!(app.current_config().theme, ThemeType::OneDark);
+    }
+
+    // Runs Ctrl+S with one mapping and one hotspot change and returns the worker's
+    // "Starting incremental update" lines
+    fn save_all_update_starts(excluded: &[&str]) -> Vec<String> {
+        let dir = tempdir().unwrap();
+        let mut app = test_app();
+        app.runner.input_dir = Some(dir.path().join("src"));
+        app.runner.output_dir = Some(dir.path().join("out"));
+        app.modified_cursors.insert("text".to_string());
+        app.cursor_editor
+            .modified_hotspots
+            .insert("left_ptr".to_string());
+        app.cursor_editor.excluded = excluded.iter().map(|s| s.to_string()).collect();
+
+        app.save_all();
+        assert!(app.modified_cursors.is_empty());
+        assert!(app.cursor_editor.modified_hotspots.is_empty());
+
+        let mut starts = Vec::new();
+        while let Ok(msg) = app.rx.recv_timeout(Duration::from_secs(5)) {
+            match msg {
+                AppMsg::LogMessage(text) if text.starts_with("Starting incremental update") => {
+                    starts.push(text)
+                }
+                AppMsg::LogMessage(text) if text == "Incremental update completed." => break,
+                AppMsg::PipelineFailed(e) => panic!("{e}"),
+                _ => {}
+            }
+        }
+        starts
+    }
+
+    #[test]
+    fn test_save_all_starts_one_update_for_hotspots_and_mappings() {
+        assert_eq!(
+            save_all_update_starts(&[]),
+            vec!["Starting incremental update for 2 cursors..."]
+        );
+        assert_eq!(
+            save_all_update_starts(&["text"]),
+            vec!["Starting incremental update for 1 cursors..."]
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use super::Component;
+use crate::event::AppMsg;
+use crate::widgets::common::centered_rect;
+use crate::widgets::theme::get_theme;
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+use std::path::PathBuf;
+
+// Yes/no popup shown before a build replaces a populated output directory
+#[derive(Default)]
+pub struct ConfirmState {
+    pub path: Option<PathBuf>,
+}
+
+impl ConfirmState {
+    pub fn show(&mut self, path: PathBuf) {
+        self.path = Some(path);
+    }
+
+    pub fn visible(&self) -> bool {
+        self.path.is_some()
+    }
+}
+
+impl Component for ConfirmState {
+    fn update(&mut self, msg: &AppMsg) -> Option<AppMsg> {
+        let AppMsg::Key(key) = msg else {
+            return None;
+        };
+
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                self.path.take()?;
+                Some(AppMsg::PipelineStarted)
+            }
+            KeyCode::Char('n') | KeyCode::Esc | KeyCode::Char('q') => {
+                let path = self.path.take()?;
+                Some(AppMsg::LogMessage(format!(
+                    "Build cancelled, {} left untouched",
+                    path.display()
+                )))
+            }
+            _ => None,
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, _is_focused: bool) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let theme = get_theme();
+
+        let popup_area = centered_rect(50, 25, area);
+        Clear.render(popup_area, buf);
+
+        let block = Block::default()
+            .title("Overwrite Output?")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.status_failed));
+
+        let lines = vec![
+            Line::from("This build will replace files in an existing directory:"),
+            Line::from(Span::styled(
+                path.display().to_string(),
+                Style::default()
+                    .fg(theme.text_highlight)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                "y/Enter: Continue | n/Esc: Cancel",
+                Style::default().fg(theme.text_secondary),
+            )),
+        ];
+
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .render(popup_area, buf);
+    }
+}
@@ -24,6 +24,14 @@ pub struct FileBrowserState {
     pub scroll_state: ScrollbarState,
     pub tx: Option<Sender<AppMsg>>,
     pub last_refresh: Instant,
+    // Case-insensitive substring filter applied to `entries` when rendering
+    pub filter: String,
+    // True while `/` filter input is capturing keys
+    pub filtering: bool,
+    // Dotfiles are left out of `entries` unless this is set
+    pub show_hidden: bool,
+    // Directories reachable with 1..9, in the order they were added
+    pub bookmarks: Vec<PathBuf>,
 }
 
 impl Default for FileBrowserState {
@@ -37,6 +45,10 @@ impl Default for FileBrowserState {
             scroll_state: ScrollbarState::default(),
             tx: None,
             last_refresh: Instant::now(),
+            filter: String::new(),
+            filtering: false,
+            show_hidden: false,
+            bookmarks: Vec::new(),
         };
         state.refresh_entries();
         if !state.entries.is_empty() {
@@ -50,8 +62,15 @@ impl FileBrowserState {
     pub fn set_sender(&mut self, tx: Sender<AppMsg>) {
         self.tx = Some(tx);
     }
+
+    pub fn set_bookmarks(&mut self, bookmarks: Vec<PathBuf>) {
+        self.bookmarks = bookmarks;
+        self.bookmarks.truncate(MAX_BOOKMARKS);
+    }
 }
 
+const MAX_BOOKMARKS: usize = 9;
+
 impl FileBrowserState {
     fn refresh_entries(&mut self) {
         self.entries.clear();
@@ -66,6 +85,10 @@ impl FileBrowserState {
             let mut files = Vec::new();
 
             for entry in entries.flatten() {
+                if !self.show_hidden && entry.file_name().to_string_lossy().starts_with('.') {
+                    continue;
+                }
+
                 let path = entry.path();
                 if path.is_dir() {
                     dirs.push(path);
@@ -82,31 +105,153 @@ impl FileBrowserState {
         }
     }
 
+    fn log(&self, msg: String) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(AppMsg::LogMessage(msg));
+        }
+    }
+
+    fn add_bookmark(&mut self) {
+        if let Some(ix) = self.bookmarks.iter().position(|b| *b == self.current_dir) {
+            self.log(format!(
+                "{} is already bookmark {}",
+                self.current_dir.display(),
+                ix + 1
+            ));
+        } else if self.bookmarks.len() >= MAX_BOOKMARKS {
+            self.log(format!("All {} bookmark slots are in use", MAX_BOOKMARKS));
+        } else {
+            self.bookmarks.push(self.current_dir.clone());
+            self.log(format!(
+                "Bookmarked {} as {}",
+                self.current_dir.display(),
+                self.bookmarks.len()
+            ));
+        }
+    }
+
+    fn jump_to_bookmark(&mut self, slot: usize) {
+        let Some(dir) = slot.checked_sub(1).and_then(|ix| self.bookmarks.get(ix)) else {
+            self.log(format!("No bookmark {}", slot));
+            return;
+        };
+
+        if !dir.is_dir() {
+            self.log(format!(
+                "Bookmark {} no longer exists: {}",
+                slot,
+                dir.display()
+            ));
+            return;
+        }
+
+        self.current_dir = dir.clone();
+        self.filter.clear();
+        self.refresh_entries();
+        let first = if self.entries.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+        self.list_state.select(first);
+        self.scroll_state = self.scroll_state.position(0);
+    }
+
+    fn toggle_hidden(&mut self) {
+        self.show_hidden = !self.show_hidden;
+        self.refresh_entries();
+
+        let visible = self.visible_entries().len();
+        let selected = match self.list_state.selected() {
+            _ if visible == 0 => None,
+            Some(i) => Some(i.min(visible - 1)),
+            None => Some(0),
+        };
+        self.list_state.select(selected);
+        self.scroll_state = self.scroll_state.position(selected.unwrap_or(0));
+    }
+
+    // Entries matching the filter; `..` is always kept so the user can still go up
+    fn visible_entries(&self) -> Vec<&PathBuf> {
+        let needle = self.filter.to_lowercase();
+        self.entries
+            .iter()
+            .filter(|path| {
+                needle.is_empty()
+                    || path.to_string_lossy() == ".."
+                    || path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_lowercase().contains(&needle))
+                        .unwrap_or(false)
+            })
+            .collect()
+    }
+
+    fn set_filter(&mut self, filter: String) {
+        self.filter = filter;
+        let first_match = if self.visible_entries().is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+        self.list_state.select(first_match);
+        self.scroll_state = self.scroll_state.position(0);
+    }
+
+    fn handle_filter_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => {
+                self.filtering = false;
+                self.set_filter(String::new());
+            }
+            KeyCode::Enter => {
+                self.filtering = false;
+            }
+            KeyCode::Backspace => {
+                let mut filter = self.filter.clone();
+                filter.pop();
+                self.set_filter(filter);
+            }
+            KeyCode::Char(c) => {
+                let mut filter = self.filter.clone();
+                filter.push(c);
+                self.set_filter(filter);
+            }
+            _ => {}
+        }
+    }
+
+    /// Highlighted entry, unless it is the `..` row
+    pub fn selected_path(&self) -> Option<PathBuf> {
+        let idx = self.list_state.selected()?;
+        let path = self.visible_entries().get(idx).map(|p| (*p).clone())?;
+        (path.to_string_lossy() != "..").then_some(path)
+    }
+
     fn enter_selected(&mut self) -> Option<PathBuf> {
-        if let Some(idx) = self.list_state.selected() {
-            if let Some(path) = self.entries.get(idx) {
-                if path.to_string_lossy() == ".." {
-                    if let Some(parent) = self.current_dir.parent() {
-                        self.current_dir = parent.to_path_buf();
-                        self.refresh_entries();
-                        self.list_state.select(Some(0));
-                        self.scroll_state = self.scroll_state.position(0);
-                    }
-                    None
-                } else if path.is_dir() {
-                    self.current_dir = path.clone();
-                    self.refresh_entries();
-                    self.list_state.select(Some(0));
-                    self.scroll_state = self.scroll_state.position(0);
-                    None
-                } else {
-                    Some(self.current_dir.clone())
-                }
-            } else {
-                None
+        let path = self
+            .list_state
+            .selected()
+            .and_then(|idx| self.visible_entries().get(idx).map(|p| (*p).clone()))?;
+
+        if path.to_string_lossy() == ".." {
+            if let Some(parent) = self.current_dir.parent() {
+                self.current_dir = parent.to_path_buf();
+                self.filter.clear();
+                self.refresh_entries();
+                self.list_state.select(Some(0));
+                self.scroll_state = self.scroll_state.position(0);
             }
-        } else {
             None
+        } else if path.is_dir() {
+            self.current_dir = path;
+            self.filter.clear();
+            self.refresh_entries();
+            self.list_state.select(Some(0));
+            self.scroll_state = self.scroll_state.position(0);
+            None
+        } else {
+            Some(self.current_dir.clone())
         }
     }
 }
@@ -120,22 +265,29 @@ impl Component for FileBrowserState {
                     self.last_refresh = Instant::now();
 
                     // Ensure selection is valid
+                    let visible = self.visible_entries().len();
                     if let Some(selected) = self.list_state.selected()
-                        && selected >= self.entries.len()
+                        && selected >= visible
                     {
-                        let new_selected = self.entries.len().saturating_sub(1);
+                        let new_selected = visible.saturating_sub(1);
                         self.list_state.select(Some(new_selected));
                     }
                 }
             }
+            AppMsg::Key(key)
+                if self.filtering && !matches!(key.code, KeyCode::Up | KeyCode::Down) =>
+            {
+                self.handle_filter_key(key.code);
+            }
             AppMsg::Key(key) => match key.code {
                 KeyCode::Down | KeyCode::Char('j') => {
-                    if self.entries.is_empty() {
+                    let len = self.visible_entries().len();
+                    if len == 0 {
                         return None;
                     }
                     let i = match self.list_state.selected() {
                         Some(i) => {
-                            if i >= self.entries.len().saturating_sub(1) {
+                            if i >= len.saturating_sub(1) {
                                 0
                             } else {
                                 i + 1
@@ -147,13 +299,14 @@ impl Component for FileBrowserState {
                     self.scroll_state = self.scroll_state.position(i);
                 }
                 KeyCode::Up | KeyCode::Char('k') => {
-                    if self.entries.is_empty() {
+                    let len = self.visible_entries().len();
+                    if len == 0 {
                         return None;
                     }
                     let i = match self.list_state.selected() {
                         Some(i) => {
                             if i == 0 {
-                                self.entries.len().saturating_sub(1)
+                                len.saturating_sub(1)
                             } else {
                                 i - 1
                             }
@@ -175,6 +328,21 @@ impl Component for FileBrowserState {
                         let _ = tx.send(AppMsg::CursorSelected(self.current_dir.clone()));
                     }
                 }
+                KeyCode::Char('/') => {
+                    self.filtering = true;
+                }
+                KeyCode::Char('.') => {
+                    self.toggle_hidden();
+                }
+                KeyCode::Char('b') => {
+                    self.add_bookmark();
+                }
+                KeyCode::Char(c @ '1'..='9') => {
+                    self.jump_to_bookmark(c as usize - '0' as usize);
+                }
+                KeyCode::Esc if !self.filter.is_empty() => {
+                    self.set_filter(String::new());
+                }
                 _ => {}
             },
             _ => {}
@@ -185,9 +353,10 @@ impl Component for FileBrowserState {
     fn render(&mut self, area: Rect, buf: &mut Buffer, is_focused: bool) {
         let theme = get_theme();
 
-        let items: Vec<ListItem> = self
-            .entries
-            .iter()
+        let visible = self.visible_entries();
+        let visible_len = visible.len();
+        let items: Vec<ListItem> = visible
+            .into_iter()
             .map(|entry| {
                 let icon = if entry.is_dir() { "📁" } else { "📄" };
                 let name = entry.file_name().unwrap_or_default().to_string_lossy();
@@ -196,7 +365,15 @@ impl Component for FileBrowserState {
             })
             .collect();
 
-        let block = focused_block("File Browser", is_focused);
+        let mut title = "File Browser".to_string();
+        if self.show_hidden {
+            title.push_str(" [hidden]");
+        }
+        if self.filtering || !self.filter.is_empty() {
+            let cursor = if self.filtering { "_" } else { "" };
+            title.push_str(&format!(" [/{}{}]", self.filter, cursor));
+        }
+        let block = focused_block(&title, is_focused);
         let inner_area = block.inner(area);
         block.render(area, buf);
 
@@ -210,7 +387,7 @@ impl Component for FileBrowserState {
 
         StatefulWidget::render(list, inner_area, buf, &mut self.list_state);
 
-        self.scroll_state = self.scroll_state.content_length(self.entries.len());
+        self.scroll_state = self.scroll_state.content_length(visible_len);
 
         let scrollbar = Scrollbar::default()
             .orientation(ScrollbarOrientation::VerticalRight)
@@ -220,3 +397,64 @@ impl Component for FileBrowserState {
         scrollbar.render(inner_area, buf, &mut self.scroll_state);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyEvent, KeyModifiers};
+    use tempfile::tempdir;
+
+    fn press(state: &mut FileBrowserState, code: KeyCode) {
+        state.update(&AppMsg::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+    }
+
+    fn visible_names(state: &FileBrowserState) -> Vec<String> {
+        state
+            .visible_entries()
+            .iter()
+            .map(|p| {
+                p.file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| p.to_string_lossy().into_owned())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_keeps_parent_and_resets_selection() {
+        let dir = tempdir().unwrap();
+        let current = dir.path().join("themes");
+        for sub in ["Cursors", "Icons"] {
+            std::fs::create_dir_all(current.join(sub)).unwrap();
+        }
+        std::fs::write(current.join("cursor_notes.txt"), "").unwrap();
+
+        let mut state = FileBrowserState {
+            current_dir: current,
+            initial_root: dir.path().to_path_buf(),
+            ..FileBrowserState::default()
+        };
+        state.refresh_entries();
+        state.list_state.select(Some(2));
+
+        press(&mut state, KeyCode::Char('/'));
+        for c in "CUR".chars() {
+            press(&mut state, KeyCode::Char(c));
+        }
+        assert_eq!(state.filter, "CUR");
+        assert_eq!(
+            visible_names(&state),
+            vec!["..", "Cursors", "cursor_notes.txt"]
+        );
+        assert_eq!(state.list_state.selected(), Some(0));
+
+        // Nothing matches, but `..` stays reachable
+        press(&mut state, KeyCode::Char('x'));
+        assert_eq!(visible_names(&state), vec![".."]);
+
+        press(&mut state, KeyCode::Esc);
+        assert!(!state.filtering);
+        assert!(state.filter.is_empty());
+        assert_eq!(visible_names(&state).len(), 4);
+    }
+}
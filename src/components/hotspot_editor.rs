@@ -2,17 +2,24 @@ use super::Component;
 use super::preview::PreviewState;
 use crate::event::AppMsg;
 use crate::model::cursor::CursorMeta;
-use crate::widgets::common::focused_block;
+use crate::pipeline::cursor_types::clamp_hotspot;
+use crate::pipeline::gif_export::export_gif;
+use crate::pipeline::log::LogLevel;
+use crate::pipeline::tip::{TipHeuristic, detect_tip, is_arrow_like};
+use crate::pipeline::xcur2png::build_sprite_sheet;
+use crate::pipeline::xcur2png::png_writer::write_png;
+use crate::widgets::common::{centered_rect, focused_block};
 use crate::widgets::theme::get_theme;
 use crossterm::event::{KeyCode, KeyEvent};
+use image::RgbaImage;
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
     style::{Modifier, Style},
     text::{Line, Span},
     widgets::{
-        List, ListItem, ListState, Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget,
-        Widget,
+        Block, Borders, Clear, List, ListItem, ListState, Paragraph, Scrollbar,
+        ScrollbarOrientation, ScrollbarState, StatefulWidget, Widget,
     },
 };
 use ratatui_image::picker::Picker;
@@ -20,6 +27,51 @@ use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
+// Sizes reached with the number keys 1-9
+const JUMP_SIZES: [u32; 9] = [16, 24, 32, 48, 64, 72, 96, 128, 256];
+
+// Slowest and fastest animation playback multipliers
+const SPEED_RANGE: (f32, f32) = (0.25, 4.0);
+
+// Mean position of every pixel that is not fully transparent, rounded to the nearest pixel
+pub fn opaque_centroid(image: &RgbaImage) -> Option<(u32, u32)> {
+    let (mut sum_x, mut sum_y, mut count) = (0u64, 0u64, 0u64);
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if pixel[3] > 0 {
+            sum_x += x as u64;
+            sum_y += y as u64;
+            count += 1;
+        }
+    }
+    (count > 0).then(|| {
+        (
+            ((sum_x as f64 / count as f64).round()) as u32,
+            ((sum_y as f64 / count as f64).round()) as u32,
+        )
+    })
+}
+
+// Starts on the 48px variant when there is one. Cursors with a single variant, or none at
+// all, get 0; every lookup goes through `get`, so an empty cursor just shows nothing.
+fn default_variant(cursor: &CursorMeta) -> usize {
+    cursor
+        .variants
+        .iter()
+        .position(|v| v.size == 48)
+        .unwrap_or(0)
+}
+
+// Parses "x,y" (whitespace tolerant) typed into the hotspot input popup
+fn parse_hotspot_input(input: &str) -> Option<(u32, u32)> {
+    let (x, y) = input.split_once(',')?;
+    Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
+
+// Arrow cursors whose every hotspot is still (0, 0) were most likely imported without one
+fn lacks_hotspot(cursor: &CursorMeta) -> bool {
+    is_arrow_like(&cursor.x11_name) && cursor.variants.iter().all(|v| v.hotspot == (0, 0))
+}
+
 pub struct HotspotEditorState {
     pub frame_ix: usize,
     pub playing: bool,
@@ -29,6 +81,8 @@ pub struct HotspotEditorState {
 
     // Edits
     pub modified_hotspots: HashSet<String>,
+    // Cursors left out of full builds, toggled with `x`
+    pub excluded: HashSet<String>,
     pub list_state: ListState,
     pub scroll_state: ScrollbarState,
     pub preview: PreviewState,
@@ -36,7 +90,17 @@ pub struct HotspotEditorState {
     // Animation timing
     pub last_tick: Instant,
     pub accumulator: Duration,
+    // Playback speed multiplier, a power of two within SPEED_RANGE
+    pub speed: f32,
+    // Set by the app while the editor is maximized; shows only the preview
     pub maximized: bool,
+    pub tip_heuristic: TipHeuristic,
+    // Text typed into the "go to hotspot" popup, `Some` while it is open
+    pub hotspot_input: Option<String>,
+    // First listed step of the ANI timing popup, `Some` while it is open
+    pub timing_scroll: Option<usize>,
+    // Hotspot copied with `y`, as a fraction of its variant's size
+    pub yanked_hotspot: Option<(f32, f32)>,
 }
 
 impl Default for HotspotEditorState {
@@ -55,12 +119,18 @@ impl HotspotEditorState {
             selected_cursor: 0,
             selected_variant: 0,
             modified_hotspots: HashSet::new(),
+            excluded: HashSet::new(),
             list_state: ListState::default(),
             scroll_state: ScrollbarState::default(),
             preview: PreviewState::new(picker_arc),
             last_tick: Instant::now(),
             accumulator: Duration::ZERO,
+            speed: 1.0,
             maximized: false,
+            tip_heuristic: TipHeuristic::TopLeft,
+            hotspot_input: None,
+            timing_scroll: None,
+            yanked_hotspot: None,
         }
     }
 
@@ -73,6 +143,35 @@ impl HotspotEditorState {
             .unwrap_or(50)
     }
 
+    // Current frame's delay at the playback speed
+    fn scaled_frame_delay(&self) -> Duration {
+        let mut frame_delay = Duration::from_millis(self.current_frame_delay());
+
+        // Prevent infinite loop if delay is 0
+        if frame_delay.is_zero() {
+            frame_delay = Duration::from_millis(50);
+        }
+        frame_delay
+            .div_f32(self.speed)
+            .max(Duration::from_millis(1))
+    }
+
+    /// Doubles (`faster`) or halves the playback speed within SPEED_RANGE
+    fn change_speed(&mut self, faster: bool) -> Option<AppMsg> {
+        let (min, max) = SPEED_RANGE;
+        let speed = if faster {
+            self.speed * 2.0
+        } else {
+            self.speed / 2.0
+        }
+        .clamp(min, max);
+        if speed == self.speed {
+            return None;
+        }
+        self.speed = speed;
+        Some(AppMsg::LogMessage(format!("Playback speed {}x", speed)))
+    }
+
     fn current_variant_frames_len(&self) -> Option<usize> {
         self.cursors
             .get(self.selected_cursor)
@@ -145,17 +244,58 @@ impl HotspotEditorState {
         }
     }
 
+    fn toggle_excluded(&mut self) -> Option<AppMsg> {
+        let name = self.cursors.get(self.selected_cursor)?.x11_name.clone();
+        let excluded = self.excluded.insert(name.clone());
+        if !excluded {
+            self.excluded.remove(&name);
+        }
+        Some(AppMsg::LogMessage(format!(
+            "{} {} full builds ({} excluded)",
+            name,
+            if excluded {
+                "skipped in"
+            } else {
+                "included in"
+            },
+            self.excluded.len()
+        )))
+    }
+
+    // Selects the variant closest to `size`; ties go to the smaller one
+    fn jump_to_size(&mut self, size: u32) -> Option<AppMsg> {
+        let cursor = self.cursors.get(self.selected_cursor)?;
+        let (idx, variant) = cursor
+            .variants
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, v)| v.size.abs_diff(size))?;
+
+        let found = variant.size;
+        if idx != self.selected_variant {
+            self.selected_variant = idx;
+            self.frame_ix = 0;
+            self.reset_animation_timer();
+        }
+        (found != size).then(|| {
+            AppMsg::LogMessage(format!(
+                "No {}x{} variant, showing {}x{}",
+                size, size, found, found
+            ))
+        })
+    }
+
     fn move_hotspot(&mut self, dx: i32, dy: i32) {
         if let Some(cursor) = self.cursors.get_mut(self.selected_cursor)
             && let Some(variant) = cursor.variants.get_mut(self.selected_variant)
         {
-            let (mut hx, mut hy) = variant.hotspot;
+            let (hx, hy) = variant.frame_hotspot(self.frame_ix);
+            let target = (
+                (hx as i32 + dx).max(0) as u32,
+                (hy as i32 + dy).max(0) as u32,
+            );
 
-            hx = (hx as i32 + dx).max(0).min(variant.size as i32) as u32;
-            hy = (hy as i32 + dy).max(0).min(variant.size as i32) as u32;
-
-            if variant.hotspot != (hx, hy) {
-                variant.hotspot = (hx, hy);
+            if variant.set_frame_hotspot(self.frame_ix, target) {
                 self.modified_hotspots.insert(cursor.x11_name.clone());
                 // Only invalidate protocol cache
                 self.preview.invalidate_protocol_for_variant(variant);
@@ -163,22 +303,366 @@ impl HotspotEditorState {
         }
     }
 
+    fn set_hotspot(&mut self, x: u32, y: u32) -> Option<(u32, u32)> {
+        let cursor = self.cursors.get_mut(self.selected_cursor)?;
+        let variant = cursor.variants.get_mut(self.selected_variant)?;
+
+        if variant.set_frame_hotspot(self.frame_ix, (x, y)) {
+            self.modified_hotspots.insert(cursor.x11_name.clone());
+            self.preview.invalidate_protocol_for_variant(variant);
+        }
+        Some(variant.frame_hotspot(self.frame_ix))
+    }
+
+    /// Places the hotspot on the cursor pixel under a clicked or dragged-over terminal cell
+    pub fn click_hotspot(&mut self, column: u16, row: u16, log: bool) -> Option<AppMsg> {
+        let (x, y) = self.preview.pixel_at(column, row)?;
+        let (hx, hy) = self.set_hotspot(x, y)?;
+        log.then(|| AppMsg::LogMessage(format!("Hotspot set to ({}, {})", hx, hy)))
+    }
+
+    // Applies the current variant's relative hotspot to every other size of the cursor
+    fn propagate_hotspot(&mut self) -> Option<AppMsg> {
+        let cursor = self.cursors.get_mut(self.selected_cursor)?;
+        let source = cursor.variants.get(self.selected_variant)?;
+        let (source_size, (hx, hy)) = (source.size, source.hotspot);
+        let (source_w, source_h) = source.pixel_size;
+        if source_w == 0 || source_h == 0 {
+            return None;
+        }
+
+        let mut changed = 0;
+        for (i, variant) in cursor.variants.iter_mut().enumerate() {
+            if i == self.selected_variant {
+                continue;
+            }
+
+            // Same rounding as the converter's target-size resize, in image pixels
+            let (width, height) = variant.pixel_size;
+            let hotspot = (
+                (hx as f32 * width as f32 / source_w as f32).round() as u32,
+                (hy as f32 * height as f32 / source_h as f32).round() as u32,
+            );
+
+            if variant.set_frame_hotspot(0, hotspot) {
+                self.preview.invalidate_protocol_for_variant(variant);
+                changed += 1;
+            }
+        }
+
+        if changed > 0 {
+            self.modified_hotspots.insert(cursor.x11_name.clone());
+        }
+
+        Some(AppMsg::LogMessage(format!(
+            "Applied {}x{} hotspot ({}, {}) to {} other sizes of {}",
+            source_size, source_size, hx, hy, changed, cursor.x11_name
+        )))
+    }
+
+    // Remembers the current variant's hotspot relative to its size, for `paste_hotspot`
+    fn yank_hotspot(&mut self) -> Option<AppMsg> {
+        let cursor = self.cursors.get(self.selected_cursor)?;
+        let variant = cursor.variants.get(self.selected_variant)?;
+        let (width, height) = variant.pixel_size;
+        if width == 0 || height == 0 {
+            return None;
+        }
+
+        let (hx, hy) = variant.hotspot;
+        self.yanked_hotspot = Some((hx as f32 / width as f32, hy as f32 / height as f32));
+        Some(AppMsg::LogMessage(format!(
+            "Copied {}x{} hotspot ({}, {}) of {}",
+            variant.size, variant.size, hx, hy, cursor.x11_name
+        )))
+    }
+
+    // Applies the yanked hotspot, scaled to the current variant's size, to that variant only
+    fn paste_hotspot(&mut self) -> Option<AppMsg> {
+        let Some((fx, fy)) = self.yanked_hotspot else {
+            return Some(AppMsg::LogMessage(
+                "No hotspot copied yet, press y on the correct size first".to_string(),
+            ));
+        };
+        let cursor = self.cursors.get_mut(self.selected_cursor)?;
+        let variant = cursor.variants.get_mut(self.selected_variant)?;
+
+        // Same rounding as propagate_hotspot
+        let size = variant.size;
+        let (width, height) = variant.pixel_size;
+        let hotspot = clamp_hotspot(
+            (
+                (fx * width as f32).round() as u32,
+                (fy * height as f32).round() as u32,
+            ),
+            (width, height),
+        );
+        if variant.set_frame_hotspot(0, hotspot) {
+            self.modified_hotspots.insert(cursor.x11_name.clone());
+            self.preview.invalidate_protocol_for_variant(variant);
+        }
+
+        Some(AppMsg::LogMessage(format!(
+            "Pasted hotspot ({}, {}) into {}x{} of {}",
+            hotspot.0, hotspot.1, size, size, cursor.x11_name
+        )))
+    }
+
+    fn handle_hotspot_input(&mut self, key: KeyEvent) -> Option<AppMsg> {
+        let input = self.hotspot_input.as_mut()?;
+
+        match key.code {
+            KeyCode::Esc => {
+                self.hotspot_input = None;
+                None
+            }
+            KeyCode::Backspace => {
+                input.pop();
+                None
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() || c == ',' || c == ' ' => {
+                input.push(c);
+                None
+            }
+            KeyCode::Enter => {
+                let text = self.hotspot_input.take().unwrap_or_default();
+                match parse_hotspot_input(&text) {
+                    Some((x, y)) => self.set_hotspot(x, y).map(|(hx, hy)| {
+                        AppMsg::LogMessage(format!("Hotspot set to ({}, {})", hx, hy))
+                    }),
+                    None => Some(AppMsg::LogMessage(format!(
+                        "Invalid hotspot '{}', expected x,y",
+                        text
+                    ))),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    // Sets the current variant's hotspot from its first frame using the active heuristic
+    fn auto_detect_hotspot(&mut self) -> Option<AppMsg> {
+        let heuristic = self.tip_heuristic;
+        let cursor = self.cursors.get_mut(self.selected_cursor)?;
+        let variant = cursor.variants.get_mut(self.selected_variant)?;
+        let frame = variant.frames.first()?;
+
+        let image = match image::open(&frame.png_path) {
+            Ok(img) => img.to_rgba8(),
+            Err(e) => {
+                return Some(AppMsg::LogMessage(format!(
+                    "Cannot detect tip for {}: {}",
+                    cursor.x11_name, e
+                )));
+            }
+        };
+
+        match detect_tip(&image, heuristic) {
+            Some(hotspot) => {
+                if variant.set_frame_hotspot(0, hotspot) {
+                    self.modified_hotspots.insert(cursor.x11_name.clone());
+                    self.preview.invalidate_protocol_for_variant(variant);
+                }
+                Some(AppMsg::LogMessage(format!(
+                    "Detected {} hotspot for {} ({}x{}): ({}, {})",
+                    heuristic.name(),
+                    cursor.x11_name,
+                    variant.size,
+                    variant.size,
+                    hotspot.0,
+                    hotspot.1
+                )))
+            }
+            None => Some(AppMsg::LogMessage(format!(
+                "No opaque pixels found in {}",
+                cursor.x11_name
+            ))),
+        }
+    }
+
+    // Moves the current frame's hotspot to the centroid of its visible pixels, for sources
+    // that left it at (0, 0)
+    fn center_hotspot(&mut self) -> Option<AppMsg> {
+        let frame_ix = self.frame_ix;
+        let cursor = self.cursors.get_mut(self.selected_cursor)?;
+        let variant = cursor.variants.get_mut(self.selected_variant)?;
+        let frame = variant.frames.get(frame_ix)?;
+
+        let image = match image::open(&frame.png_path) {
+            Ok(img) => img.to_rgba8(),
+            Err(e) => {
+                return Some(AppMsg::LogMessage(format!(
+                    "Cannot center hotspot for {}: {}",
+                    cursor.x11_name, e
+                )));
+            }
+        };
+
+        let Some(hotspot) = opaque_centroid(&image) else {
+            return Some(AppMsg::LogMessageLevel(
+                LogLevel::Warn,
+                format!(
+                    "Frame {} of {} is fully transparent, hotspot left unchanged",
+                    frame_ix + 1,
+                    cursor.x11_name
+                ),
+            ));
+        };
+
+        if variant.set_frame_hotspot(frame_ix, hotspot) {
+            self.modified_hotspots.insert(cursor.x11_name.clone());
+            self.preview.invalidate_protocol_for_variant(variant);
+        }
+        Some(AppMsg::LogMessage(format!(
+            "Centered hotspot of {} ({}x{}): ({}, {})",
+            cursor.x11_name, variant.size, variant.size, hotspot.0, hotspot.1
+        )))
+    }
+
+    fn zoom(&mut self, delta: i32) -> Option<AppMsg> {
+        if !self.preview.change_zoom(delta) {
+            return None;
+        }
+        Some(AppMsg::LogMessage(format!(
+            "Preview zoom {}x",
+            self.preview.zoom
+        )))
+    }
+
+    // Writes the current variant's animation as `<x11_name>.gif` beside its first frame
+    fn export_current_gif(&self) -> Option<AppMsg> {
+        let cursor = self.cursors.get(self.selected_cursor)?;
+        let variant = cursor.variants.get(self.selected_variant)?;
+        let dir = variant.frames.first()?.png_path.parent()?;
+        let out = dir.join(format!("{}.gif", cursor.x11_name));
+
+        Some(match export_gif(&variant.frames, &out) {
+            Ok(skipped) if skipped.is_empty() => {
+                AppMsg::LogMessage(format!("Exported {}", out.display()))
+            }
+            Ok(skipped) => AppMsg::LogMessageLevel(
+                LogLevel::Warn,
+                format!(
+                    "Exported {}, skipping {} unreadable frame(s)",
+                    out.display(),
+                    skipped.len()
+                ),
+            ),
+            Err(e) => {
+                AppMsg::ErrorOccurred(format!("GIF export failed for {}: {}", cursor.x11_name, e))
+            }
+        })
+    }
+
+    // One row per size and one column per frame, next to the frame PNGs
+    fn export_current_sprite_sheet(&self) -> Option<AppMsg> {
+        let cursor = self.cursors.get(self.selected_cursor)?;
+        let dir = cursor.variants.first()?.frames.first()?.png_path.parent()?;
+        let out = dir.join(format!("{}_sheet.png", cursor.x11_name));
+
+        // A missing frame would shift the rest of its row into the wrong columns
+        let rows: Result<Vec<Vec<RgbaImage>>, String> = cursor
+            .variants
+            .iter()
+            .map(|variant| {
+                variant
+                    .frames
+                    .iter()
+                    .map(|frame| {
+                        image::open(&frame.png_path)
+                            .map(|img| img.to_rgba8())
+                            .map_err(|e| format!("{}: {}", frame.png_path.display(), e))
+                    })
+                    .collect()
+            })
+            .collect();
+        let rows = match rows {
+            Ok(rows) => rows,
+            Err(e) => {
+                return Some(AppMsg::ErrorOccurred(format!(
+                    "Sprite sheet export failed for {}, unreadable frame {}",
+                    cursor.x11_name, e
+                )));
+            }
+        };
+        let rows: Vec<Vec<&RgbaImage>> = rows.iter().map(|row| row.iter().collect()).collect();
+
+        Some(match write_png(&build_sprite_sheet(&rows), &out) {
+            Ok(()) => AppMsg::LogMessage(format!("Exported {}", out.display())),
+            Err(e) => AppMsg::ErrorOccurred(format!(
+                "Sprite sheet export failed for {}: {}",
+                cursor.x11_name, e
+            )),
+        })
+    }
+
+    // Places every size of an arrow cursor that was imported without a hotspot on its tip
+    fn detect_missing_tips(&mut self) -> Option<AppMsg> {
+        let cursor = self.cursors.get_mut(self.selected_cursor)?;
+        let mut placed = 0;
+        for variant in &mut cursor.variants {
+            let Some(frame) = variant.frames.first() else {
+                continue;
+            };
+            let Ok(img) = image::open(&frame.png_path) else {
+                continue;
+            };
+
+            if let Some(hotspot) = detect_tip(&img.to_rgba8(), TipHeuristic::TopLeft)
+                && variant.set_frame_hotspot(0, hotspot)
+            {
+                self.preview.invalidate_protocol_for_variant(variant);
+                placed += 1;
+            }
+        }
+
+        if placed > 0 {
+            self.modified_hotspots.insert(cursor.x11_name.clone());
+        }
+        Some(AppMsg::LogMessage(format!(
+            "Placed the hotspot of {} sizes of {} on the cursor tip",
+            placed, cursor.x11_name
+        )))
+    }
+
+    // Opens the timing popup, asking the app to read the source first when the cursor was
+    // not loaded from an .ani file
+    fn show_ani_timing(&mut self) -> Option<AppMsg> {
+        let cursor = self.cursors.get(self.selected_cursor)?;
+        if cursor.ani_sequence.is_some() {
+            self.timing_scroll = Some(0);
+            None
+        } else {
+            Some(AppMsg::AniTimingRequested(cursor.x11_name.clone()))
+        }
+    }
+
+    fn handle_timing_key(&mut self, key: KeyEvent) -> Option<AppMsg> {
+        let scroll = self.timing_scroll.as_mut()?;
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('i') => self.timing_scroll = None,
+            KeyCode::Char('j') | KeyCode::Down => *scroll += 1,
+            KeyCode::Char('k') | KeyCode::Up => *scroll = scroll.saturating_sub(1),
+            _ => {}
+        }
+        None
+    }
+
     fn handle_key(&mut self, key: KeyEvent) -> Option<AppMsg> {
+        if self.hotspot_input.is_some() {
+            return self.handle_hotspot_input(key);
+        }
+        if self.timing_scroll.is_some() {
+            return self.handle_timing_key(key);
+        }
+
         match key.code {
             KeyCode::Char(' ') => {
-                if key
-                    .modifiers
-                    .contains(crossterm::event::KeyModifiers::CONTROL)
-                {
-                    self.maximized = !self.maximized;
-                    None
-                } else {
-                    self.playing = !self.playing;
-                    Some(AppMsg::LogMessage(format!(
-                        "Animation {}",
-                        if self.playing { "playing" } else { "paused" }
-                    )))
-                }
+                self.playing = !self.playing;
+                Some(AppMsg::LogMessage(format!(
+                    "Animation {}",
+                    if self.playing { "playing" } else { "paused" }
+                )))
             }
             KeyCode::Left => {
                 self.move_hotspot(-1, 0);
@@ -208,6 +692,10 @@ impl HotspotEditorState {
                 self.prev_variant();
                 None
             }
+            KeyCode::Char(c @ '1'..='9') => {
+                let size = JUMP_SIZES[(c as u8 - b'1') as usize];
+                self.jump_to_size(size)
+            }
             KeyCode::Char(']') => {
                 self.next_variant();
                 None
@@ -220,6 +708,69 @@ impl HotspotEditorState {
                     None
                 }
             }
+            KeyCode::Char('a') => self.propagate_hotspot(),
+            KeyCode::Char('y') => self.yank_hotspot(),
+            KeyCode::Char('p') => self.paste_hotspot(),
+            KeyCode::Char('g') => {
+                self.hotspot_input = Some(String::new());
+                None
+            }
+            KeyCode::Char('t') => match self.cursors.get(self.selected_cursor) {
+                Some(cursor) if lacks_hotspot(cursor) => self.detect_missing_tips(),
+                _ => self.auto_detect_hotspot(),
+            },
+            KeyCode::Char('C') => self.center_hotspot(),
+            KeyCode::Char('T') => {
+                self.tip_heuristic = self.tip_heuristic.next();
+                Some(AppMsg::LogMessage(format!(
+                    "Tip detection: {}",
+                    self.tip_heuristic.name()
+                )))
+            }
+            KeyCode::Char('G') => self.export_current_gif(),
+            KeyCode::Char('e') => self.export_current_sprite_sheet(),
+            KeyCode::Char('x') => self.toggle_excluded(),
+            KeyCode::Char('i') => self.show_ani_timing(),
+            KeyCode::Char('+') | KeyCode::Char('=') => self.zoom(1),
+            KeyCode::Char('-') => self.zoom(-1),
+            KeyCode::Char('<') => self.change_speed(false),
+            KeyCode::Char('>') => self.change_speed(true),
+            KeyCode::Char('c') => {
+                self.preview.toggle_crosshair();
+                Some(AppMsg::LogMessage(format!(
+                    "Hotspot crosshair {}",
+                    if self.preview.crosshair { "on" } else { "off" }
+                )))
+            }
+            KeyCode::Char('m') => {
+                self.preview.toggle_grid();
+                Some(AppMsg::LogMessage(format!(
+                    "Pixel grid {}",
+                    if self.preview.show_grid { "on" } else { "off" }
+                )))
+            }
+            KeyCode::Char('b') => {
+                self.preview.toggle_checkerboard();
+                Some(AppMsg::LogMessage(format!(
+                    "Checkerboard background {}",
+                    if self.preview.show_checkerboard {
+                        "on"
+                    } else {
+                        "off"
+                    }
+                )))
+            }
+            KeyCode::Char('d') => {
+                self.preview.toggle_shadow();
+                Some(AppMsg::LogMessage(format!(
+                    "Shadow preview {}",
+                    if self.preview.show_shadow {
+                        "on"
+                    } else {
+                        "off"
+                    }
+                )))
+            }
             KeyCode::Char(',') => {
                 self.playing = false;
                 self.prev_frame();
@@ -241,7 +792,7 @@ impl HotspotEditorState {
             .iter()
             .enumerate()
             .map(|(i, cursor)| {
-                let style = if i == self.selected_cursor {
+                let mut style = if i == self.selected_cursor {
                     Style::default()
                         .fg(theme.background)
                         .bg(theme.status_completed)
@@ -249,6 +800,9 @@ impl HotspotEditorState {
                 } else {
                     Style::default().fg(theme.text_primary)
                 };
+                if self.excluded.contains(&cursor.x11_name) {
+                    style = style.add_modifier(Modifier::CROSSED_OUT | Modifier::DIM);
+                }
 
                 let marker = if self.modified_hotspots.contains(&cursor.x11_name) {
                     "*"
@@ -256,14 +810,28 @@ impl HotspotEditorState {
                     ""
                 };
 
-                ListItem::new(Line::from(vec![
+                let mut spans = vec![
                     Span::styled(format!("{}{}", cursor.x11_name, marker), style),
                     Span::raw(" "),
                     Span::styled(
                         format!("({})", cursor.variants.len()),
                         style.fg(theme.text_secondary),
                     ),
-                ]))
+                ];
+                if lacks_hotspot(cursor) {
+                    spans.push(Span::styled(
+                        " no hotspot, t: tip",
+                        style.fg(theme.text_highlight),
+                    ));
+                }
+                if cursor.is_animated() {
+                    let loop_text = match cursor.total_duration_ms() {
+                        0 => " ⟳".to_string(),
+                        ms => format!(" ⟳ {:.1}s", ms as f32 / 1000.0),
+                    };
+                    spans.push(Span::styled(loop_text, style.fg(theme.text_secondary)));
+                }
+                ListItem::new(Line::from(spans))
             })
             .collect();
 
@@ -289,6 +857,132 @@ impl HotspotEditorState {
 
         scrollbar.render(inner_area, buf, &mut self.scroll_state);
     }
+
+    fn render_hotspot_input(&self, area: Rect, buf: &mut Buffer) {
+        let Some(input) = &self.hotspot_input else {
+            return;
+        };
+        let theme = get_theme();
+
+        let current = self
+            .cursors
+            .get(self.selected_cursor)
+            .and_then(|c| c.variants.get(self.selected_variant))
+            .map(|v| {
+                let (hx, hy) = v.frame_hotspot(self.frame_ix);
+                format!("Current: ({}, {}) of {}", hx, hy, v.size)
+            })
+            .unwrap_or_default();
+
+        let popup_area = centered_rect(50, 30, area);
+        Clear.render(popup_area, buf);
+
+        let block = Block::default()
+            .title("Set Hotspot (x,y)")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border_focused));
+
+        let lines = vec![
+            Line::from(Span::styled(
+                current,
+                Style::default().fg(theme.text_secondary),
+            )),
+            Line::from(vec![
+                Span::styled("> ", Style::default().fg(theme.text_highlight)),
+                Span::styled(
+                    format!("{}_", input),
+                    Style::default()
+                        .fg(theme.text_primary)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::from(Span::styled(
+                "Enter: Apply | Esc: Cancel",
+                Style::default().fg(theme.text_secondary),
+            )),
+        ];
+
+        Paragraph::new(lines).block(block).render(popup_area, buf);
+    }
+
+    fn render_ani_timing(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(scroll) = self.timing_scroll else {
+            return;
+        };
+        let Some(cursor) = self.cursors.get(self.selected_cursor) else {
+            return;
+        };
+        let Some(sequence) = &cursor.ani_sequence else {
+            return;
+        };
+        let theme = get_theme();
+        let secondary = Style::default().fg(theme.text_secondary);
+
+        let popup_area = centered_rect(60, 70, area);
+        Clear.render(popup_area, buf);
+
+        let block = Block::default()
+            .title(format!("ANI Timing: {}", cursor.x11_name))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border_focused));
+        let inner = block.inner(popup_area);
+        block.render(popup_area, buf);
+
+        let source = |present: bool, fallback: &str| {
+            if present {
+                "file".to_string()
+            } else {
+                fallback.to_string()
+            }
+        };
+        let steps = sequence.steps();
+        let mut lines = vec![
+            Line::from(Span::styled(
+                format!(
+                    "{} frames, {} steps, header rate {} jiffies",
+                    sequence.frame_count, sequence.step_count, sequence.display_rate
+                ),
+                Style::default().fg(theme.text_primary),
+            )),
+            Line::from(Span::styled(
+                format!(
+                    "Order: {} | Delays: {}",
+                    source(sequence.order.is_some(), "frame order"),
+                    source(sequence.rates.is_some(), "header rate")
+                ),
+                secondary,
+            )),
+            Line::from(Span::styled(
+                format!("{:>5} {:>6} {:>8} {:>6}", "Step", "Frame", "Jiffies", "ms"),
+                Style::default()
+                    .fg(theme.text_highlight)
+                    .add_modifier(Modifier::BOLD),
+            )),
+        ];
+
+        let visible = (inner.height as usize).saturating_sub(lines.len() + 1);
+        let scroll = scroll.min(steps.len().saturating_sub(visible));
+        self.timing_scroll = Some(scroll);
+        for (i, (frame, jiffies, ms)) in steps.iter().enumerate().skip(scroll).take(visible) {
+            let style = if i == self.frame_ix {
+                Style::default()
+                    .fg(theme.text_highlight)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.text_primary)
+            };
+            lines.push(Line::from(Span::styled(
+                format!("{:>5} {:>6} {:>8} {:>6}", i + 1, frame, jiffies, ms),
+                style,
+            )));
+        }
+        lines.push(Line::from(Span::styled(
+            "j/k: Scroll | i/Esc: Close",
+            secondary,
+        )));
+
+        Paragraph::new(lines).render(inner, buf);
+    }
 }
 
 impl Component for HotspotEditorState {
@@ -307,21 +1001,12 @@ impl Component for HotspotEditorState {
                     self.last_tick = now;
                     self.accumulator += delta;
 
-                    let mut frame_delay = Duration::from_millis(self.current_frame_delay());
-
-                    // Prevent infinite loop if delay is 0
-                    if frame_delay.is_zero() {
-                        frame_delay = Duration::from_millis(50);
-                    }
-
+                    let mut frame_delay = self.scaled_frame_delay();
                     while self.accumulator >= frame_delay {
                         self.accumulator -= frame_delay;
                         self.next_frame();
                         // Update frame delay for the new frame
-                        frame_delay = Duration::from_millis(self.current_frame_delay());
-                        if frame_delay.is_zero() {
-                            frame_delay = Duration::from_millis(50);
-                        }
+                        frame_delay = self.scaled_frame_delay();
                     }
                 } else {
                     // Reset timer when not playing
@@ -333,14 +1018,9 @@ impl Component for HotspotEditorState {
             AppMsg::CursorLoaded(cursors) => {
                 self.cursors = cursors.clone();
                 self.selected_cursor = 0;
+                self.timing_scroll = None;
 
-                // Default to 48x48
-                self.selected_variant = 0;
-                if let Some(cursor) = self.cursors.first()
-                    && let Some(idx) = cursor.variants.iter().position(|v| v.size == 48)
-                {
-                    self.selected_variant = idx;
-                }
+                self.selected_variant = self.cursors.first().map(default_variant).unwrap_or(0);
                 self.frame_ix = 0;
                 self.modified_hotspots.clear();
                 self.preview.clear_cache();
@@ -356,6 +1036,18 @@ impl Component for HotspotEditorState {
 
                 None
             }
+            AppMsg::AniTimingLoaded(x11_name, sequence) => {
+                let cursor = self.cursors.iter_mut().find(|c| &c.x11_name == x11_name)?;
+                cursor.ani_sequence = Some(sequence.clone());
+                if self
+                    .cursors
+                    .get(self.selected_cursor)
+                    .is_some_and(|c| &c.x11_name == x11_name)
+                {
+                    self.timing_scroll = Some(0);
+                }
+                None
+            }
             AppMsg::Key(key) => self.handle_key(*key),
             _ => None,
         }
@@ -365,7 +1057,7 @@ impl Component for HotspotEditorState {
         if self.cursors.is_empty() {
             let block = focused_block("Hotspot Editor", is_focused);
 
-            ratatui::widgets::Paragraph::new("No cursor loaded")
+            Paragraph::new("No cursor loaded")
                 .block(block)
                 .render(area, buf);
             return;
@@ -413,7 +1105,7 @@ impl Component for HotspotEditorState {
                 if let Some(frame) = variant.frames.get(self.frame_ix) {
                     Some((
                         path.as_str(),
-                        variant.hotspot,
+                        frame.hotspot,
                         variant.size,
                         cursor,
                         variant,
@@ -430,13 +1122,180 @@ impl Component for HotspotEditorState {
             None
         };
 
-        self.preview.render(
-            chunks[1],
-            buf,
-            is_focused,
-            self.playing,
-            self.maximized,
-            data,
+        self.preview
+            .render(chunks[1], buf, is_focused, self.speed, self.maximized, data);
+
+        self.render_hotspot_input(inner, buf);
+        self.render_ani_timing(inner, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::cursor::{Frame, SizeVariant};
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    fn press(editor: &mut HotspotEditorState, code: KeyCode) {
+        editor.update(&AppMsg::Key(KeyEvent::from(code)));
+    }
+
+    fn variant(size: u32, hotspot: (u32, u32)) -> SizeVariant {
+        SizeVariant {
+            size,
+            pixel_size: (size, size),
+            frames: vec![Frame {
+                png_path: PathBuf::new(),
+                delay_ms: 50,
+                hotspot,
+            }],
+            hotspot,
+        }
+    }
+
+    #[test]
+    fn test_opaque_centroid() {
+        let mut image = RgbaImage::new(8, 8);
+        assert_eq!(opaque_centroid(&image), None);
+
+        // Any alpha counts, unlike the tip threshold, and the mean is rounded
+        image.put_pixel(1, 1, image::Rgba([0, 0, 0, 255]));
+        image.put_pixel(4, 2, image::Rgba([0, 0, 0, 1]));
+        image.put_pixel(1, 5, image::Rgba([0, 0, 0, 255]));
+        assert_eq!(opaque_centroid(&image), Some((2, 3)));
+    }
+
+    #[test]
+    fn test_sprite_sheet_export_fails_on_unreadable_frame() {
+        let dir = tempdir().unwrap();
+        let readable = dir.path().join("wait_000.png");
+        RgbaImage::new(32, 32).save(&readable).unwrap();
+
+        let mut cursor_variant = variant(32, (0, 0));
+        cursor_variant.frames[0].png_path = readable;
+        cursor_variant.frames.push(Frame {
+            png_path: dir.path().join("wait_001.png"),
+            delay_ms: 50,
+            hotspot: (0, 0),
+        });
+        let mut editor = HotspotEditorState::default();
+        editor.update(&AppMsg::CursorLoaded(vec![CursorMeta {
+            x11_name: "wait".to_string(),
+            variants: vec![cursor_variant],
+            ani_sequence: None,
+        }]));
+
+        let msg = editor.export_current_sprite_sheet();
+        assert!(
+            matches!(&msg, Some(AppMsg::ErrorOccurred(e)) if e.contains("wait_001.png")),
+            "{msg:?}"
         );
+        assert!(!dir.path().join("wait_sheet.png").exists());
+    }
+
+    #[test]
+    fn test_tip_is_only_detected_on_request() {
+        let dir = tempdir().unwrap();
+        let png_path = dir.path().join("left_ptr_000.png");
+        let mut image = RgbaImage::new(32, 32);
+        image.put_pixel(6, 2, image::Rgba([255, 255, 255, 255]));
+        image.save(&png_path).unwrap();
+
+        let mut editor = HotspotEditorState::default();
+        let mut cursor_variant = variant(32, (0, 0));
+        cursor_variant.frames[0].png_path = png_path;
+        editor.update(&AppMsg::CursorLoaded(vec![CursorMeta {
+            x11_name: "left_ptr".to_string(),
+            variants: vec![cursor_variant],
+            ani_sequence: None,
+        }]));
+        assert_eq!(editor.cursors[0].variants[0].hotspot, (0, 0));
+        assert!(editor.modified_hotspots.is_empty());
+
+        press(&mut editor, KeyCode::Char('t'));
+        assert_eq!(editor.cursors[0].variants[0].hotspot, (6, 2));
+        assert!(editor.modified_hotspots.contains("left_ptr"));
+    }
+
+    #[test]
+    fn test_paste_scales_yanked_hotspot_into_one_size() {
+        let mut editor = HotspotEditorState::default();
+        editor.update(&AppMsg::CursorLoaded(vec![CursorMeta {
+            x11_name: "hand".to_string(),
+            variants: vec![
+                variant(24, (1, 1)),
+                variant(32, (8, 4)),
+                variant(64, (2, 2)),
+            ],
+            ani_sequence: None,
+        }]));
+
+        editor.selected_variant = 1;
+        press(&mut editor, KeyCode::Char('y'));
+        editor.selected_variant = 2;
+        press(&mut editor, KeyCode::Char('p'));
+
+        let variants = &editor.cursors[0].variants;
+        assert_eq!(variants[2].hotspot, (16, 8));
+        assert_eq!(variants[2].frames[0].hotspot, (16, 8));
+        // Sizes that were not pasted into keep their hotspot
+        assert_eq!(variants[0].hotspot, (1, 1));
+        assert!(editor.modified_hotspots.contains("hand"));
+    }
+
+    #[test]
+    fn test_single_image_cursor_loads_and_renders() {
+        let dir = tempdir().unwrap();
+        let png_path = dir.path().join("arrow_000.png");
+        RgbaImage::new(32, 32).save(&png_path).unwrap();
+
+        let single = CursorMeta {
+            x11_name: "arrow".to_string(),
+            variants: vec![SizeVariant {
+                size: 32,
+                pixel_size: (32, 32),
+                frames: vec![Frame {
+                    png_path,
+                    delay_ms: 0,
+                    hotspot: (3, 4),
+                }],
+                hotspot: (3, 4),
+            }],
+            ani_sequence: None,
+        };
+        let empty = CursorMeta {
+            x11_name: "empty".to_string(),
+            ..Default::default()
+        };
+
+        let mut editor = HotspotEditorState::default();
+        editor.update(&AppMsg::CursorLoaded(vec![single, empty]));
+        assert_eq!((editor.selected_variant, editor.frame_ix), (0, 0));
+
+        let area = Rect::new(0, 0, 100, 30);
+        let mut buf = Buffer::empty(area);
+        for code in [
+            KeyCode::Char(']'),
+            KeyCode::Char('['),
+            KeyCode::Char('.'),
+            KeyCode::Char(','),
+            KeyCode::Char('4'),
+            KeyCode::Char('9'),
+        ] {
+            press(&mut editor, code);
+            assert_eq!((editor.selected_variant, editor.frame_ix), (0, 0));
+        }
+        editor.update(&AppMsg::Tick);
+        editor.render(area, &mut buf, true);
+
+        // A cursor without any variant is selectable without panicking either
+        press(&mut editor, KeyCode::Char('j'));
+        assert_eq!(editor.selected_cursor, 1);
+        for code in [KeyCode::Char(']'), KeyCode::Char('.'), KeyCode::Char('a')] {
+            press(&mut editor, code);
+        }
+        editor.update(&AppMsg::Tick);
+        editor.render(area, &mut buf, true);
     }
 }
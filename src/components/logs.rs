@@ -1,5 +1,6 @@
 use super::Component;
 use crate::event::AppMsg;
+use crate::pipeline::log::LogLevel;
 use crate::widgets::common::focused_block;
 use crate::widgets::theme::get_theme;
 use crossterm::event::KeyCode;
@@ -13,7 +14,9 @@ use ratatui::{
 
 #[derive(Debug)]
 pub struct LogsState {
-    pub logs: Vec<String>,
+    pub logs: Vec<(LogLevel, String)>,
+    // Lines below this level are hidden; cycled with `f`
+    pub min_level: LogLevel,
     scroll_state: ScrollbarState,
     scroll_offset: u16,
     stick_to_bottom: bool,
@@ -23,6 +26,7 @@ impl Default for LogsState {
     fn default() -> Self {
         Self {
             logs: Vec::new(),
+            min_level: LogLevel::Detail,
             scroll_state: ScrollbarState::default(),
             scroll_offset: 0,
             stick_to_bottom: true,
@@ -31,8 +35,17 @@ impl Default for LogsState {
 }
 
 impl LogsState {
-    pub fn add_log(&mut self, message: String) {
-        self.logs.push(message);
+    pub fn add_log(&mut self, level: LogLevel, message: String) {
+        self.logs.push((level, message));
+    }
+
+    fn cycle_filter(&mut self) {
+        self.min_level = match self.min_level {
+            LogLevel::Error => LogLevel::Detail,
+            LogLevel::Warn => LogLevel::Error,
+            _ => LogLevel::Warn,
+        };
+        self.stick_to_bottom = true;
     }
 }
 
@@ -40,10 +53,16 @@ impl Component for LogsState {
     fn update(&mut self, msg: &AppMsg) -> Option<AppMsg> {
         match msg {
             AppMsg::LogMessage(msg) => {
-                self.add_log(msg.clone());
+                self.add_log(LogLevel::Info, msg.clone());
+            }
+            AppMsg::LogMessageLevel(level, msg) => {
+                self.add_log(*level, msg.clone());
             }
             AppMsg::ErrorOccurred(err) => {
-                self.add_log(format!("ERROR: {}", err));
+                self.add_log(LogLevel::Error, format!("ERROR: {}", err));
+            }
+            AppMsg::PipelineFailed(err) => {
+                self.add_log(LogLevel::Error, format!("Pipeline failed: {}", err));
             }
             AppMsg::Key(key) => match key.code {
                 KeyCode::Up | KeyCode::Char('k') => {
@@ -64,6 +83,9 @@ impl Component for LogsState {
                     self.scroll_offset = self.scroll_offset.saturating_add(10);
                     self.scroll_state = self.scroll_state.position(self.scroll_offset as usize);
                 }
+                KeyCode::Char('f') => {
+                    self.cycle_filter();
+                }
                 _ => {}
             },
             _ => {}
@@ -72,7 +94,11 @@ impl Component for LogsState {
     }
 
     fn render(&mut self, area: Rect, buf: &mut Buffer, is_focused: bool) {
-        let block = focused_block("Logs", is_focused);
+        let title = match self.min_level {
+            LogLevel::Detail => "Logs".to_string(),
+            level => format!("Logs [{}+]", level.name()),
+        };
+        let block = focused_block(&title, is_focused);
 
         let inner_area = block.inner(area);
         block.render(area, buf);
@@ -86,11 +112,15 @@ impl Component for LogsState {
         let mut total_height = 0;
         let mut wrapped_lines = Vec::new();
 
-        for log in &self.logs {
+        for (level, log) in self
+            .logs
+            .iter()
+            .filter(|(level, _)| *level >= self.min_level)
+        {
             let lines = textwrap::wrap(log, width);
             total_height += lines.len();
             for line in lines {
-                wrapped_lines.push(line.to_string());
+                wrapped_lines.push((*level, line.to_string()));
             }
         }
 
@@ -113,14 +143,15 @@ impl Component for LogsState {
 
         let styled_lines: Vec<Line> = wrapped_lines
             .iter()
-            .map(|line| {
+            .map(|(level, line)| {
                 let theme = get_theme();
-                let style = if line.contains("ERROR") {
-                    Style::default().fg(theme.status_failed)
-                } else if line.contains("completed") || line.contains("Success") {
-                    Style::default().fg(theme.status_completed)
-                } else {
-                    Style::default().fg(theme.text_primary)
+                let style = match level {
+                    LogLevel::Error => Style::default().fg(theme.status_failed),
+                    LogLevel::Warn => Style::default().fg(theme.text_highlight),
+                    _ if line.contains("completed") || line.contains("Success") => {
+                        Style::default().fg(theme.status_completed)
+                    }
+                    _ => Style::default().fg(theme.text_primary),
                 };
                 Line::from(Span::styled(line.clone(), style))
             })
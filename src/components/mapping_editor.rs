@@ -1,7 +1,9 @@
 use super::Component;
+use crate::config::Config;
 use crate::event::AppMsg;
 use crate::model::mapping::CursorMapping;
-use crate::widgets::common::focused_block;
+use crate::model::matcher::{DEFAULT_MIN_MATCH_SCORE, find_best_match, score_match};
+use crate::widgets::common::{centered_rect, focused_block};
 use crate::widgets::theme::get_theme;
 use crossbeam_channel::Sender;
 use crossterm::event::{KeyCode, KeyEvent};
@@ -12,73 +14,41 @@ use ratatui::{
     text::{Line, Span},
     widgets::{
         Block, Borders, Clear, List, ListItem, ListState, Paragraph, Scrollbar,
-        ScrollbarOrientation, ScrollbarState, StatefulWidget, Widget,
+        ScrollbarOrientation, ScrollbarState, StatefulWidget, Widget, Wrap,
     },
 };
-
-// Scores how well a source name matches a target standard name.
-fn score_match(source: &str, target: &str) -> Option<usize> {
-    let source_lower = source.to_lowercase();
-    let target_lower = target.to_lowercase();
-
-    let source_words: Vec<&str> = source_lower
-        .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
-        .filter(|w| w.len() >= 2)
-        .collect();
-
-    let target_words: Vec<&str> = target_lower
-        .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
-        .filter(|w| w.len() >= 2)
-        .collect();
-
-    let mut total_score = 0usize;
-    let mut matched_any = false;
-
-    for target_word in &target_words {
-        let mut best_word_score = 0usize;
-
-        for source_word in &source_words {
-            let score = if source_word == target_word {
-                // Exact match, highest priority
-                target_word.len() * 10
-            } else if source_word.starts_with(target_word) || target_word.starts_with(source_word) {
-                // Prefix match, one starts with the other
-                // Score based on the length of the shorter (matched) portion
-                let common_len = source_word.len().min(target_word.len());
-                common_len * 5
-            } else if source_word.contains(target_word) || target_word.contains(source_word) {
-                // Substring match
-                let common_len = source_word.len().min(target_word.len());
-                common_len * 2
-            } else {
-                0
-            };
-
-            best_word_score = best_word_score.max(score);
-        }
-
-        if best_word_score > 0 {
-            matched_any = true;
-            total_score += best_word_score;
-        }
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+// Indices of `sources` matching a typed query, best first. Prefix hits beat substring hits,
+// and `score_match` lets multi-word queries like "help sel" match "Help Select".
+fn filter_sources(sources: &[String], query: &str) -> Vec<usize> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return (0..sources.len()).collect();
     }
 
-    if matched_any { Some(total_score) } else { None }
-}
-
-// Finds the best matching source for a given target name.
-// Returns the source with the highest score, preferring shorter names on ties.
-fn find_best_match<'a>(sources: &'a [String], target: &str) -> Option<&'a String> {
-    sources
+    let mut ranked: Vec<(usize, usize)> = sources
         .iter()
-        .filter_map(|source| score_match(source, target).map(|score| (source, score)))
-        .max_by(|(src_a, score_a), (src_b, score_b)| {
-            // Compare by score, then prefer shorter source names
-            score_a
-                .cmp(score_b)
-                .then_with(|| src_b.len().cmp(&src_a.len()))
+        .enumerate()
+        .filter_map(|(i, source)| {
+            let lower = source.to_lowercase();
+            let substring = match lower.find(&query) {
+                Some(0) => query.len() * 20,
+                Some(_) => query.len() * 10,
+                None => 0,
+            };
+            let score = substring + score_match(source, &query).unwrap_or(0);
+            (score > 0).then_some((i, score))
         })
-        .map(|(source, _)| source)
+        .collect();
+
+    ranked.sort_by(|(a, score_a), (b, score_b)| {
+        score_b
+            .cmp(score_a)
+            .then_with(|| sources[*a].len().cmp(&sources[*b].len()))
+    });
+    ranked.into_iter().map(|(i, _)| i).collect()
 }
 
 #[derive(Default)]
@@ -92,6 +62,15 @@ pub struct MappingEditorState {
     pub list_state: ListState,
     pub scroll_state: ScrollbarState,
     pub popup_scroll_state: ScrollbarState,
+    pub show_report: bool,
+    // Destination typed into the "write mapping" popup, `Some` while it is open
+    pub save_path_input: Option<String>,
+    // X11 name typed into the "new entry" popup, `Some` while it is open
+    pub new_name_input: Option<String>,
+    // Type-to-filter query for the source popup
+    pub popup_query: String,
+    // Auto-matches scoring below this leave the role on its standard (missing) name
+    min_match_score: usize,
     default_mapping: CursorMapping,
 }
 
@@ -115,16 +94,52 @@ impl MappingEditorState {
             list_state: ListState::default(),
             scroll_state: ScrollbarState::default(),
             popup_scroll_state: ScrollbarState::default(),
+            show_report: false,
+            save_path_input: None,
+            new_name_input: None,
+            popup_query: String::new(),
+            min_match_score: DEFAULT_MIN_MATCH_SCORE,
             default_mapping: CursorMapping::default(),
         }
     }
 
+    pub fn set_min_match_score(&mut self, score: usize) {
+        self.min_match_score = score;
+    }
+
+    pub fn min_match_score(&self) -> usize {
+        self.min_match_score
+    }
+
     pub fn set_available_sources(&mut self, sources: Vec<String>, tx: &Sender<AppMsg>) {
         self.available_sources = sources;
         self.available_sources.sort();
+        self.auto_match(true, tx);
+    }
 
+    /// Re-runs auto-matching against `sources` for the entries whose source is missing,
+    /// leaving every other choice alone. Returns the (x11, source) pairs that were matched.
+    pub fn rematch_missing(
+        &mut self,
+        sources: Vec<String>,
+        tx: &Sender<AppMsg>,
+    ) -> Vec<(String, String)> {
+        self.available_sources = sources;
+        self.available_sources.sort();
+        self.auto_match(false, tx)
+    }
+
+    // Matches every entry without an available source. Unmatched entries are reset to their
+    // standard name when `reset_unmatched` is set, and otherwise keep their current one.
+    fn auto_match(&mut self, reset_unmatched: bool, tx: &Sender<AppMsg>) -> Vec<(String, String)> {
+        let mut matched = Vec::new();
         if !self.available_sources.is_empty() {
             for (x11_name, win_name) in &mut self.mappings_list {
+                // Keep choices from a saved mapping that still resolve to a real source
+                if self.available_sources.contains(win_name) {
+                    continue;
+                }
+
                 let standard_win_name = self
                     .default_mapping
                     .x11_to_win
@@ -132,9 +147,11 @@ impl MappingEditorState {
                     .cloned()
                     .unwrap_or_else(|| "Normal".to_string());
 
-                if let Some(matched_source) =
-                    find_best_match(&self.available_sources, &standard_win_name)
-                {
+                if let Some(matched_source) = find_best_match(
+                    &self.available_sources,
+                    &standard_win_name,
+                    self.min_match_score,
+                ) {
                     tx.send(AppMsg::LogMessage(format!(
                         "Matched {} (std: {}) -> {}",
                         x11_name, standard_win_name, matched_source
@@ -143,43 +160,277 @@ impl MappingEditorState {
 
                     *win_name = matched_source.clone();
                     self.mapping.set_mapping(x11_name.clone(), win_name.clone());
-                } else {
+                    matched.push((x11_name.clone(), win_name.clone()));
+                } else if reset_unmatched {
                     // No match found, keep the standard name (will show as Missing)
                     *win_name = standard_win_name;
                     self.mapping.set_mapping(x11_name.clone(), win_name.clone());
                 }
             }
         }
+        matched
+    }
+
+    /// Roots whose mapped source is not among the available sources and will fall back
+    pub fn missing_sources(&self) -> Vec<(String, String)> {
+        self.mappings_list
+            .iter()
+            .filter(|(_, win_name)| !self.available_sources.contains(win_name))
+            .cloned()
+            .collect()
+    }
+
+    /// Available sources that no X11 name is mapped to, in scan order
+    pub fn unused_sources(&self) -> Vec<String> {
+        let used: HashSet<&str> = self
+            .mappings_list
+            .iter()
+            .map(|(_, win_name)| win_name.as_str())
+            .collect();
+        self.available_sources
+            .iter()
+            .filter(|source| !used.contains(source.as_str()))
+            .cloned()
+            .collect()
+    }
+
+    fn unused_sources_summary(&self) -> String {
+        let unused = self.unused_sources();
+        if unused.is_empty() {
+            "Every source file is used by the mapping".to_string()
+        } else {
+            format!(
+                "{} source files are not mapped to any cursor: {}",
+                unused.len(),
+                unused.join(", ")
+            )
+        }
+    }
+
+    fn validation_summary(&self) -> String {
+        let missing = self.missing_sources();
+        let mut summary = if missing.is_empty() {
+            "Mapping check: all mapped sources are available".to_string()
+        } else {
+            let roots: Vec<&str> = missing.iter().map(|(x11, _)| x11.as_str()).collect();
+            format!(
+                "Mapping check: {} roots will fall back: {}",
+                missing.len(),
+                roots.join(", ")
+            )
+        };
+
+        let conflicts = self.mapping.validate();
+        if !conflicts.is_empty() {
+            let described: Vec<String> = conflicts.iter().map(|c| c.to_string()).collect();
+            summary.push_str(&format!(
+                "; {} conflicts: {}",
+                conflicts.len(),
+                described.join("; ")
+            ));
+        }
+        summary
+    }
+
+    /// Whether a popup is open and should receive every key, including `q`
+    pub fn has_popup(&self) -> bool {
+        self.show_popup
+            || self.show_report
+            || self.save_path_input.is_some()
+            || self.new_name_input.is_some()
+    }
+
+    fn popup_matches(&self) -> Vec<usize> {
+        filter_sources(&self.available_sources, &self.popup_query)
+    }
+
+    fn set_popup_query(&mut self, query: String) {
+        self.popup_query = query;
+        let first = if self.popup_matches().is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+        self.popup_state.select(first);
+        self.popup_scroll_state = self.popup_scroll_state.position(0);
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected_index = index.min(self.mappings_list.len().saturating_sub(1));
+        self.list_state.select(Some(self.selected_index));
+        self.scroll_state = self.scroll_state.position(self.selected_index);
+    }
+
+    fn add_entry(&mut self, x11_name: &str) -> Option<AppMsg> {
+        let x11_name = x11_name.trim();
+        if x11_name.is_empty() {
+            return None;
+        }
+        if x11_name.contains(|c: char| c.is_whitespace() || c == '/') {
+            return Some(AppMsg::LogMessage(format!(
+                "Invalid cursor name '{}'",
+                x11_name
+            )));
+        }
+        if self.mapping.x11_to_win.contains_key(x11_name) {
+            return Some(AppMsg::LogMessage(format!(
+                "{} is already mapped",
+                x11_name
+            )));
+        }
+
+        let win_name = self
+            .default_mapping
+            .get_win_name(x11_name)
+            .cloned()
+            .unwrap_or_else(|| "Normal".to_string());
+
+        self.mapping
+            .set_mapping(x11_name.to_string(), win_name.clone());
+        self.mappings_list
+            .push((x11_name.to_string(), win_name.clone()));
+        self.mappings_list.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let index = self
+            .mappings_list
+            .iter()
+            .position(|(name, _)| name == x11_name)
+            .unwrap_or(0);
+        self.select(index);
+
+        Some(AppMsg::MappingChanged(x11_name.to_string(), Some(win_name)))
+    }
+
+    fn remove_selected(&mut self) -> Option<AppMsg> {
+        if self.selected_index >= self.mappings_list.len() {
+            return None;
+        }
+
+        let (x11_name, _) = self.mappings_list.remove(self.selected_index);
+        self.mapping.x11_to_win.remove(&x11_name);
+        self.mapping.symlinks.remove(&x11_name);
+        self.select(self.selected_index);
+
+        Some(AppMsg::MappingChanged(x11_name, None))
+    }
+
+    fn handle_new_name_input(&mut self, key: KeyEvent) -> Option<AppMsg> {
+        let input = self.new_name_input.as_mut()?;
+
+        match key.code {
+            KeyCode::Esc => {
+                self.new_name_input = None;
+                None
+            }
+            KeyCode::Backspace => {
+                input.pop();
+                None
+            }
+            KeyCode::Char(c) => {
+                input.push(c);
+                None
+            }
+            KeyCode::Enter => {
+                let input = self.new_name_input.take().unwrap_or_default();
+                self.add_entry(&input)
+            }
+            _ => None,
+        }
+    }
+
+    fn write_mapping(&self, input: &str) -> AppMsg {
+        let input = input.trim();
+        let path = match input.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir()
+                .map(|home| home.join(rest))
+                .unwrap_or_else(|| PathBuf::from(input)),
+            None => PathBuf::from(input),
+        };
+
+        match self.mapping.save_to_file(&path) {
+            Ok(()) => AppMsg::LogMessage(format!("Mapping written to {}", path.display())),
+            Err(e) => AppMsg::LogMessage(format!(
+                "Failed to write mapping to {}: {}",
+                path.display(),
+                e
+            )),
+        }
+    }
+
+    fn handle_save_path_input(&mut self, key: KeyEvent) -> Option<AppMsg> {
+        let input = self.save_path_input.as_mut()?;
+
+        match key.code {
+            KeyCode::Esc => {
+                self.save_path_input = None;
+                None
+            }
+            KeyCode::Backspace => {
+                input.pop();
+                None
+            }
+            KeyCode::Char(c) => {
+                input.push(c);
+                None
+            }
+            KeyCode::Enter => {
+                let input = self.save_path_input.take().unwrap_or_default();
+                if input.trim().is_empty() {
+                    return None;
+                }
+                Some(self.write_mapping(&input))
+            }
+            _ => None,
+        }
     }
 
     fn handle_key(&mut self, key: KeyEvent) -> Option<AppMsg> {
+        if self.save_path_input.is_some() {
+            return self.handle_save_path_input(key);
+        }
+
+        if self.new_name_input.is_some() {
+            return self.handle_new_name_input(key);
+        }
+
+        if self.show_report {
+            // Any key dismisses the report
+            self.show_report = false;
+            return None;
+        }
+
         if self.show_popup {
+            let matches = self.popup_matches();
             match key.code {
                 KeyCode::Enter => {
-                    if let Some(idx) = self.popup_state.selected()
-                        && idx < self.available_sources.len()
+                    if let Some(&source_idx) =
+                        self.popup_state.selected().and_then(|idx| matches.get(idx))
                     {
                         let x11_name = self.mappings_list[self.selected_index].0.clone();
-                        let new_win_name = self.available_sources[idx].clone();
+                        let new_win_name = self.available_sources[source_idx].clone();
 
                         self.mapping
                             .set_mapping(x11_name.clone(), new_win_name.clone());
                         self.mappings_list[self.selected_index].1 = new_win_name.clone();
                         self.show_popup = false;
-                        return Some(AppMsg::MappingChanged(x11_name, new_win_name));
+                        return Some(AppMsg::MappingChanged(x11_name, Some(new_win_name)));
                     }
                     self.show_popup = false;
                     None
                 }
                 KeyCode::Esc => {
-                    self.show_popup = false;
+                    if self.popup_query.is_empty() {
+                        self.show_popup = false;
+                    } else {
+                        self.set_popup_query(String::new());
+                    }
                     None
                 }
-                KeyCode::Up | KeyCode::Char('k') => {
+                KeyCode::Up => {
                     let i = match self.popup_state.selected() {
                         Some(i) => {
                             if i == 0 {
-                                self.available_sources.len().saturating_sub(1)
+                                matches.len().saturating_sub(1)
                             } else {
                                 i - 1
                             }
@@ -190,10 +441,10 @@ impl MappingEditorState {
                     self.popup_scroll_state = self.popup_scroll_state.position(i);
                     None
                 }
-                KeyCode::Down | KeyCode::Char('j') => {
+                KeyCode::Down => {
                     let i = match self.popup_state.selected() {
                         Some(i) => {
-                            if i >= self.available_sources.len().saturating_sub(1) {
+                            if i >= matches.len().saturating_sub(1) {
                                 0
                             } else {
                                 i + 1
@@ -205,6 +456,18 @@ impl MappingEditorState {
                     self.popup_scroll_state = self.popup_scroll_state.position(i);
                     None
                 }
+                KeyCode::Backspace => {
+                    let mut query = self.popup_query.clone();
+                    query.pop();
+                    self.set_popup_query(query);
+                    None
+                }
+                KeyCode::Char(c) => {
+                    let mut query = self.popup_query.clone();
+                    query.push(c);
+                    self.set_popup_query(query);
+                    None
+                }
                 _ => None,
             }
         } else {
@@ -228,6 +491,7 @@ impl MappingEditorState {
                 KeyCode::Enter | KeyCode::Char('e') => {
                     if self.selected_index < self.mappings_list.len() {
                         self.show_popup = true;
+                        self.popup_query.clear();
                         let current_val = &self.mappings_list[self.selected_index].1;
                         // Find current selection in available sources
                         let initial_idx = self
@@ -244,12 +508,139 @@ impl MappingEditorState {
                     None
                 }
                 KeyCode::Char('s') => Some(AppMsg::MappingSaved),
+                KeyCode::Char('n') => {
+                    self.new_name_input = Some(String::new());
+                    None
+                }
+                KeyCode::Char('d') => self.remove_selected(),
+                KeyCode::Char('w') => {
+                    let default_path = Config::mapping_path()
+                        .map(|path| path.display().to_string())
+                        .unwrap_or_default();
+                    self.save_path_input = Some(default_path);
+                    None
+                }
+                KeyCode::Char('v') => {
+                    self.show_report = true;
+                    Some(AppMsg::LogMessage(self.validation_summary()))
+                }
+                KeyCode::Char('u') => Some(AppMsg::LogMessage(self.unused_sources_summary())),
+                KeyCode::Char('m') => Some(AppMsg::RematchMissing),
                 _ => None,
             }
         }
     }
 }
 
+impl MappingEditorState {
+    fn render_text_input(&self, area: Rect, buf: &mut Buffer) {
+        let (title, input, confirm) = if let Some(input) = &self.save_path_input {
+            ("Write Mapping (TOML)", input, "Enter: Write")
+        } else if let Some(input) = &self.new_name_input {
+            ("New X11 Cursor Name", input, "Enter: Add")
+        } else {
+            return;
+        };
+        let theme = get_theme();
+
+        let popup_area = centered_rect(80, 30, area);
+        Clear.render(popup_area, buf);
+
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border_focused));
+
+        let lines = vec![
+            Line::from(vec![
+                Span::styled("> ", Style::default().fg(theme.text_highlight)),
+                Span::styled(
+                    format!("{}_", input),
+                    Style::default()
+                        .fg(theme.text_primary)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::from(Span::styled(
+                format!("{} | Esc: Cancel", confirm),
+                Style::default().fg(theme.text_secondary),
+            )),
+        ];
+
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .render(popup_area, buf);
+    }
+
+    fn render_report(&self, area: Rect, buf: &mut Buffer) {
+        let theme = get_theme();
+        let missing = self.missing_sources();
+        let conflicts = self.mapping.validate();
+
+        let popup_area = centered_rect(60, 60, area);
+        Clear.render(popup_area, buf);
+
+        let title = format!(
+            "Mapping Check ({} missing, {} conflicts)",
+            missing.len(),
+            conflicts.len()
+        );
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border_focused));
+
+        let has_normal = self.available_sources.iter().any(|s| s == "Normal");
+        let fallback = if has_normal { "Normal" } else { "nothing" };
+
+        let mut lines: Vec<Line> = if missing.is_empty() {
+            vec![Line::from(Span::styled(
+                "All mapped sources are available",
+                Style::default().fg(theme.status_completed),
+            ))]
+        } else {
+            missing
+                .iter()
+                .map(|(x11_name, win_name)| {
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{:<20}", x11_name),
+                            Style::default().fg(theme.text_primary),
+                        ),
+                        Span::raw(" ← "),
+                        Span::styled(
+                            format!("{} (falls back to {})", win_name, fallback),
+                            Style::default().fg(theme.status_failed),
+                        ),
+                    ])
+                })
+                .collect()
+        };
+
+        if !conflicts.is_empty() {
+            lines.push(Line::from(""));
+            lines.extend(conflicts.iter().map(|conflict| {
+                Line::from(Span::styled(
+                    format!("Conflict: {}", conflict),
+                    Style::default().fg(theme.status_failed),
+                ))
+            }));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Press any key to close",
+            Style::default().fg(theme.text_secondary),
+        )));
+
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .render(popup_area, buf);
+    }
+}
+
 impl Component for MappingEditorState {
     fn update(&mut self, msg: &AppMsg) -> Option<AppMsg> {
         match msg {
@@ -430,18 +821,26 @@ impl Component for MappingEditorState {
             let popup_area = centered_rect(60, 50, area);
             Clear.render(popup_area, buf);
 
+            let title = if self.popup_query.is_empty() {
+                "Select Source (type to filter)".to_string()
+            } else {
+                format!("Select Source [{}]", self.popup_query)
+            };
             let block = Block::default()
-                .title("Select Source")
+                .title(title)
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(theme.border_focused));
 
             let inner_popup = block.inner(popup_area);
             block.render(popup_area, buf);
 
-            let items: Vec<ListItem> = self
-                .available_sources
+            let matches = self.popup_matches();
+            let items: Vec<ListItem> = matches
                 .iter()
-                .map(|s| ListItem::new(s.as_str()).style(Style::default().fg(theme.text_primary)))
+                .map(|&i| {
+                    ListItem::new(self.available_sources[i].as_str())
+                        .style(Style::default().fg(theme.text_primary))
+                })
                 .collect();
 
             let list = List::new(items).highlight_style(
@@ -453,9 +852,7 @@ impl Component for MappingEditorState {
 
             ratatui::widgets::StatefulWidget::render(list, inner_popup, buf, &mut self.popup_state);
 
-            self.popup_scroll_state = self
-                .popup_scroll_state
-                .content_length(self.available_sources.len());
+            self.popup_scroll_state = self.popup_scroll_state.content_length(matches.len());
             let popup_scrollbar = Scrollbar::default()
                 .orientation(ScrollbarOrientation::VerticalRight)
                 .begin_symbol(Some("▲"))
@@ -463,25 +860,134 @@ impl Component for MappingEditorState {
 
             popup_scrollbar.render(inner_popup, buf, &mut self.popup_scroll_state);
         }
+
+        if self.show_report {
+            self.render_report(area, buf);
+        }
+        self.render_text_input(area, buf);
     }
 }
 
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
-    let popup_layout = Layout::default()
-        .direction(ratatui::layout::Direction::Vertical)
-        .constraints([
-            Constraint::Percentage((100 - percent_y) / 2),
-            Constraint::Percentage(percent_y),
-            Constraint::Percentage((100 - percent_y) / 2),
-        ])
-        .split(r);
-
-    Layout::default()
-        .direction(ratatui::layout::Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage((100 - percent_x) / 2),
-            Constraint::Percentage(percent_x),
-            Constraint::Percentage((100 - percent_x) / 2),
-        ])
-        .split(popup_layout[1])[1]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam_channel::unbounded;
+    use crossterm::event::KeyModifiers;
+
+    fn press(editor: &mut MappingEditorState, code: KeyCode) -> Option<AppMsg> {
+        editor.update(&AppMsg::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+    }
+
+    fn mapping_of(pairs: &[(&str, &str)]) -> CursorMapping {
+        CursorMapping {
+            x11_to_win: pairs
+                .iter()
+                .map(|(x11, win)| (x11.to_string(), win.to_string()))
+                .collect(),
+            ..CursorMapping::default()
+        }
+    }
+
+    fn sources(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_filter_sources_keeps_all_for_empty_query() {
+        let sources = sources(&["Normal", "Help", "Link"]);
+        assert_eq!(filter_sources(&sources, "  "), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_filter_sources_ranks_prefix_before_substring() {
+        let sources = sources(&["Unlink", "Help", "Link"]);
+        assert_eq!(filter_sources(&sources, "LINK"), vec![2, 0]);
+    }
+
+    #[test]
+    fn test_filter_sources_matches_multiple_words() {
+        let sources = sources(&["Busy", "Help", "Help Select"]);
+        assert_eq!(filter_sources(&sources, "help sel"), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_adding_an_entry_reports_the_change() {
+        let mut editor = MappingEditorState::new(mapping_of(&[("left_ptr", "Normal")]));
+
+        press(&mut editor, KeyCode::Char('n'));
+        for c in "zoom-in".chars() {
+            press(&mut editor, KeyCode::Char(c));
+        }
+        let msg = press(&mut editor, KeyCode::Enter);
+
+        let Some(AppMsg::MappingChanged(x11_name, Some(win_name))) = msg else {
+            panic!("expected MappingChanged, got {msg:?}");
+        };
+        assert_eq!(x11_name, "zoom-in");
+        assert_eq!(editor.mapping.get_win_name("zoom-in"), Some(&win_name));
+        assert_eq!(editor.mappings_list[editor.selected_index].0, "zoom-in");
+    }
+
+    #[test]
+    fn test_removing_an_entry_reports_the_change() {
+        let mut mapping = mapping_of(&[("left_ptr", "Normal"), ("text", "Text")]);
+        mapping
+            .symlinks
+            .insert("text".to_string(), vec!["xterm".to_string()]);
+        let mut editor = MappingEditorState::new(mapping);
+        editor.select(1);
+
+        let msg = press(&mut editor, KeyCode::Char('d'));
+
+        assert!(
+            matches!(&msg, Some(AppMsg::MappingChanged(name, None)) if name == "text"),
+            "{msg:?}"
+        );
+        assert!(editor.mapping.get_win_name("text").is_none());
+        assert!(!editor.mapping.symlinks.contains_key("text"));
+        // The selection moves back onto the remaining entry
+        assert_eq!(editor.selected_index, 0);
+    }
+
+    #[test]
+    fn test_rematch_missing_keeps_resolved_choices() {
+        let mapping = CursorMapping {
+            x11_to_win: [
+                ("left_ptr", "My Arrow"),
+                ("pointer", "My Arrow"),
+                ("text", "Text"),
+            ]
+            .into_iter()
+            .map(|(x11, win)| (x11.to_string(), win.to_string()))
+            .collect(),
+            ..CursorMapping::default()
+        };
+        let mut editor = MappingEditorState::new(mapping);
+        let (tx, _rx) = unbounded();
+
+        editor.set_available_sources(vec!["My Arrow".to_string()], &tx);
+        assert_eq!(
+            editor.missing_sources(),
+            vec![("text".to_string(), "Text".to_string())]
+        );
+
+        let matched = editor.rematch_missing(
+            vec![
+                "My Arrow".to_string(),
+                "Person".to_string(),
+                "Text Select".to_string(),
+            ],
+            &tx,
+        );
+        assert_eq!(
+            matched,
+            vec![("text".to_string(), "Text Select".to_string())]
+        );
+        assert!(editor.missing_sources().is_empty());
+        // `pointer` resolved before and keeps the user's choice over its standard source
+        assert_eq!(
+            editor.mapping.get_win_name("pointer"),
+            Some(&"My Arrow".to_string())
+        );
+    }
 }
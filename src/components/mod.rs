@@ -1,11 +1,13 @@
 use crate::event::AppMsg;
 use ratatui::{buffer::Buffer, layout::Rect};
 
+pub mod confirm;
 pub mod file_browser;
 pub mod hotspot_editor;
 pub mod logs;
 pub mod mapping_editor;
 pub mod preview;
+pub mod results;
 pub mod runner;
 pub mod settings;
 pub mod theme_overrides;
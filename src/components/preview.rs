@@ -3,15 +3,21 @@ use imageproc::drawing::draw_line_segment_mut;
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
-    style::Style,
+    style::{Color, Style},
     text::Line,
     widgets::{Paragraph, StatefulWidget, Widget},
 };
-use ratatui_image::{StatefulImage, picker::Picker, protocol::StatefulProtocol};
+use ratatui_image::{
+    StatefulImage,
+    picker::{Picker, ProtocolType},
+    protocol::StatefulProtocol,
+};
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 
+use crate::config::{DEFAULT_CACHE_CAPACITY, DEFAULT_GRID_MIN_SCALE};
 use crate::model::cursor::{CursorMeta, Frame, SizeVariant};
+use crate::pipeline::win2xcur::utils::{ShadowConfig, apply_shadow_to_image};
 use crate::widgets::theme::get_theme;
 
 pub type PreviewData<'a> = (
@@ -28,60 +34,358 @@ pub type PreviewData<'a> = (
 struct BaseImageData {
     canvas: RgbaImage,
     scale: f32,
-    offset_x: u32,
-    offset_y: u32,
+    offset_x: f32,
+    offset_y: f32,
+}
+
+// Encoded protocol plus the transform its canvas was drawn with
+struct CachedProtocol {
+    protocol: StatefulProtocol,
+    scale: f32,
+    offset_x: f32,
+    offset_y: f32,
+}
+
+// Where the last frame landed on screen, for mapping mouse clicks back to cursor pixels
+#[derive(Clone, Copy)]
+struct RenderedView {
+    area: Rect,
+    font_size: (u16, u16),
+    scale: f32,
+    offset_x: f32,
+    offset_y: f32,
+}
+
+pub const MAX_ZOOM: u32 = 8;
+const LOW_FIDELITY_NOTICE: &str = "Terminal image protocol unavailable; preview approximate";
+
+// String-keyed map that evicts the least recently used entry once over capacity.
+// Capacities stay small, so a linear scan on eviction is cheaper than extra bookkeeping.
+struct LruMap<V> {
+    entries: HashMap<String, (V, u64)>,
+    capacity: usize,
+    clock: u64,
+}
+
+impl<V> LruMap<V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity: capacity.max(1),
+            clock: 0,
+        }
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn contains_key(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    fn get(&mut self, key: &str) -> Option<&V> {
+        self.get_mut(key).map(|value| &*value)
+    }
+
+    fn get_mut(&mut self, key: &str) -> Option<&mut V> {
+        let now = self.tick();
+        self.entries.get_mut(key).map(|(value, used)| {
+            *used = now;
+            value
+        })
+    }
+
+    // The entry being inserted is the newest, so it is never the one evicted
+    fn insert(&mut self, key: String, value: V) {
+        let now = self.tick();
+        self.entries.insert(key, (value, now));
+        self.evict();
+    }
+
+    fn evict(&mut self) {
+        while self.entries.len() > self.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(key) => self.entries.remove(&key),
+                None => break,
+            };
+        }
+    }
+
+    fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        self.evict();
+    }
+
+    fn retain(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        self.entries.retain(|key, _| keep(key));
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+    }
 }
 
 pub struct PreviewState {
     pub picker: Arc<Mutex<Picker>>,
-    base_cache: HashMap<String, BaseImageData>,
+    base_cache: LruMap<BaseImageData>,
     // Cache for final encoded protocols: "path|WxH|hx,hy" -> ready to render
-    protocol_cache: HashMap<String, StatefulProtocol>,
+    protocol_cache: LruMap<CachedProtocol>,
+    // Paths that could not be decoded, so we don't retry every frame
+    failed_paths: HashSet<String>,
+    // Magnification on top of fit-to-pane; above 1 the view follows the hotspot
+    pub zoom: u32,
+    // Full-canvas guide lines through the hotspot
+    pub crosshair: bool,
+    // Drop shadow from Settings, drawn under the frame while `show_shadow` is on
+    shadow: Option<ShadowConfig>,
+    pub show_shadow: bool,
+    // Pixel grid, drawn once an image pixel covers at least `grid_min_scale` canvas pixels
+    pub show_grid: bool,
+    grid_min_scale: f32,
+    // Color the cached canvases were drawn with, follows the UI theme
+    grid_color: Rgba<u8>,
+    // Light/dark squares behind the image, to judge transparent and semi-transparent pixels
+    pub show_checkerboard: bool,
+    last_view: Option<RenderedView>,
 }
 
 impl PreviewState {
     pub fn new(picker: Arc<Mutex<Picker>>) -> Self {
         Self {
             picker,
-            base_cache: HashMap::new(),
-            protocol_cache: HashMap::new(),
+            base_cache: LruMap::new(DEFAULT_CACHE_CAPACITY),
+            protocol_cache: LruMap::new(DEFAULT_CACHE_CAPACITY),
+            failed_paths: HashSet::new(),
+            zoom: 1,
+            crosshair: false,
+            shadow: None,
+            show_shadow: false,
+            show_grid: true,
+            grid_min_scale: DEFAULT_GRID_MIN_SCALE,
+            grid_color: Self::theme_grid_color(),
+            show_checkerboard: false,
+            last_view: None,
+        }
+    }
+
+    /// Steps the zoom by `delta` within 1..=MAX_ZOOM; returns whether it changed
+    pub fn change_zoom(&mut self, delta: i32) -> bool {
+        let zoom = (self.zoom as i32 + delta).clamp(1, MAX_ZOOM as i32) as u32;
+        if zoom == self.zoom {
+            return false;
+        }
+        self.zoom = zoom;
+        // Every cached canvas was laid out for the old zoom
+        self.base_cache.clear();
+        self.protocol_cache.clear();
+        true
+    }
+
+    /// Bounds how many decoded canvases and encoded protocols are kept around
+    pub fn set_cache_capacity(&mut self, capacity: usize) {
+        self.base_cache.set_capacity(capacity);
+        self.protocol_cache.set_capacity(capacity);
+    }
+
+    /// Cursor pixel under a terminal cell of the last rendered frame, if the cell shows it
+    pub fn pixel_at(&self, column: u16, row: u16) -> Option<(u32, u32)> {
+        let view = self.last_view?;
+        let area = view.area;
+        if column < area.x
+            || row < area.y
+            || column >= area.x + area.width
+            || row >= area.y + area.height
+        {
+            return None;
+        }
+
+        // Aim for the middle of the cell
+        let (font_w, font_h) = view.font_size;
+        let px = (column - area.x) as f32 * font_w as f32 + font_w as f32 / 2.0;
+        let py = (row - area.y) as f32 * font_h as f32 + font_h as f32 / 2.0;
+        let x = (px - view.offset_x) / view.scale;
+        let y = (py - view.offset_y) / view.scale;
+        if x < 0.0 || y < 0.0 {
+            return None;
+        }
+        Some((x as u32, y as u32))
+    }
+
+    /// Whether the terminal fell back to half-block cells, which can't show single pixels
+    pub fn low_fidelity(&self) -> bool {
+        self.picker
+            .lock()
+            .map(|picker| picker.protocol_type() == ProtocolType::Halfblocks)
+            .unwrap_or(false)
+    }
+
+    pub fn cache_capacity(&self) -> usize {
+        self.protocol_cache.capacity
+    }
+
+    pub fn toggle_crosshair(&mut self) {
+        self.crosshair = !self.crosshair;
+        self.protocol_cache.clear();
+    }
+
+    /// Updates the shadow config the preview draws with when `show_shadow` is on
+    pub fn set_shadow(&mut self, shadow: Option<ShadowConfig>) {
+        if self.shadow == shadow {
+            return;
+        }
+        self.shadow = shadow;
+        if self.show_shadow {
+            self.protocol_cache.clear();
+        }
+    }
+
+    pub fn toggle_grid(&mut self) {
+        self.show_grid = !self.show_grid;
+        // The grid is baked into the base canvases
+        self.base_cache.clear();
+        self.protocol_cache.clear();
+    }
+
+    pub fn toggle_checkerboard(&mut self) {
+        self.show_checkerboard = !self.show_checkerboard;
+        // Baked into the base canvases like the grid
+        self.base_cache.clear();
+        self.protocol_cache.clear();
+    }
+
+    pub fn grid_min_scale(&self) -> f32 {
+        self.grid_min_scale
+    }
+
+    pub fn set_grid_min_scale(&mut self, scale: f32) {
+        let scale = scale.max(1.0);
+        if scale != self.grid_min_scale {
+            self.grid_min_scale = scale;
+            self.base_cache.clear();
+            self.protocol_cache.clear();
+        }
+    }
+
+    // Secondary text color of the UI theme, so the grid stands out on light and dark themes
+    fn theme_grid_color() -> Rgba<u8> {
+        match get_theme().text_secondary {
+            Color::Rgb(r, g, b) => Rgba([r, g, b, 140]),
+            _ => Rgba([128, 128, 128, 100]),
         }
     }
 
-    fn base_key(path: &str, target_size: (u32, u32)) -> String {
-        format!("{}|{}x{}", path, target_size.0, target_size.1)
+    pub fn toggle_shadow(&mut self) {
+        self.show_shadow = !self.show_shadow;
+        self.protocol_cache.clear();
+    }
+
+    fn active_shadow(&self) -> Option<&ShadowConfig> {
+        self.shadow.as_ref().filter(|_| self.show_shadow)
+    }
+
+    fn base_key(
+        path: &str,
+        target_size: (u32, u32),
+        zoom: u32,
+        focus: (u32, u32),
+        shadow: Option<&ShadowConfig>,
+    ) -> String {
+        let shadow = shadow.map_or_else(
+            || "none".to_string(),
+            |s| {
+                format!(
+                    "{:?}/{}/{}/{}/{}/{}",
+                    s.color, s.opacity, s.x_offset, s.y_offset, s.sigma, s.radius
+                )
+            },
+        );
+        format!(
+            "{}|{}x{}|{}@{},{}|{}",
+            path, target_size.0, target_size.1, zoom, focus.0, focus.1, shadow
+        )
     }
 
-    fn proto_key(path: &str, target_size: (u32, u32), hotspot: (u32, u32)) -> String {
+    fn proto_key(
+        path: &str,
+        target_size: (u32, u32),
+        hotspot: (u32, u32),
+        crosshair: bool,
+    ) -> String {
         format!(
-            "{}|{}x{}|{},{}",
-            path, target_size.0, target_size.1, hotspot.0, hotspot.1
+            "{}|{}x{}|{},{}|{}",
+            path, target_size.0, target_size.1, hotspot.0, hotspot.1, crosshair
         )
     }
 
-    fn process_base_image(path: &str, target_size: (u32, u32)) -> Option<BaseImageData> {
-        let img = image::open(path).ok()?;
+    // Places a `len`-pixel image on a `canvas`-pixel axis: centered when it fits, otherwise
+    // shifted to keep `focus` in the middle without leaving empty space at either edge
+    fn axis_offset(canvas: u32, len: f32, focus: f32) -> f32 {
+        if len <= canvas as f32 {
+            return ((canvas as f32 - len) / 2.0).floor();
+        }
+        (canvas as f32 / 2.0 - focus)
+            .clamp(canvas as f32 - len, 0.0)
+            .floor()
+    }
+
+    fn process_base_image(
+        path: &str,
+        target_size: (u32, u32),
+        zoom: u32,
+        focus: (u32, u32),
+        shadow: Option<&ShadowConfig>,
+        grid: Option<(f32, Rgba<u8>)>,
+        checkerboard: bool,
+    ) -> Option<BaseImageData> {
+        let mut img = image::open(path).ok()?;
+        // Shadow at source resolution, the same way the build applies it
+        if let Some(shadow) = shadow {
+            img = DynamicImage::ImageRgba8(apply_shadow_to_image(&img.to_rgba8(), shadow).ok()?);
+        }
         let (w, h) = img.dimensions();
         let (canvas_w, canvas_h) = target_size;
 
-        let scale = (canvas_w as f32 / w as f32).min(canvas_h as f32 / h as f32);
+        let scale = (canvas_w as f32 / w as f32).min(canvas_h as f32 / h as f32) * zoom as f32;
         let new_w = (w as f32 * scale) as u32;
         let new_h = (h as f32 * scale) as u32;
 
-        let resized = img.resize_exact(new_w, new_h, image::imageops::FilterType::Nearest);
+        let offset_x = Self::axis_offset(canvas_w, new_w as f32, (focus.0 as f32 + 0.5) * scale);
+        let offset_y = Self::axis_offset(canvas_h, new_h as f32, (focus.1 as f32 + 0.5) * scale);
+
+        // Only scale the source pixels that land on the canvas, zoomed images get large
+        let x0 = ((-offset_x / scale).floor().max(0.0) as u32).min(w);
+        let y0 = ((-offset_y / scale).floor().max(0.0) as u32).min(h);
+        let x1 = (((canvas_w as f32 - offset_x) / scale).ceil() as u32).clamp(x0, w);
+        let y1 = (((canvas_h as f32 - offset_y) / scale).ceil() as u32).clamp(y0, h);
+        let visible = img.crop_imm(x0, y0, x1 - x0, y1 - y0);
+        let resized = visible.resize_exact(
+            ((x1 - x0) as f32 * scale) as u32,
+            ((y1 - y0) as f32 * scale) as u32,
+            image::imageops::FilterType::Nearest,
+        );
 
         let mut canvas = RgbaImage::new(canvas_w, canvas_h);
-
-        // Center the resized image on canvas
-        let offset_x = (canvas_w - new_w) / 2;
-        let offset_y = (canvas_h - new_h) / 2;
-
-        image::imageops::overlay(&mut canvas, &resized, offset_x as i64, offset_y as i64);
+        if checkerboard {
+            Self::draw_checkerboard(&mut canvas, scale, offset_x, offset_y, new_w, new_h);
+        }
+        image::imageops::overlay(
+            &mut canvas,
+            &resized,
+            (offset_x + x0 as f32 * scale) as i64,
+            (offset_y + y0 as f32 * scale) as i64,
+        );
 
         // Draw pixel grid if scale is large enough
-        if scale >= 4.0 {
-            let grid_color = Rgba([128, 128, 128, 100]); // Semi-transparent gray
-
+        if let Some((min_scale, grid_color)) = grid
+            && scale >= min_scale
+        {
             // Vertical lines
             for i in 0..=w {
                 let mut x = (i as f32 * scale) as i32 + offset_x as i32;
@@ -93,8 +397,8 @@ impl PreviewState {
                 if x >= 0 && x < canvas_w as i32 {
                     draw_line_segment_mut(
                         &mut canvas,
-                        (x as f32, offset_y as f32),
-                        (x as f32, (offset_y + new_h) as f32),
+                        (x as f32, offset_y),
+                        (x as f32, offset_y + new_h as f32),
                         grid_color,
                     );
                 }
@@ -111,8 +415,8 @@ impl PreviewState {
                 if y >= 0 && y < canvas_h as i32 {
                     draw_line_segment_mut(
                         &mut canvas,
-                        (offset_x as f32, y as f32),
-                        ((offset_x + new_w) as f32, y as f32),
+                        (offset_x, y as f32),
+                        (offset_x + new_w as f32, y as f32),
                         grid_color,
                     );
                 }
@@ -127,17 +431,63 @@ impl PreviewState {
         })
     }
 
+    // Fills the image's area of the canvas with squares of two source pixels each, at least
+    // 4 canvas pixels wide, anchored to the image so they move with it when zoomed
+    fn draw_checkerboard(
+        canvas: &mut RgbaImage,
+        scale: f32,
+        offset_x: f32,
+        offset_y: f32,
+        width: u32,
+        height: u32,
+    ) {
+        const LIGHT: Rgba<u8> = Rgba([204, 204, 204, 255]);
+        const DARK: Rgba<u8> = Rgba([153, 153, 153, 255]);
+        let square = (scale * 2.0).max(4.0);
+
+        let x0 = offset_x.max(0.0) as u32;
+        let y0 = offset_y.max(0.0) as u32;
+        let x1 = ((offset_x + width as f32).max(0.0) as u32).min(canvas.width());
+        let y1 = ((offset_y + height as f32).max(0.0) as u32).min(canvas.height());
+        for y in y0..y1 {
+            let row = ((y as f32 - offset_y) / square) as u32;
+            for x in x0..x1 {
+                let column = ((x as f32 - offset_x) / square) as u32;
+                let color = if (row + column).is_multiple_of(2) {
+                    LIGHT
+                } else {
+                    DARK
+                };
+                canvas.put_pixel(x, y, color);
+            }
+        }
+    }
+
     fn draw_hotspot(
         canvas: &mut RgbaImage,
         hotspot: (u32, u32),
         scale: f32,
-        offset_x: u32,
-        offset_y: u32,
+        offset_x: f32,
+        offset_y: f32,
+        filled: bool,
     ) {
-        let hx = (hotspot.0 as f32 * scale) + offset_x as f32;
-        let hy = (hotspot.1 as f32 * scale) + offset_y as f32;
+        let hx = (hotspot.0 as f32 * scale) + offset_x;
+        let hy = (hotspot.1 as f32 * scale) + offset_y;
         let color = Rgba([255, 0, 0, 255]);
 
+        // A one-pixel outline disappears once the canvas is squeezed into half blocks
+        if filled {
+            let (w, h) = canvas.dimensions();
+            let x1 = ((hx + scale) as u32).min(w);
+            let y1 = ((hy + scale) as u32).min(h);
+            for y in hy.max(0.0) as u32..y1 {
+                for x in hx.max(0.0) as u32..x1 {
+                    canvas.put_pixel(x, y, color);
+                }
+            }
+            return;
+        }
+
         let box_w = scale - 1.0;
         let box_h = scale - 1.0;
 
@@ -151,19 +501,82 @@ impl PreviewState {
         draw_line_segment_mut(canvas, (hx + box_w, hy), (hx + box_w, hy + box_h), color);
     }
 
+    // Faint lines through the middle of the hotspot pixel, blended so the art stays visible
+    fn draw_crosshair(
+        canvas: &mut RgbaImage,
+        hotspot: (u32, u32),
+        scale: f32,
+        offset_x: f32,
+        offset_y: f32,
+    ) {
+        let cx = (hotspot.0 as f32 + 0.5) * scale + offset_x;
+        let cy = (hotspot.1 as f32 + 0.5) * scale + offset_y;
+        let (w, h) = canvas.dimensions();
+        let color = [0u8, 255, 255];
+        let alpha = 0.45;
+
+        let mut blend = |x: u32, y: u32| {
+            let pixel = canvas.get_pixel_mut(x, y);
+            for c in 0..3 {
+                pixel[c] = (pixel[c] as f32 * (1.0 - alpha) + color[c] as f32 * alpha) as u8;
+            }
+            pixel[3] = pixel[3].max((alpha * 255.0) as u8);
+        };
+
+        if cx >= 0.0 && (cx as u32) < w {
+            for y in 0..h {
+                blend(cx as u32, y);
+            }
+        }
+        if cy >= 0.0 && (cy as u32) < h {
+            for x in 0..w {
+                blend(x, cy as u32);
+            }
+        }
+    }
+
     fn ensure_cached(&mut self, path: &str, hotspot: (u32, u32), target_size: (u32, u32)) {
-        let proto_key = Self::proto_key(path, target_size, hotspot);
+        let proto_key = Self::proto_key(path, target_size, hotspot, self.crosshair);
 
         if self.protocol_cache.contains_key(&proto_key) {
             return;
         }
 
-        let base_key = Self::base_key(path, target_size);
+        if self.failed_paths.contains(path) {
+            return;
+        }
+
+        // Zoomed canvases are laid out around the hotspot, so they depend on it
+        let focus = if self.zoom > 1 { hotspot } else { (0, 0) };
+        let shadow = self.active_shadow().cloned();
+        let low_fidelity = self.low_fidelity();
+        let grid_color = Self::theme_grid_color();
+        if grid_color != self.grid_color {
+            // The UI theme changed since these were drawn
+            self.grid_color = grid_color;
+            self.base_cache.clear();
+            self.protocol_cache.clear();
+        }
+        let grid = (self.show_grid && !low_fidelity).then_some((self.grid_min_scale, grid_color));
+        let base_key = Self::base_key(path, target_size, self.zoom, focus, shadow.as_ref());
 
         if !self.base_cache.contains_key(&base_key) {
-            if let Some(base_data) = Self::process_base_image(path, target_size) {
+            if self.zoom > 1 {
+                let prefix = format!("{}|", path);
+                self.base_cache.retain(|k| !k.starts_with(&prefix));
+            }
+            if let Some(base_data) = Self::process_base_image(
+                path,
+                target_size,
+                self.zoom,
+                focus,
+                shadow.as_ref(),
+                grid,
+                self.show_checkerboard,
+            ) {
                 self.base_cache.insert(base_key.clone(), base_data);
             } else {
+                self.failed_paths.insert(path.to_string());
                 return;
             }
         }
@@ -171,18 +584,35 @@ impl PreviewState {
         if let Some(base_data) = self.base_cache.get(&base_key) {
             let mut final_canvas = base_data.canvas.clone();
 
+            if self.crosshair && !low_fidelity {
+                Self::draw_crosshair(
+                    &mut final_canvas,
+                    hotspot,
+                    base_data.scale,
+                    base_data.offset_x,
+                    base_data.offset_y,
+                );
+            }
+
             Self::draw_hotspot(
                 &mut final_canvas,
                 hotspot,
                 base_data.scale,
                 base_data.offset_x,
                 base_data.offset_y,
+                low_fidelity,
             );
 
             // Encode to protocol
             if let Ok(picker) = self.picker.lock() {
-                let proto = picker.new_resize_protocol(DynamicImage::ImageRgba8(final_canvas));
-                self.protocol_cache.insert(proto_key, proto);
+                let protocol = picker.new_resize_protocol(DynamicImage::ImageRgba8(final_canvas));
+                let cached = CachedProtocol {
+                    protocol,
+                    scale: base_data.scale,
+                    offset_x: base_data.offset_x,
+                    offset_y: base_data.offset_y,
+                };
+                self.protocol_cache.insert(proto_key, cached);
             }
         }
     }
@@ -196,7 +626,7 @@ impl PreviewState {
             .collect();
 
         // Only remove from protocol cache, keep base images
-        self.protocol_cache.retain(|k, _| {
+        self.protocol_cache.retain(|k| {
             let path = k.split('|').next().unwrap_or("");
             !paths_to_remove.contains(path)
         });
@@ -205,6 +635,7 @@ impl PreviewState {
     pub fn clear_cache(&mut self) {
         self.base_cache.clear();
         self.protocol_cache.clear();
+        self.failed_paths.clear();
     }
 
     fn center_image_rect(area: Rect) -> Rect {
@@ -243,22 +674,28 @@ impl PreviewState {
         area: Rect,
         buf: &mut Buffer,
         _is_focused: bool,
-        _playing: bool,
+        speed: f32,
         maximized: bool,
         data: Option<PreviewData>,
     ) {
+        let low_fidelity = self.low_fidelity();
         let chunks = if maximized {
             Layout::default()
                 .constraints([Constraint::Percentage(100)])
                 .split(area)
         } else {
             Layout::default()
-                .constraints([Constraint::Min(10), Constraint::Length(1)])
+                .constraints([
+                    Constraint::Min(10),
+                    Constraint::Length(1),
+                    Constraint::Length(low_fidelity as u16),
+                ])
                 .direction(ratatui::layout::Direction::Vertical)
                 .split(area)
         };
 
         let image_area = Self::center_image_rect(chunks[0]);
+        self.last_view = None;
 
         let (font_w, font_h) = if let Ok(picker) = self.picker.lock() {
             picker.font_size()
@@ -273,33 +710,63 @@ impl PreviewState {
             self.ensure_cached(path, *hotspot, (target_w, target_h));
         }
 
-        if let Some((path, hotspot, size, _, variant, frame, frame_ix)) = data {
-            let key = Self::proto_key(path, (target_w, target_h), hotspot);
-
-            if let Some(proto) = self.protocol_cache.get_mut(&key) {
-                StatefulImage::default().render(image_area, buf, proto);
+        let mut zoom_text = if self.zoom > 1 {
+            format!(" | Zoom: {}x", self.zoom)
+        } else {
+            String::new()
+        };
+        if speed != 1.0 {
+            zoom_text.push_str(&format!(" | Speed: {}x", speed));
+        }
 
+        if let Some((path, hotspot, size, _, variant, frame, frame_ix)) = data {
+            let key = Self::proto_key(path, (target_w, target_h), hotspot, self.crosshair);
+
+            if let Some(cached) = self.protocol_cache.get_mut(&key) {
+                StatefulImage::default().render(image_area, buf, &mut cached.protocol);
+                self.last_view = Some(RenderedView {
+                    area: image_area,
+                    font_size: (font_w, font_h),
+                    scale: cached.scale,
+                    offset_x: cached.offset_x,
+                    offset_y: cached.offset_y,
+                });
+
+                // Sources can list an image under a nominal size it isn't drawn at
+                let size_text = if variant.pixel_size == (size, size) {
+                    format!("{}x{}", size, size)
+                } else {
+                    format!(
+                        "{}x{} ({}x{} px)",
+                        size, size, variant.pixel_size.0, variant.pixel_size.1
+                    )
+                };
                 let (text_content, text_area) = if maximized {
-                    let lines = vec![
+                    let mut lines = vec![
                         Line::from(format!("Frame: {}/{}", frame_ix + 1, variant.frames.len())),
                         Line::from(format!("Delay: {}ms", frame.delay_ms)),
                         Line::from(format!("Hotspot: ({}, {})", hotspot.0, hotspot.1)),
-                        Line::from(format!("Size: {}x{}", size, size)),
+                        Line::from(format!("Size: {}", size_text)),
+                        Line::from(format!("Zoom: {}x", self.zoom)),
+                        Line::from(format!("Speed: {}x", speed)),
                     ];
+                    if low_fidelity {
+                        lines.push(Line::from(LOW_FIDELITY_NOTICE));
+                    }
                     let height = lines.len() as u16;
                     let width = lines.iter().map(|l| l.width()).max().unwrap_or(0) as u16 + 2;
                     let centered_y = area.y + (area.height.saturating_sub(height)) / 2;
                     (lines, Rect::new(area.x, centered_y, width, height))
                 } else {
                     let info_text = format!(
-                        "Frame: {}/{} | Delay: {}ms | Hotspot: ({}, {}) | Size: {}x{}",
+                        "Frame: {}/{} | Delay: {}ms | Hotspot: ({}, {}) | Size: {}{}",
                         frame_ix + 1,
                         variant.frames.len(),
                         frame.delay_ms,
                         hotspot.0,
                         hotspot.1,
-                        size,
-                        size
+                        size_text,
+                        zoom_text
                     );
                     (vec![Line::from(info_text)], chunks[1])
                 };
@@ -322,10 +789,127 @@ impl PreviewState {
                                 .padding(ratatui::widgets::Padding::left(3)),
                         )
                         .render(text_area, buf);
+                    if low_fidelity {
+                        Paragraph::new(LOW_FIDELITY_NOTICE)
+                            .style(Style::default().fg(theme.text_secondary))
+                            .block(
+                                ratatui::widgets::Block::default()
+                                    .padding(ratatui::widgets::Padding::left(3)),
+                            )
+                            .render(chunks[2], buf);
+                    }
                 }
+            } else if self.failed_paths.contains(path) {
+                let file_name = std::path::Path::new(path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.to_string());
+                Paragraph::new(format!("Failed to load {}", file_name))
+                    .style(Style::default().fg(get_theme().status_failed))
+                    .render(area, buf);
             } else {
                 Paragraph::new("Loading image...").render(area, buf);
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_map_stays_bounded() {
+        let mut cache = LruMap::new(3);
+        for i in 0..3 {
+            cache.insert(format!("frame{}", i), i);
+        }
+        // Touching frame0 makes frame1 the oldest
+        assert_eq!(cache.get("frame0"), Some(&0));
+
+        for i in 3..10 {
+            cache.insert(format!("frame{}", i), i);
+            assert!(cache.entries.len() <= 3);
+            assert!(cache.contains_key(&format!("frame{}", i)));
+        }
+        assert!(!cache.contains_key("frame1"));
+
+        cache.set_capacity(1);
+        assert_eq!(cache.entries.len(), 1);
+        assert!(cache.contains_key("frame9"));
+    }
+
+    #[test]
+    fn test_halfblocks_preview_skips_grid() {
+        let state = PreviewState::new(Arc::new(Mutex::new(Picker::halfblocks())));
+        assert!(state.low_fidelity());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("frame.png");
+        RgbaImage::new(4, 4).save(&path).unwrap();
+        let path = path.to_string_lossy();
+
+        // 4px image on a 32px canvas is drawn at 8x, where the grid would normally appear
+        let grid = Some((DEFAULT_GRID_MIN_SCALE, Rgba([128, 128, 128, 100])));
+        let with_grid =
+            PreviewState::process_base_image(&path, (32, 32), 1, (0, 0), None, grid, false)
+                .unwrap();
+        let without =
+            PreviewState::process_base_image(&path, (32, 32), 1, (0, 0), None, None, false)
+                .unwrap();
+        assert!(with_grid.canvas.get_pixel(8, 3)[3] > 0);
+        assert!(without.canvas.pixels().all(|p| p[3] == 0));
+    }
+
+    #[test]
+    fn test_grid_threshold_is_configurable() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("frame.png");
+        RgbaImage::new(4, 4).save(&path).unwrap();
+        let path = path.to_string_lossy();
+        let color = Rgba([10, 20, 30, 140]);
+
+        // Drawn at 8x: a threshold of 8 still shows the grid in the given color, 9 does not
+        let at_8 = PreviewState::process_base_image(
+            &path,
+            (32, 32),
+            1,
+            (0, 0),
+            None,
+            Some((8.0, color)),
+            false,
+        )
+        .unwrap();
+        assert_eq!(at_8.canvas.get_pixel(8, 3), &color);
+        let at_9 = PreviewState::process_base_image(
+            &path,
+            (32, 32),
+            1,
+            (0, 0),
+            None,
+            Some((9.0, color)),
+            false,
+        )
+        .unwrap();
+        assert!(at_9.canvas.pixels().all(|p| p[3] == 0));
+    }
+
+    #[test]
+    fn test_checkerboard_shows_through_transparency() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("frame.png");
+        let mut image = RgbaImage::new(4, 4);
+        image.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+        image.save(&path).unwrap();
+        let path = path.to_string_lossy();
+
+        // Drawn at 8x, so each square spans two image pixels
+        let base =
+            PreviewState::process_base_image(&path, (32, 32), 1, (0, 0), None, None, true).unwrap();
+        assert_eq!(base.canvas.get_pixel(2, 2), &Rgba([255, 0, 0, 255]));
+        assert_eq!(base.canvas.get_pixel(12, 2), &Rgba([204, 204, 204, 255]));
+        assert_eq!(base.canvas.get_pixel(20, 2), &Rgba([153, 153, 153, 255]));
+        assert_eq!(base.canvas.get_pixel(20, 20), &Rgba([204, 204, 204, 255]));
+        assert!(base.canvas.pixels().all(|p| p[3] == 255));
+    }
+}
@@ -0,0 +1,147 @@
+use super::Component;
+use crate::event::AppMsg;
+use crate::pipeline::theme_actions::ThemeAction;
+use crate::pipeline_worker::BuildSummary;
+use crate::widgets::common::centered_rect;
+use crate::widgets::theme::get_theme;
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+// Popup shown after a full build with what was produced and what to do next
+#[derive(Default)]
+pub struct ResultsState {
+    pub summaries: Vec<BuildSummary>,
+    pub visible: bool,
+    pub selected: usize,
+}
+
+impl ResultsState {
+    pub fn show(&mut self, summaries: Vec<BuildSummary>) {
+        self.visible = !summaries.is_empty();
+        self.summaries = summaries;
+        self.selected = 0;
+    }
+
+    fn selected_summary(&self) -> Option<&BuildSummary> {
+        self.summaries.get(self.selected)
+    }
+}
+
+impl Component for ResultsState {
+    fn update(&mut self, msg: &AppMsg) -> Option<AppMsg> {
+        let AppMsg::Key(key) = msg else {
+            return None;
+        };
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                self.visible = false;
+                None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                if self.selected + 1 < self.summaries.len() {
+                    self.selected += 1;
+                }
+                None
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+                None
+            }
+            KeyCode::Char(c) => {
+                let action = ThemeAction::from_key(c)?;
+                let summary = self.selected_summary()?;
+                Some(AppMsg::ThemeActionRequested(
+                    action,
+                    summary.theme_dir.clone(),
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    fn render(&mut self, area: Rect, buf: &mut Buffer, _is_focused: bool) {
+        if !self.visible {
+            return;
+        }
+        let theme = get_theme();
+
+        let popup_area = centered_rect(70, 70, area);
+        Clear.render(popup_area, buf);
+
+        let block = Block::default()
+            .title("Build Complete")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border_focused));
+
+        let mut lines = Vec::new();
+        for (i, summary) in self.summaries.iter().enumerate() {
+            let (marker, name_style) = if i == self.selected {
+                (
+                    ">> ",
+                    Style::default()
+                        .fg(theme.text_highlight)
+                        .add_modifier(Modifier::BOLD),
+                )
+            } else {
+                ("   ", Style::default().fg(theme.text_primary))
+            };
+
+            lines.push(Line::from(vec![
+                Span::raw(marker),
+                Span::styled(summary.theme_name.clone(), name_style),
+            ]));
+            lines.push(Line::from(Span::styled(
+                format!("   {}", summary.theme_dir.display()),
+                Style::default().fg(theme.text_secondary),
+            )));
+            lines.push(Line::from(format!(
+                "   {} cursors, {} symlinks",
+                summary.cursor_count, summary.symlink_count
+            )));
+
+            if summary.warnings.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    "   No warnings",
+                    Style::default().fg(theme.status_completed),
+                )));
+            }
+            for warning in &summary.warnings {
+                lines.push(Line::from(Span::styled(
+                    format!("   ! {}", warning),
+                    Style::default().fg(theme.status_failed),
+                )));
+            }
+            lines.push(Line::from(""));
+        }
+
+        let actions: Vec<String> = ThemeAction::all()
+            .iter()
+            .map(|action| format!("{}: {}", action.key(), action.label()))
+            .collect();
+        lines.push(Line::from(Span::styled(
+            actions.join(" | "),
+            Style::default().fg(theme.text_primary),
+        )));
+
+        let mut hint = "Esc: Close".to_string();
+        if self.summaries.len() > 1 {
+            hint.push_str(" | j/k: Select theme");
+        }
+        lines.push(Line::from(Span::styled(
+            hint,
+            Style::default().fg(theme.text_secondary),
+        )));
+
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .render(popup_area, buf);
+    }
+}
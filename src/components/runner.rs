@@ -5,12 +5,13 @@ use crate::widgets::theme::get_theme;
 use crossbeam_channel::Sender;
 use ratatui::{
     buffer::Buffer,
-    layout::Rect,
+    layout::{Constraint, Layout, Rect},
     style::Style,
     text::{Line, Span},
-    widgets::{Paragraph, Widget},
+    widgets::{Gauge, Paragraph, Widget},
 };
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum PipelineStatus {
@@ -26,6 +27,8 @@ pub struct RunnerState {
     pub output_dir: Option<PathBuf>,
     pub files_processed: usize,
     pub total_files: usize,
+    pub start_time: Option<Instant>,
+    pub eta: Option<Duration>,
     pub tx: Option<Sender<AppMsg>>,
 }
 
@@ -37,6 +40,8 @@ impl Default for RunnerState {
             output_dir: None,
             files_processed: 0,
             total_files: 0,
+            start_time: None,
+            eta: None,
             tx: None,
         }
     }
@@ -57,20 +62,60 @@ impl RunnerState {
         self.output_dir = Some(path);
     }
 
+    fn start_timer(&mut self) {
+        self.start_time = Some(Instant::now());
+        self.eta = None;
+    }
+
     pub fn update_progress(&mut self, processed: usize, total: usize) {
+        // A new batch (e.g. the next theme variant) restarts the estimate
+        if self.start_time.is_none() || processed < self.files_processed {
+            self.start_timer();
+        }
+
         self.files_processed = processed;
         self.total_files = total;
         if processed < total {
             self.status = PipelineStatus::Running;
         }
+
+        self.eta = match self.start_time {
+            Some(start) if processed > 0 => {
+                let per_file = start.elapsed() / processed as u32;
+                Some(per_file * total.saturating_sub(processed) as u32)
+            }
+            _ => None,
+        };
     }
 
     pub fn complete_pipeline(&mut self, processed: usize) {
         self.status = PipelineStatus::Completed(processed);
+        self.start_time = None;
+        self.eta = None;
     }
 
     pub fn fail_pipeline(&mut self, error: String) {
         self.status = PipelineStatus::Failed(error.clone());
+        self.start_time = None;
+        self.eta = None;
+    }
+
+    // Share of files done; nothing is known about the total before the first progress event
+    fn progress_ratio(&self) -> f64 {
+        if self.total_files == 0 {
+            return 0.0;
+        }
+        (self.files_processed as f64 / self.total_files as f64).min(1.0)
+    }
+
+    fn eta_text(&self) -> String {
+        match self.eta {
+            Some(eta) => {
+                let secs = eta.as_secs();
+                format!("ETA: {:02}:{:02}", secs / 60, secs % 60)
+            }
+            None => "ETA: --:--".to_string(),
+        }
     }
 }
 
@@ -80,6 +125,15 @@ impl Component for RunnerState {
             AppMsg::PipelineStarted => {
                 self.status = PipelineStatus::Running;
                 self.files_processed = 0;
+                self.start_timer();
+            }
+            AppMsg::ConvertXCursorOnly
+            | AppMsg::ConvertPNGOnly
+            | AppMsg::RefreshSymlinks
+            | AppMsg::GenerateGallery
+            | AppMsg::PackageTarGz => {
+                self.files_processed = 0;
+                self.start_timer();
             }
             AppMsg::PipelineProgress(processed, total) => {
                 self.update_progress(*processed, *total);
@@ -126,14 +180,33 @@ impl Component for RunnerState {
             status_lines.push(Line::from(format!("Output: {}", output.display())));
         }
 
+        let mut text_area = inner;
         if self.status == PipelineStatus::Running {
-            status_lines.push(Line::from(format!(
-                "Progress: {}/{}",
-                self.files_processed, self.total_files
-            )));
+            status_lines.push(Line::from(self.eta_text()));
+
+            let [rest, gauge_area] =
+                Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+            text_area = rest;
+
+            let ratio = self.progress_ratio();
+            Gauge::default()
+                .gauge_style(
+                    Style::default()
+                        .fg(theme.status_running)
+                        .bg(theme.background),
+                )
+                .ratio(ratio)
+                .label(format!(
+                    "{}/{} ({:.0}%)",
+                    self.files_processed,
+                    self.total_files,
+                    ratio * 100.0
+                ))
+                .use_unicode(true)
+                .render(gauge_area, buf);
         }
 
         let status = Paragraph::new(status_lines).wrap(ratatui::widgets::Wrap { trim: true });
-        status.render(inner, buf);
+        status.render(text_area, buf);
     }
 }
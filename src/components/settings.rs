@@ -1,8 +1,14 @@
 use super::Component;
 use crate::event::AppMsg;
+use crate::pipeline::hyprcursor::{HlcCompression, ManifestFormat, ResizeAlgorithm};
+use crate::pipeline::log::LogLevel;
+use crate::pipeline::win2xcur::converter::{RESIZE_FILTERS, resize_filter_name};
+use crate::pipeline::win2xcur::utils::ShadowConfig;
+use crate::pipeline_worker::OutputLayout;
 use crate::widgets::common::focused_block;
 use crate::widgets::theme::{ThemeType, get_current_theme_type, get_theme, set_theme};
 use crossterm::event::KeyCode;
+use image::imageops::FilterType;
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
@@ -11,19 +17,100 @@ use ratatui::{
     widgets::{List, ListItem, ListState, Paragraph, StatefulWidget, Widget, Wrap},
 };
 
+#[derive(PartialEq)]
+pub enum PerformanceSetting {
+    Threads,
+    ResizeFilter,
+    LogVerbosity,
+    HlcCompression,
+    HyprResize,
+    ManifestFormat,
+    OutputLayout,
+}
+
+impl PerformanceSetting {
+    fn next(&self) -> Option<Self> {
+        match self {
+            PerformanceSetting::Threads => Some(PerformanceSetting::ResizeFilter),
+            PerformanceSetting::ResizeFilter => Some(PerformanceSetting::LogVerbosity),
+            PerformanceSetting::LogVerbosity => Some(PerformanceSetting::HlcCompression),
+            PerformanceSetting::HlcCompression => Some(PerformanceSetting::HyprResize),
+            PerformanceSetting::HyprResize => Some(PerformanceSetting::ManifestFormat),
+            PerformanceSetting::ManifestFormat => Some(PerformanceSetting::OutputLayout),
+            PerformanceSetting::OutputLayout => None,
+        }
+    }
+
+    fn prev(&self) -> Option<Self> {
+        match self {
+            PerformanceSetting::Threads => None,
+            PerformanceSetting::ResizeFilter => Some(PerformanceSetting::Threads),
+            PerformanceSetting::LogVerbosity => Some(PerformanceSetting::ResizeFilter),
+            PerformanceSetting::HlcCompression => Some(PerformanceSetting::LogVerbosity),
+            PerformanceSetting::HyprResize => Some(PerformanceSetting::HlcCompression),
+            PerformanceSetting::ManifestFormat => Some(PerformanceSetting::HyprResize),
+            PerformanceSetting::OutputLayout => Some(PerformanceSetting::ManifestFormat),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ShadowSetting {
+    Enabled,
+    Opacity,
+    XOffset,
+    YOffset,
+    Sigma,
+}
+
+impl ShadowSetting {
+    fn next(&self) -> Option<Self> {
+        match self {
+            ShadowSetting::Enabled => Some(ShadowSetting::Opacity),
+            ShadowSetting::Opacity => Some(ShadowSetting::XOffset),
+            ShadowSetting::XOffset => Some(ShadowSetting::YOffset),
+            ShadowSetting::YOffset => Some(ShadowSetting::Sigma),
+            ShadowSetting::Sigma => None,
+        }
+    }
+
+    fn prev(&self) -> Option<Self> {
+        match self {
+            ShadowSetting::Enabled => None,
+            ShadowSetting::Opacity => Some(ShadowSetting::Enabled),
+            ShadowSetting::XOffset => Some(ShadowSetting::Opacity),
+            ShadowSetting::YOffset => Some(ShadowSetting::XOffset),
+            ShadowSetting::Sigma => Some(ShadowSetting::YOffset),
+        }
+    }
+}
+
 #[derive(PartialEq)]
 pub enum SettingsSection {
     Theme,
     Performance,
+    Shadow,
 }
 
 pub struct SettingsState {
     pub themes: Vec<ThemeType>,
     pub selected_index: usize,
+    // Theme picked in this section, which is saved instead of the one the app started with
+    pub chosen_theme: Option<ThemeType>,
     pub list_state: ListState,
     pub active_section: SettingsSection,
     pub thread_count: usize,
     pub max_thread_count: usize,
+    pub performance_setting: PerformanceSetting,
+    pub resize_filter: FilterType,
+    pub log_verbosity: LogLevel,
+    pub hlc_compression: HlcCompression,
+    pub hypr_resize: ResizeAlgorithm,
+    pub manifest_format: ManifestFormat,
+    pub output_layout: OutputLayout,
+    pub shadow_setting: ShadowSetting,
+    pub shadow_enabled: bool,
+    pub shadow: ShadowConfig,
 }
 
 impl Default for SettingsState {
@@ -42,10 +129,21 @@ impl Default for SettingsState {
         Self {
             themes,
             selected_index,
+            chosen_theme: None,
             list_state,
             active_section: SettingsSection::Theme,
             thread_count: 0,
             max_thread_count,
+            performance_setting: PerformanceSetting::Threads,
+            resize_filter: FilterType::Lanczos3,
+            log_verbosity: LogLevel::default_verbosity(),
+            hlc_compression: HlcCompression::default(),
+            hypr_resize: ResizeAlgorithm::default(),
+            manifest_format: ManifestFormat::default(),
+            output_layout: OutputLayout::default(),
+            shadow_setting: ShadowSetting::Enabled,
+            shadow_enabled: false,
+            shadow: ShadowConfig::default(),
         }
     }
 }
@@ -54,12 +152,159 @@ impl SettingsState {
     pub fn apply_theme(&mut self) {
         if self.selected_index < self.themes.len() {
             set_theme(self.themes[self.selected_index]);
+            self.chosen_theme = Some(self.themes[self.selected_index]);
         }
     }
 
     pub fn set_thread_count(&mut self, count: usize) {
         self.thread_count = count;
     }
+
+    pub fn set_resize_filter(&mut self, filter: FilterType) {
+        self.resize_filter = filter;
+    }
+
+    pub fn set_log_verbosity(&mut self, verbosity: LogLevel) {
+        self.log_verbosity = verbosity;
+    }
+
+    fn cycle_log_verbosity(&mut self, forward: bool) -> AppMsg {
+        let levels = LogLevel::verbosities();
+        let len = levels.len();
+        let current = levels
+            .iter()
+            .position(|l| *l == self.log_verbosity)
+            .unwrap_or(0);
+        let next = if forward {
+            (current + 1) % len
+        } else {
+            (current + len - 1) % len
+        };
+        self.log_verbosity = levels[next];
+        AppMsg::LogVerbosityChanged(self.log_verbosity)
+    }
+
+    pub fn set_hlc_compression(&mut self, compression: HlcCompression) {
+        self.hlc_compression = compression;
+    }
+
+    fn cycle_hlc_compression(&mut self, forward: bool) -> AppMsg {
+        let methods = HlcCompression::all();
+        let len = methods.len();
+        let current = methods
+            .iter()
+            .position(|m| *m == self.hlc_compression)
+            .unwrap_or(0);
+        let next = if forward {
+            (current + 1) % len
+        } else {
+            (current + len - 1) % len
+        };
+        self.hlc_compression = methods[next];
+        AppMsg::HlcCompressionChanged(self.hlc_compression)
+    }
+
+    pub fn set_hypr_resize(&mut self, algorithm: ResizeAlgorithm) {
+        self.hypr_resize = algorithm;
+    }
+
+    fn cycle_hypr_resize(&mut self, forward: bool) -> AppMsg {
+        let algorithms = ResizeAlgorithm::all();
+        let len = algorithms.len();
+        let current = algorithms
+            .iter()
+            .position(|a| *a == self.hypr_resize)
+            .unwrap_or(0);
+        let next = if forward {
+            (current + 1) % len
+        } else {
+            (current + len - 1) % len
+        };
+        self.hypr_resize = algorithms[next];
+        AppMsg::HyprResizeAlgorithmChanged(self.hypr_resize)
+    }
+
+    pub fn set_manifest_format(&mut self, format: ManifestFormat) {
+        self.manifest_format = format;
+    }
+
+    fn cycle_manifest_format(&mut self) -> AppMsg {
+        // Only two formats, so both directions toggle
+        self.manifest_format = match self.manifest_format {
+            ManifestFormat::Hl => ManifestFormat::Toml,
+            ManifestFormat::Toml => ManifestFormat::Hl,
+        };
+        AppMsg::HyprManifestFormatChanged(self.manifest_format)
+    }
+
+    pub fn set_output_layout(&mut self, layout: OutputLayout) {
+        self.output_layout = layout;
+    }
+
+    fn cycle_output_layout(&mut self, forward: bool) -> AppMsg {
+        let layouts = OutputLayout::all();
+        let len = layouts.len();
+        let current = layouts
+            .iter()
+            .position(|l| *l == self.output_layout)
+            .unwrap_or(0);
+        let next = if forward {
+            (current + 1) % len
+        } else {
+            (current + len - 1) % len
+        };
+        self.output_layout = layouts[next];
+        AppMsg::OutputLayoutChanged(self.output_layout)
+    }
+
+    pub fn set_shadow(&mut self, enabled: bool, shadow: ShadowConfig) {
+        self.shadow_enabled = enabled;
+        self.shadow = shadow.clamped();
+    }
+
+    pub fn shadow_config(&self) -> Option<ShadowConfig> {
+        self.shadow_enabled.then(|| self.shadow.clone())
+    }
+
+    // Steps the selected shadow value; offsets and blur stay inside ShadowConfig's ranges
+    fn adjust_shadow(&mut self, forward: bool) -> Option<AppMsg> {
+        let sign = if forward { 1.0 } else { -1.0 };
+        let before = (self.shadow_enabled, self.shadow.clone());
+        match self.shadow_setting {
+            ShadowSetting::Enabled => self.shadow_enabled = !self.shadow_enabled,
+            ShadowSetting::Opacity => {
+                self.shadow.opacity = if forward {
+                    self.shadow.opacity.saturating_add(13)
+                } else {
+                    self.shadow.opacity.saturating_sub(13)
+                };
+            }
+            ShadowSetting::XOffset => self.shadow.x_offset += 0.01 * sign,
+            ShadowSetting::YOffset => self.shadow.y_offset += 0.01 * sign,
+            ShadowSetting::Sigma => self.shadow.sigma += 0.01 * sign,
+        }
+        self.shadow = self.shadow.clone().clamped();
+
+        if (self.shadow_enabled, self.shadow.clone()) == before {
+            return None;
+        }
+        Some(AppMsg::ShadowChanged(self.shadow_config()))
+    }
+
+    fn cycle_resize_filter(&mut self, forward: bool) -> AppMsg {
+        let len = RESIZE_FILTERS.len();
+        let current = RESIZE_FILTERS
+            .iter()
+            .position(|f| *f == self.resize_filter)
+            .unwrap_or(0);
+        let next = if forward {
+            (current + 1) % len
+        } else {
+            (current + len - 1) % len
+        };
+        self.resize_filter = RESIZE_FILTERS[next];
+        AppMsg::ResizeFilterChanged(self.resize_filter)
+    }
 }
 
 impl Component for SettingsState {
@@ -78,9 +323,21 @@ impl Component for SettingsState {
                             }
                         }
                         SettingsSection::Performance => {
-                            self.active_section = SettingsSection::Theme;
-                            self.selected_index = self.themes.len() - 1;
-                            self.list_state.select(Some(self.selected_index));
+                            if let Some(prev) = self.performance_setting.prev() {
+                                self.performance_setting = prev;
+                            } else {
+                                self.active_section = SettingsSection::Theme;
+                                self.selected_index = self.themes.len() - 1;
+                                self.list_state.select(Some(self.selected_index));
+                            }
+                        }
+                        SettingsSection::Shadow => {
+                            if let Some(prev) = self.shadow_setting.prev() {
+                                self.shadow_setting = prev;
+                            } else {
+                                self.active_section = SettingsSection::Performance;
+                                self.performance_setting = PerformanceSetting::OutputLayout;
+                            }
                         }
                     }
                 }
@@ -92,19 +349,36 @@ impl Component for SettingsState {
                                 self.list_state.select(Some(self.selected_index));
                             } else {
                                 self.active_section = SettingsSection::Performance;
+                                self.performance_setting = PerformanceSetting::Threads;
                                 self.list_state.select(None);
                             }
                         }
                         SettingsSection::Performance => {
-                            self.active_section = SettingsSection::Theme;
-                            self.selected_index = 0;
-                            self.list_state.select(Some(self.selected_index));
+                            if let Some(next) = self.performance_setting.next() {
+                                self.performance_setting = next;
+                            } else {
+                                self.active_section = SettingsSection::Shadow;
+                                self.shadow_setting = ShadowSetting::Enabled;
+                            }
+                        }
+                        SettingsSection::Shadow => {
+                            if let Some(next) = self.shadow_setting.next() {
+                                self.shadow_setting = next;
+                            } else {
+                                self.active_section = SettingsSection::Theme;
+                                self.selected_index = 0;
+                                self.list_state.select(Some(self.selected_index));
+                            }
                         }
                     }
                 }
                 KeyCode::Enter | KeyCode::Char(' ') => {
                     if self.active_section == SettingsSection::Theme {
                         self.apply_theme();
+                    } else if self.active_section == SettingsSection::Shadow
+                        && self.shadow_setting == ShadowSetting::Enabled
+                    {
+                        return self.adjust_shadow(true);
                     }
                 }
                 KeyCode::Right | KeyCode::Char('l') => {
@@ -119,12 +393,33 @@ impl Component for SettingsState {
                                 self.apply_theme();
                             }
                         }
-                        SettingsSection::Performance => {
-                            if self.thread_count < self.max_thread_count {
-                                self.thread_count += 1;
-                                return Some(AppMsg::ThreadCountChanged(self.thread_count));
+                        SettingsSection::Performance => match self.performance_setting {
+                            PerformanceSetting::Threads => {
+                                if self.thread_count < self.max_thread_count {
+                                    self.thread_count += 1;
+                                    return Some(AppMsg::ThreadCountChanged(self.thread_count));
+                                }
                             }
-                        }
+                            PerformanceSetting::ResizeFilter => {
+                                return Some(self.cycle_resize_filter(true));
+                            }
+                            PerformanceSetting::LogVerbosity => {
+                                return Some(self.cycle_log_verbosity(true));
+                            }
+                            PerformanceSetting::HlcCompression => {
+                                return Some(self.cycle_hlc_compression(true));
+                            }
+                            PerformanceSetting::HyprResize => {
+                                return Some(self.cycle_hypr_resize(true));
+                            }
+                            PerformanceSetting::ManifestFormat => {
+                                return Some(self.cycle_manifest_format());
+                            }
+                            PerformanceSetting::OutputLayout => {
+                                return Some(self.cycle_output_layout(true));
+                            }
+                        },
+                        SettingsSection::Shadow => return self.adjust_shadow(true),
                     }
                 }
                 KeyCode::Left | KeyCode::Char('h') => {
@@ -139,13 +434,34 @@ impl Component for SettingsState {
                                 self.apply_theme();
                             }
                         }
-                        SettingsSection::Performance => {
-                            // Decrease thread count
-                            if self.thread_count > 0 {
-                                self.thread_count -= 1;
-                                return Some(AppMsg::ThreadCountChanged(self.thread_count));
+                        SettingsSection::Performance => match self.performance_setting {
+                            PerformanceSetting::Threads => {
+                                // Decrease thread count
+                                if self.thread_count > 0 {
+                                    self.thread_count -= 1;
+                                    return Some(AppMsg::ThreadCountChanged(self.thread_count));
+                                }
                             }
-                        }
+                            PerformanceSetting::ResizeFilter => {
+                                return Some(self.cycle_resize_filter(false));
+                            }
+                            PerformanceSetting::LogVerbosity => {
+                                return Some(self.cycle_log_verbosity(false));
+                            }
+                            PerformanceSetting::HlcCompression => {
+                                return Some(self.cycle_hlc_compression(false));
+                            }
+                            PerformanceSetting::HyprResize => {
+                                return Some(self.cycle_hypr_resize(false));
+                            }
+                            PerformanceSetting::ManifestFormat => {
+                                return Some(self.cycle_manifest_format());
+                            }
+                            PerformanceSetting::OutputLayout => {
+                                return Some(self.cycle_output_layout(false));
+                            }
+                        },
+                        SettingsSection::Shadow => return self.adjust_shadow(false),
                     }
                 }
                 _ => {}
@@ -166,7 +482,10 @@ impl Component for SettingsState {
             .constraints([
                 Constraint::Min(5),    // Theme list
                 Constraint::Length(1), // Separator
-                Constraint::Length(4), // Performance settings
+                Constraint::Length(8), // Performance settings
+                Constraint::Length(1), // Separator
+                Constraint::Length(6), // Shadow settings
+                Constraint::Length(1), // Help
             ])
             .split(inner);
 
@@ -263,14 +582,19 @@ impl Component for SettingsState {
             format!("{}", self.thread_count)
         };
 
-        let thread_style = if self.active_section == SettingsSection::Performance {
-            Style::default()
-                .fg(theme.background)
-                .bg(theme.text_highlight)
-                .add_modifier(Modifier::BOLD)
-        } else {
-            Style::default().fg(theme.text_primary)
+        let setting_style = |setting: PerformanceSetting| {
+            if self.active_section == SettingsSection::Performance
+                && self.performance_setting == setting
+            {
+                Style::default()
+                    .fg(theme.background)
+                    .bg(theme.text_highlight)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.text_primary)
+            }
         };
+        let thread_style = setting_style(PerformanceSetting::Threads);
 
         let thread_setting = Paragraph::new(Line::from(vec![
             Span::raw("Threads: "),
@@ -280,6 +604,147 @@ impl Component for SettingsState {
         let thread_area = Rect::new(perf_area.x, perf_area.y + 1, perf_area.width, 1);
         thread_setting.render(thread_area, buf);
 
+        let filter_setting = Paragraph::new(Line::from(vec![
+            Span::raw("Resize filter: "),
+            Span::styled(
+                format!("< {} >", resize_filter_name(self.resize_filter)),
+                setting_style(PerformanceSetting::ResizeFilter),
+            ),
+        ]));
+
+        let filter_area = Rect::new(perf_area.x, perf_area.y + 2, perf_area.width, 1);
+        filter_setting.render(filter_area, buf);
+
+        let verbosity_setting = Paragraph::new(Line::from(vec![
+            Span::raw("Log level: "),
+            Span::styled(
+                format!("< {} >", self.log_verbosity.name()),
+                setting_style(PerformanceSetting::LogVerbosity),
+            ),
+        ]));
+
+        let verbosity_area = Rect::new(perf_area.x, perf_area.y + 3, perf_area.width, 1);
+        verbosity_setting.render(verbosity_area, buf);
+
+        let compression_setting = Paragraph::new(Line::from(vec![
+            Span::raw(".hlc compression: "),
+            Span::styled(
+                format!("< {} >", self.hlc_compression.name()),
+                setting_style(PerformanceSetting::HlcCompression),
+            ),
+        ]));
+
+        let compression_area = Rect::new(perf_area.x, perf_area.y + 4, perf_area.width, 1);
+        compression_setting.render(compression_area, buf);
+
+        let hypr_resize_setting = Paragraph::new(Line::from(vec![
+            Span::raw("Hyprcursor resize: "),
+            Span::styled(
+                format!("< {} >", self.hypr_resize.name()),
+                setting_style(PerformanceSetting::HyprResize),
+            ),
+        ]));
+
+        let hypr_resize_area = Rect::new(perf_area.x, perf_area.y + 5, perf_area.width, 1);
+        hypr_resize_setting.render(hypr_resize_area, buf);
+
+        let manifest_format_setting = Paragraph::new(Line::from(vec![
+            Span::raw("Hyprcursor manifest: "),
+            Span::styled(
+                format!("< {} >", self.manifest_format.name()),
+                setting_style(PerformanceSetting::ManifestFormat),
+            ),
+        ]));
+
+        let manifest_format_area = Rect::new(perf_area.x, perf_area.y + 6, perf_area.width, 1);
+        manifest_format_setting.render(manifest_format_area, buf);
+
+        let output_layout_setting = Paragraph::new(Line::from(vec![
+            Span::raw("Output layout: "),
+            Span::styled(
+                format!("< {} >", self.output_layout.name()),
+                setting_style(PerformanceSetting::OutputLayout),
+            ),
+        ]));
+
+        let output_layout_area = Rect::new(perf_area.x, perf_area.y + 7, perf_area.width, 1);
+        output_layout_setting.render(output_layout_area, buf);
+
+        Paragraph::new("─".repeat(chunks[3].width as usize))
+            .style(Style::default().fg(theme.border_unfocused))
+            .render(chunks[3], buf);
+
+        let shadow_area = chunks[4];
+        Paragraph::new(Line::from(Span::styled(
+            "Shadow",
+            Style::default()
+                .fg(if self.active_section == SettingsSection::Shadow {
+                    theme.text_highlight
+                } else {
+                    theme.text_secondary
+                })
+                .add_modifier(Modifier::BOLD),
+        )))
+        .render(
+            Rect::new(shadow_area.x, shadow_area.y, shadow_area.width, 1),
+            buf,
+        );
+
+        let shadow_rows = [
+            (
+                ShadowSetting::Enabled,
+                "Drop shadow: ",
+                if self.shadow_enabled { "On" } else { "Off" }.to_string(),
+            ),
+            (
+                ShadowSetting::Opacity,
+                "Opacity: ",
+                format!("{}%", self.shadow.opacity as u32 * 100 / 255),
+            ),
+            (
+                ShadowSetting::XOffset,
+                "X offset: ",
+                format!("{:.2}", self.shadow.x_offset),
+            ),
+            (
+                ShadowSetting::YOffset,
+                "Y offset: ",
+                format!("{:.2}", self.shadow.y_offset),
+            ),
+            (
+                ShadowSetting::Sigma,
+                "Blur: ",
+                format!("{:.2}", self.shadow.sigma),
+            ),
+        ];
+        for (row, (setting, label, value)) in shadow_rows.into_iter().enumerate() {
+            let style = if self.active_section == SettingsSection::Shadow
+                && self.shadow_setting == setting
+            {
+                Style::default()
+                    .fg(theme.background)
+                    .bg(theme.text_highlight)
+                    .add_modifier(Modifier::BOLD)
+            } else if setting != ShadowSetting::Enabled && !self.shadow_enabled {
+                Style::default().fg(theme.text_secondary)
+            } else {
+                Style::default().fg(theme.text_primary)
+            };
+            Paragraph::new(Line::from(vec![
+                Span::raw(label),
+                Span::styled(format!("< {} >", value), style),
+            ]))
+            .render(
+                Rect::new(
+                    shadow_area.x,
+                    shadow_area.y + 1 + row as u16,
+                    shadow_area.width,
+                    1,
+                ),
+                buf,
+            );
+        }
+
         // Help text
         let help_lines = vec![Line::from(Span::styled(
             "↑↓: Navigate  ←→: Adjust",
@@ -287,7 +752,6 @@ impl Component for SettingsState {
         ))];
 
         let help_para = Paragraph::new(help_lines).wrap(Wrap { trim: true });
-        let help_area = Rect::new(perf_area.x, perf_area.y + 2, perf_area.width, 1);
-        help_para.render(help_area, buf);
+        help_para.render(chunks[5], buf);
     }
 }
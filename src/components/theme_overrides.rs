@@ -1,5 +1,6 @@
 use super::Component;
 use crate::event::AppMsg;
+use crate::pipeline::xcursor_gen::DEFAULT_INHERITS;
 use crate::widgets::common::focused_block;
 use crate::widgets::theme::get_theme;
 use crossterm::event::KeyCode;
@@ -12,10 +13,73 @@ use ratatui::{
 };
 use std::collections::HashSet;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BuildOption {
+    DarkVariant,
+    StraightAlphaVariant,
+    SkipInstall,
+    KeepIntermediate,
+    LinkDuplicates,
+}
+
+impl BuildOption {
+    pub fn all() -> Vec<BuildOption> {
+        vec![
+            BuildOption::DarkVariant,
+            BuildOption::StraightAlphaVariant,
+            BuildOption::SkipInstall,
+            BuildOption::KeepIntermediate,
+            BuildOption::LinkDuplicates,
+        ]
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            BuildOption::DarkVariant => "Dark variant (-Dark)",
+            BuildOption::StraightAlphaVariant => "Straight alpha test (-Straight)",
+            BuildOption::SkipInstall => "Skip ~/.icons install",
+            BuildOption::KeepIntermediate => "Keep xcur_intermediate",
+            BuildOption::LinkDuplicates => "Link identical cursors",
+        }
+    }
+}
+
+/// Text fields at the top of the panel; typing goes to the active one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextField {
+    OutputName,
+    Inherits,
+    Description,
+}
+
+impl TextField {
+    fn next(self) -> Self {
+        match self {
+            TextField::OutputName => TextField::Inherits,
+            TextField::Inherits => TextField::Description,
+            TextField::Description => TextField::Description,
+        }
+    }
+
+    fn prev(self) -> Self {
+        match self {
+            TextField::OutputName => TextField::OutputName,
+            TextField::Inherits => TextField::OutputName,
+            TextField::Description => TextField::Inherits,
+        }
+    }
+}
+
 pub struct ThemeOverridesState {
     pub output_name: String,
+    /// Theme(s) named in `Inherits=` of index.theme, comma separated
+    pub inherits: String,
+    /// Comment of index.theme and description of the hyprcursor manifest
+    pub description: String,
+    pub active_field: TextField,
     pub available_sizes: Vec<u32>,
     pub selected_sizes: HashSet<u32>,
+    pub build_options: HashSet<BuildOption>,
     pub selector_index: usize,
     pub list_state: ListState,
 }
@@ -34,14 +98,67 @@ impl Default for ThemeOverridesState {
 
         Self {
             output_name: String::new(),
+            inherits: DEFAULT_INHERITS.to_string(),
+            description: String::new(),
+            active_field: TextField::OutputName,
             available_sizes,
             selected_sizes,
+            build_options: HashSet::new(),
             selector_index: 0,
             list_state,
         }
     }
 }
 
+impl ThemeOverridesState {
+    pub fn set_selected_sizes(&mut self, sizes: &[u32]) {
+        self.selected_sizes = sizes.iter().copied().collect();
+    }
+
+    pub fn is_enabled(&self, option: BuildOption) -> bool {
+        self.build_options.contains(&option)
+    }
+
+    fn active_text(&mut self) -> &mut String {
+        match self.active_field {
+            TextField::OutputName => &mut self.output_name,
+            TextField::Inherits => &mut self.inherits,
+            TextField::Description => &mut self.description,
+        }
+    }
+
+    // Theme names are limited to characters that are safe in a path; the description is
+    // free text
+    fn accepts(&self, c: char) -> bool {
+        match self.active_field {
+            TextField::OutputName => c.is_alphanumeric() || c == '-' || c == '_' || c == ' ',
+            // A comma separated list of theme names
+            TextField::Inherits => {
+                c.is_alphanumeric() || c == '-' || c == '_' || c == ' ' || c == ',' || c == '.'
+            }
+            TextField::Description => !c.is_control(),
+        }
+    }
+
+    // Sizes come first, followed by the build option toggles
+    fn row_count(&self) -> usize {
+        self.available_sizes.len() + BuildOption::all().len()
+    }
+
+    fn toggle_selected(&mut self) {
+        if let Some(&size) = self.available_sizes.get(self.selector_index) {
+            if !self.selected_sizes.remove(&size) {
+                self.selected_sizes.insert(size);
+            }
+        } else if let Some(&option) =
+            BuildOption::all().get(self.selector_index - self.available_sizes.len())
+            && !self.build_options.remove(&option)
+        {
+            self.build_options.insert(option);
+        }
+    }
+}
+
 impl Component for ThemeOverridesState {
     fn update(&mut self, msg: &AppMsg) -> Option<AppMsg> {
         if let AppMsg::Key(key) = msg {
@@ -49,31 +166,31 @@ impl Component for ThemeOverridesState {
                 KeyCode::Up => {
                     if self.selector_index > 0 {
                         self.selector_index -= 1;
-                        self.list_state.select(Some(self.selector_index));
+                        if self.selector_index < self.available_sizes.len() {
+                            self.list_state.select(Some(self.selector_index));
+                        }
                     }
                 }
                 KeyCode::Down => {
-                    if self.selector_index < self.available_sizes.len() - 1 {
+                    if self.selector_index < self.row_count() - 1 {
                         self.selector_index += 1;
-                        self.list_state.select(Some(self.selector_index));
+                        if self.selector_index < self.available_sizes.len() {
+                            self.list_state.select(Some(self.selector_index));
+                        }
                     }
                 }
                 KeyCode::Enter => {
-                    let size = self.available_sizes[self.selector_index];
-                    if self.selected_sizes.contains(&size) {
-                        self.selected_sizes.remove(&size);
-                    } else {
-                        self.selected_sizes.insert(size);
-                    }
+                    self.toggle_selected();
                 }
+                KeyCode::Left => self.active_field = self.active_field.prev(),
+                KeyCode::Right => self.active_field = self.active_field.next(),
                 KeyCode::Char(c) => {
-                    // Allow alphanumeric, dash, underscore, and space
-                    if c.is_alphanumeric() || c == '-' || c == '_' || c == ' ' {
-                        self.output_name.push(c);
+                    if self.accepts(c) {
+                        self.active_text().push(c);
                     }
                 }
                 KeyCode::Backspace => {
-                    self.output_name.pop();
+                    self.active_text().pop();
                 }
                 _ => {}
             }
@@ -91,39 +208,71 @@ impl Component for ThemeOverridesState {
         let chunks = ratatui::layout::Layout::default()
             .direction(ratatui::layout::Direction::Vertical)
             .constraints([
-                ratatui::layout::Constraint::Length(3), // Output Name
+                ratatui::layout::Constraint::Length(3), // Output Name and Inherits
+                ratatui::layout::Constraint::Length(3), // Description
                 ratatui::layout::Constraint::Min(1),    // Sizes
+                ratatui::layout::Constraint::Length(BuildOption::all().len() as u16 + 2), // Options
             ])
             .split(inner);
 
-        // Output Name Field
-        let name_style = if is_focused {
-            Style::default().fg(theme.text_highlight)
-        } else {
-            Style::default().fg(theme.text_primary)
-        };
-        let name_block = Block::default()
-            .title("Output Name")
-            .borders(Borders::ALL)
-            .style(name_style);
-
-        // Add cursor
-        let name_text = if is_focused {
-            format!("{}_", self.output_name)
-        } else {
-            self.output_name.clone()
-        };
-
-        Paragraph::new(name_text)
-            .block(name_block)
-            .render(chunks[0], buf);
+        // Output Name and Inherits side by side, Description below them
+        let field_chunks = ratatui::layout::Layout::default()
+            .direction(ratatui::layout::Direction::Horizontal)
+            .constraints([
+                ratatui::layout::Constraint::Percentage(50),
+                ratatui::layout::Constraint::Percentage(50),
+            ])
+            .split(chunks[0]);
+        let fields = [
+            (
+                TextField::OutputName,
+                "Output Name",
+                &self.output_name,
+                field_chunks[0],
+            ),
+            (
+                TextField::Inherits,
+                "Inherits",
+                &self.inherits,
+                field_chunks[1],
+            ),
+            (
+                TextField::Description,
+                "Description",
+                &self.description,
+                chunks[1],
+            ),
+        ];
+        for (field, title, value, field_area) in fields {
+            let editing = is_focused && field == self.active_field;
+            let field_style = if editing {
+                Style::default().fg(theme.text_highlight)
+            } else {
+                Style::default().fg(theme.text_primary)
+            };
+            let field_block = Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .style(field_style);
+
+            // Add cursor
+            let text = if editing {
+                format!("{}_", value)
+            } else {
+                value.clone()
+            };
+
+            Paragraph::new(text)
+                .block(field_block)
+                .render(field_area, buf);
+        }
 
         // Sizes Field
         let size_block = Block::default()
             .title("Sizes (Enter to toggle)")
             .borders(Borders::ALL);
-        let inner_size_area = size_block.inner(chunks[1]);
-        size_block.render(chunks[1], buf);
+        let inner_size_area = size_block.inner(chunks[2]);
+        size_block.render(chunks[2], buf);
 
         let items: Vec<ListItem> = self
             .available_sizes
@@ -148,5 +297,37 @@ impl Component for ThemeOverridesState {
 
         let list = List::new(items);
         StatefulWidget::render(list, inner_size_area, buf, &mut self.list_state);
+
+        // Build Options Field
+        let options_block = Block::default()
+            .title("Build Options")
+            .borders(Borders::ALL);
+        let inner_options_area = options_block.inner(chunks[3]);
+        options_block.render(chunks[3], buf);
+
+        let option_items: Vec<ListItem> = BuildOption::all()
+            .iter()
+            .enumerate()
+            .map(|(i, option)| {
+                let checkbox = if self.is_enabled(*option) {
+                    "[x]"
+                } else {
+                    "[ ]"
+                };
+                let content = format!("{} {}", checkbox, option.label());
+
+                let style = if self.available_sizes.len() + i == self.selector_index && is_focused {
+                    Style::default()
+                        .fg(theme.background)
+                        .bg(theme.text_highlight)
+                } else {
+                    Style::default().fg(theme.text_primary)
+                };
+
+                ListItem::new(Span::styled(content, style))
+            })
+            .collect();
+
+        Widget::render(List::new(option_items), inner_options_area, buf);
     }
 }
@@ -1,12 +1,69 @@
 use crate::model::mapping::CursorMapping;
-use std::path::PathBuf;
+use crate::model::matcher::DEFAULT_MIN_MATCH_SCORE;
+use crate::pipeline::hyprcursor::{HlcCompression, ManifestFormat, ResizeAlgorithm};
+use crate::pipeline::limits::DEFAULT_MAX_DIMENSION;
+use crate::pipeline::log::LogLevel;
+use crate::pipeline::win2xcur::converter::{RESIZE_FILTERS, resize_filter_name};
+use crate::pipeline::win2xcur::utils::ShadowConfig;
+use crate::pipeline::xcursor_gen::DEFAULT_INHERITS;
+use crate::pipeline_worker::OutputLayout;
+use crate::widgets::theme::ThemeType;
+use image::imageops::FilterType;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
 
-#[derive(Clone, Debug)]
+/// Entries kept in each of the hotspot preview's image caches, unless configured otherwise
+pub const DEFAULT_CACHE_CAPACITY: usize = 64;
+/// Magnification from which the pixel grid is drawn, unless configured otherwise
+pub const DEFAULT_GRID_MIN_SCALE: f32 = 4.0;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub input_dir: PathBuf,
     pub output_dir: PathBuf,
+    #[serde(skip)]
     pub mapping: CursorMapping,
     pub thread_count: usize,
+    pub theme: ThemeType,
+    pub selected_sizes: Vec<u32>,
+    /// X11 cursors a build should warn about when missing or falling back to Normal
+    pub essential_cursors: Vec<String>,
+    pub log_verbosity: LogLevel,
+    /// Filter used when scaling cursors to the target sizes
+    #[serde(with = "filter_name")]
+    pub resize_filter: FilterType,
+    /// Compression method for generated `.hlc` archives
+    pub hlc_compression: HlcCompression,
+    /// `resize_algorithm` written into generated meta.hl files
+    pub hyprcursor_resize_algorithm: ResizeAlgorithm,
+    /// Per-cursor `resize_algorithm`, by X11 name, used instead of the one above
+    pub hyprcursor_resize_overrides: BTreeMap<String, ResizeAlgorithm>,
+    /// Whether extracted hyprcursor sources use manifest.hl/meta.hl or their TOML forms
+    pub hyprcursor_manifest_format: ManifestFormat,
+    /// Whether full builds keep their intermediates beside the theme or inside it
+    pub output_layout: OutputLayout,
+    /// `Inherits=` of the built index.theme
+    pub theme_inherits: String,
+    /// Comment of the built index.theme and hyprcursor manifest; empty uses a generic one
+    pub theme_description: String,
+    /// Whether converted cursors get a drop shadow, and its look
+    pub shadow_enabled: bool,
+    pub shadow: ShadowConfig,
+    /// Largest width or height accepted when reading cursor images
+    pub max_cursor_dimension: u32,
+    /// Entries kept in each of the hotspot preview's image caches
+    pub preview_cache_capacity: usize,
+    /// Preview magnification from which the pixel grid is drawn
+    pub preview_grid_min_scale: f32,
+    /// Lowest score at which a source file is auto-matched to a cursor role
+    pub auto_match_min_score: usize,
+    /// File browser bookmarks, jumped to with 1..9
+    pub bookmarks: Vec<PathBuf>,
+    /// Whether Ctrl+hjkl at the edge of the layout jumps to the panel on the opposite side
+    pub wrap_navigation: bool,
 }
 
 impl Default for Config {
@@ -16,6 +73,206 @@ impl Default for Config {
             output_dir: PathBuf::from("./out"),
             mapping: CursorMapping::default(),
             thread_count: 0,
+            theme: ThemeType::CatppuccinMocha,
+            selected_sizes: vec![24, 32, 48],
+            essential_cursors: default_essential_cursors(),
+            log_verbosity: LogLevel::default_verbosity(),
+            resize_filter: FilterType::Lanczos3,
+            hlc_compression: HlcCompression::default(),
+            hyprcursor_resize_algorithm: ResizeAlgorithm::default(),
+            hyprcursor_resize_overrides: BTreeMap::new(),
+            hyprcursor_manifest_format: ManifestFormat::default(),
+            output_layout: OutputLayout::default(),
+            theme_inherits: DEFAULT_INHERITS.to_string(),
+            theme_description: String::new(),
+            shadow_enabled: false,
+            shadow: ShadowConfig::default(),
+            max_cursor_dimension: DEFAULT_MAX_DIMENSION,
+            preview_cache_capacity: DEFAULT_CACHE_CAPACITY,
+            preview_grid_min_scale: DEFAULT_GRID_MIN_SCALE,
+            auto_match_min_score: DEFAULT_MIN_MATCH_SCORE,
+            bookmarks: Vec::new(),
+            wrap_navigation: false,
+        }
+    }
+}
+
+// FilterType has no serde support; it is stored by the name the settings panel shows
+mod filter_name {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(filter: &FilterType, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(resize_filter_name(*filter))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<FilterType, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        RESIZE_FILTERS
+            .into_iter()
+            .find(|filter| resize_filter_name(*filter).eq_ignore_ascii_case(&name))
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown resize filter `{}`", name)))
+    }
+}
+
+fn default_essential_cursors() -> Vec<String> {
+    [
+        "left_ptr",
+        "pointer",
+        "text",
+        "wait",
+        "progress",
+        "not-allowed",
+        "crosshair",
+    ]
+    .iter()
+    .map(|name| name.to_string())
+    .collect()
+}
+
+impl Config {
+    /// Location of the persisted config: `~/.config/ani2hyprtui/config.toml`
+    pub fn config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("ani2hyprtui").join("config.toml"))
+    }
+
+    /// Default location for the mapping written from the mapping editor
+    pub fn mapping_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("ani2hyprtui").join("mapping.toml"))
+    }
+
+    /// Load the user config, using defaults if there is none yet. A malformed file is an
+    /// error, so the caller can keep it from being overwritten.
+    /// A saved `mapping.toml` replaces the built-in mapping.
+    pub fn load() -> std::io::Result<Self> {
+        let mut config = match Self::config_path() {
+            Some(path) if path.exists() => Self::load_from_file(path)?,
+            _ => Self::default(),
+        };
+
+        if let Some(mapping) =
+            Self::mapping_path().and_then(|path| CursorMapping::load_from_file(path).ok())
+        {
+            config.mapping = mapping;
+        }
+
+        Ok(config)
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = Self::config_path().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Could not determine config directory",
+            )
+        })?;
+        self.save_to_file(path)
+    }
+
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)?;
         }
+        let content = toml::to_string_pretty(self).map_err(std::io::Error::other)?;
+        fs::write(path, content)
+    }
+
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_config_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("nested").join("config.toml");
+
+        let config = Config {
+            input_dir: PathBuf::from("/tmp/in"),
+            output_dir: PathBuf::from("/tmp/out"),
+            thread_count: 3,
+            theme: ThemeType::Nord,
+            selected_sizes: vec![32, 64],
+            log_verbosity: LogLevel::Info,
+            hlc_compression: HlcCompression::Zstd,
+            hyprcursor_resize_algorithm: ResizeAlgorithm::Bilinear,
+            hyprcursor_resize_overrides: BTreeMap::from([(
+                "left_ptr".to_string(),
+                ResizeAlgorithm::Nearest,
+            )]),
+            hyprcursor_manifest_format: ManifestFormat::Toml,
+            output_layout: OutputLayout::Nested,
+            theme_inherits: "Adwaita".to_string(),
+            theme_description: "Soft cursors, v2".to_string(),
+            shadow_enabled: true,
+            shadow: ShadowConfig {
+                sigma: 0.2,
+                ..ShadowConfig::default()
+            },
+            max_cursor_dimension: 512,
+            auto_match_min_score: 20,
+            bookmarks: vec![PathBuf::from("/tmp/in/cursors")],
+            wrap_navigation: true,
+            preview_grid_min_scale: 8.0,
+            resize_filter: FilterType::Nearest,
+            ..Config::default()
+        };
+        config.save_to_file(&path).unwrap();
+
+        let loaded = Config::load_from_file(&path).unwrap();
+        assert_eq!(loaded.input_dir, PathBuf::from("/tmp/in"));
+        assert_eq!(loaded.output_dir, PathBuf::from("/tmp/out"));
+        assert_eq!(loaded.thread_count, 3);
+        assert_eq!(loaded.theme, ThemeType::Nord);
+        assert_eq!(loaded.selected_sizes, vec![32, 64]);
+        assert_eq!(loaded.log_verbosity, LogLevel::Info);
+        assert_eq!(loaded.hlc_compression, HlcCompression::Zstd);
+        assert_eq!(loaded.max_cursor_dimension, 512);
+        assert_eq!(loaded.auto_match_min_score, 20);
+        assert!(loaded.shadow_enabled);
+        assert_eq!(loaded.shadow.sigma, 0.2);
+        assert_eq!(
+            loaded.hyprcursor_resize_algorithm,
+            ResizeAlgorithm::Bilinear
+        );
+        assert_eq!(
+            loaded.hyprcursor_resize_overrides.get("left_ptr"),
+            Some(&ResizeAlgorithm::Nearest)
+        );
+        assert_eq!(loaded.hyprcursor_manifest_format, ManifestFormat::Toml);
+        assert_eq!(loaded.output_layout, OutputLayout::Nested);
+        assert_eq!(loaded.theme_inherits, "Adwaita");
+        assert_eq!(loaded.theme_description, "Soft cursors, v2");
+        assert_eq!(loaded.bookmarks, vec![PathBuf::from("/tmp/in/cursors")]);
+        assert!(loaded.wrap_navigation);
+        assert_eq!(loaded.preview_grid_min_scale, 8.0);
+        assert_eq!(loaded.resize_filter, FilterType::Nearest);
+    }
+
+    #[test]
+    fn test_config_partial_and_malformed() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        fs::write(&path, "thread_count = 2\n").unwrap();
+        let loaded = Config::load_from_file(&path).unwrap();
+        assert_eq!(loaded.thread_count, 2);
+        assert_eq!(loaded.selected_sizes, vec![24, 32, 48]);
+        assert!(loaded.essential_cursors.contains(&"text".to_string()));
+        assert_eq!(loaded.theme_inherits, DEFAULT_INHERITS);
+
+        assert_eq!(loaded.resize_filter, FilterType::Lanczos3);
+
+        fs::write(&path, "thread_count = \"lots\"").unwrap();
+        assert!(Config::load_from_file(&path).is_err());
+
+        fs::write(&path, "resize_filter = \"Bicubic\"").unwrap();
+        assert!(Config::load_from_file(&path).is_err());
     }
 }
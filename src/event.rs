@@ -1,7 +1,14 @@
 use crossterm::event::KeyEvent;
+use image::imageops::FilterType;
 use std::path::PathBuf;
 
 use crate::model::cursor::CursorMeta;
+use crate::pipeline::hyprcursor::{HlcCompression, ManifestFormat, ResizeAlgorithm};
+use crate::pipeline::log::LogLevel;
+use crate::pipeline::theme_actions::ThemeAction;
+use crate::pipeline::win2xcur::AniSequence;
+use crate::pipeline::win2xcur::utils::ShadowConfig;
+use crate::pipeline_worker::{BuildSummary, OutputLayout};
 
 #[derive(Clone, Debug)]
 pub enum AppMsg {
@@ -15,23 +22,50 @@ pub enum AppMsg {
 
     // Cursor loading
     CursorLoaded(Vec<CursorMeta>),
+    // Asks for the raw ANI frame order and rates of a cursor, by X11 name
+    AniTimingRequested(String),
+    AniTimingLoaded(String, AniSequence),
 
     // Mapping changes
-    MappingChanged(String, String),
+    // X11 name and its new source; None when the entry was removed
+    MappingChanged(String, Option<String>),
     MappingSaved,
     HotspotsSaved(Vec<String>),
 
     // Pipeline control
     PipelineStarted,
+    // A full build would replace this populated directory; asks before starting
+    ConfirmOverwrite(PathBuf),
     ConvertXCursorOnly,
     ConvertPNGOnly,
+    // Rebuilds only the symlinks of the existing output theme from the current mapping
+    RefreshSymlinks,
+    // Writes an index.html overview of the converted cursors into the theme directory
+    GenerateGallery,
+    // Packs the built theme directory into `<theme>.tar.gz` next to it
+    PackageTarGz,
+    // Rescans the input directory and auto-matches the mapping entries that are missing
+    RematchMissing,
+    CancelPipeline,
     PipelineProgress(usize, usize),
     PipelineCompleted(usize),
     PipelineFailed(String),
     XCursorGenerated(String),
+    BuildFinished(Vec<BuildSummary>),
+    ThemeActionRequested(ThemeAction, PathBuf),
 
     // General
     ErrorOccurred(String),
+    // Plain log lines are Info
     LogMessage(String),
+    LogMessageLevel(LogLevel, String),
     ThreadCountChanged(usize),
+    ResizeFilterChanged(FilterType),
+    LogVerbosityChanged(LogLevel),
+    HlcCompressionChanged(HlcCompression),
+    HyprResizeAlgorithmChanged(ResizeAlgorithm),
+    HyprManifestFormatChanged(ManifestFormat),
+    OutputLayoutChanged(OutputLayout),
+    // None turns the drop shadow off
+    ShadowChanged(Option<ShadowConfig>),
 }
@@ -7,21 +7,75 @@ pub mod pipeline;
 mod pipeline_worker;
 mod widgets;
 
+use widgets::theme::ThemeType;
+
 fn main() {
     let args: Vec<String> = std::env::args().collect();
     if args.contains(&"--version".to_string()) {
         println!("ani2hyprtui {}", env!("CARGO_PKG_VERSION"));
         return;
     }
+    if args.contains(&"--info".to_string()) {
+        print_info();
+        return;
+    }
+
+    let theme = args.iter().position(|a| a == "--theme").map(|i| {
+        let name = args.get(i + 1).map(String::as_str).unwrap_or("");
+        ThemeType::from_name(name).unwrap_or_else(|| {
+            eprintln!("Unknown theme '{}'. Available themes:", name);
+            for theme in ThemeType::all() {
+                eprintln!("  {}", theme.name());
+            }
+            std::process::exit(1);
+        })
+    });
 
     let picker = ratatui_image::picker::Picker::from_query_stdio().unwrap_or_else(|e| {
         eprintln!("Failed to query terminal ({}), using fallback", e);
         ratatui_image::picker::Picker::halfblocks()
     });
 
-    let mut app = app::App::new_with_picker(picker);
+    let mut app = app::App::new_with_picker(picker, theme);
     if let Err(e) = app.run() {
         eprintln!("Error: {}", e);
         std::process::exit(1);
     }
 }
+
+// Environment details that affect rendering and performance, for bug reports
+fn print_info() {
+    use pipeline::hyprcursor::HlcCompression;
+    use ratatui_image::picker::Picker;
+
+    println!("ani2hyprtui {}", env!("CARGO_PKG_VERSION"));
+    println!(
+        "Platform: {} {}",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+
+    match Picker::from_query_stdio() {
+        Ok(picker) => {
+            let (font_w, font_h) = picker.font_size();
+            println!(
+                "Image protocol: {:?} (cell {}x{} px)",
+                picker.protocol_type(),
+                font_w,
+                font_h
+            );
+        }
+        Err(e) => println!("Image protocol: Halfblocks (terminal query failed: {})", e),
+    }
+
+    let cpus = std::thread::available_parallelism()
+        .map(|n| n.get().to_string())
+        .unwrap_or_else(|_| "unknown".to_string());
+    println!("CPUs: {}", cpus);
+
+    let compressions: Vec<&str> = HlcCompression::available()
+        .iter()
+        .map(|c| c.name())
+        .collect();
+    println!("HLC compression: {}", compressions.join(", "));
+}
@@ -1,14 +1,22 @@
 use std::path::PathBuf;
 
+use crate::pipeline::cursor_types::clamp_hotspot;
+use crate::pipeline::win2xcur::AniSequence;
+
 #[derive(Clone, Debug)]
 pub struct Frame {
     pub png_path: PathBuf,
     pub delay_ms: u32,
+    // images sharing a nominal size may still point at different pixels
+    pub hotspot: (u32, u32),
 }
 
 #[derive(Clone, Debug)]
 pub struct SizeVariant {
+    // Nominal size the variant is listed under
     pub size: u32,
+    // Width and height of its images, which can differ from `size` for HiDPI sources
+    pub pixel_size: (u32, u32),
     pub frames: Vec<Frame>,
     pub hotspot: (u32, u32),
 }
@@ -17,4 +25,141 @@ pub struct SizeVariant {
 pub struct CursorMeta {
     pub x11_name: String,
     pub variants: Vec<SizeVariant>,
+    // Raw ANI frame order and rates, shown by the editor's timing view
+    pub ani_sequence: Option<AniSequence>,
+}
+
+impl CursorMeta {
+    // Animated when any size has more than one frame; a lone frame is static whatever its delay
+    pub fn is_animated(&self) -> bool {
+        self.variants.iter().any(SizeVariant::is_animated)
+    }
+
+    // Loop length of the longest size, which the others normally match
+    pub fn total_duration_ms(&self) -> u32 {
+        self.variants
+            .iter()
+            .map(SizeVariant::total_duration_ms)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl SizeVariant {
+    pub fn is_animated(&self) -> bool {
+        self.frames.len() > 1
+    }
+
+    pub fn total_duration_ms(&self) -> u32 {
+        self.frames.iter().map(|f| f.delay_ms).sum()
+    }
+
+    // Hotspot of a single frame; falls back to the variant's when the frame is missing
+    pub fn frame_hotspot(&self, frame_ix: usize) -> (u32, u32) {
+        self.frames
+            .get(frame_ix)
+            .map(|f| f.hotspot)
+            .unwrap_or(self.hotspot)
+    }
+
+    // Moves the hotspot of `frame_ix` to `target` and shifts every other frame by the same
+    // offset, keeping per-image differences. Returns whether anything changed.
+    pub fn set_frame_hotspot(&mut self, frame_ix: usize, target: (u32, u32)) -> bool {
+        let target = clamp_hotspot(target, self.pixel_size);
+        let current = self.frame_hotspot(frame_ix);
+        let dx = target.0 as i64 - current.0 as i64;
+        let dy = target.1 as i64 - current.1 as i64;
+        if dx == 0 && dy == 0 {
+            return false;
+        }
+
+        let shift = |(x, y): (u32, u32)| {
+            clamp_hotspot(
+                ((x as i64 + dx).max(0) as u32, (y as i64 + dy).max(0) as u32),
+                self.pixel_size,
+            )
+        };
+
+        for frame in &mut self.frames {
+            frame.hotspot = shift(frame.hotspot);
+        }
+        self.hotspot = match self.frames.first() {
+            Some(first) => first.hotspot,
+            None => target,
+        };
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(hotspot: (u32, u32)) -> Frame {
+        Frame {
+            png_path: PathBuf::new(),
+            delay_ms: 50,
+            hotspot,
+        }
+    }
+
+    #[test]
+    fn test_set_frame_hotspot_keeps_per_image_offsets() {
+        let mut variant = SizeVariant {
+            size: 32,
+            pixel_size: (32, 32),
+            frames: vec![frame((4, 4)), frame((6, 2))],
+            hotspot: (4, 4),
+        };
+
+        assert!(variant.set_frame_hotspot(1, (8, 5)));
+        assert_eq!(variant.frame_hotspot(0), (6, 7));
+        assert_eq!(variant.frame_hotspot(1), (8, 5));
+        assert_eq!(variant.hotspot, (6, 7));
+
+        assert!(!variant.set_frame_hotspot(1, (8, 5)));
+    }
+
+    #[test]
+    fn test_set_frame_hotspot_clamps_to_pixel_size() {
+        // Listed as 48 but drawn at 32px
+        let mut variant = SizeVariant {
+            size: 48,
+            pixel_size: (32, 32),
+            frames: vec![frame((0, 0))],
+            hotspot: (0, 0),
+        };
+
+        assert!(variant.set_frame_hotspot(0, (40, 40)));
+        assert_eq!(variant.hotspot, (31, 31));
+    }
+
+    #[test]
+    fn test_animation_duration_sums_frame_delays() {
+        let variant = |frames: Vec<Frame>| SizeVariant {
+            size: 32,
+            pixel_size: (32, 32),
+            frames,
+            hotspot: (0, 0),
+        };
+        let mut still = frame((0, 0));
+        still.delay_ms = 0;
+
+        let animated = CursorMeta {
+            variants: vec![
+                variant(vec![still.clone()]),
+                variant(vec![frame((0, 0)), frame((0, 0)), frame((0, 0))]),
+            ],
+            ..CursorMeta::default()
+        };
+        assert!(animated.is_animated());
+        assert_eq!(animated.total_duration_ms(), 150);
+
+        let static_cursor = CursorMeta {
+            variants: vec![variant(vec![still])],
+            ..CursorMeta::default()
+        };
+        assert!(!static_cursor.is_animated());
+        assert_eq!(static_cursor.total_duration_ms(), 0);
+    }
 }
@@ -1,8 +1,36 @@
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
 use std::fs;
 use std::path::Path;
 
+/// A mistake in a mapping that would make the built theme depend on iteration order
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MappingConflict {
+    // The same alias is listed under several roots; only one link can win
+    DuplicateAlias { alias: String, roots: Vec<String> },
+    // An alias has the name of a cursor that is built from its own source
+    AliasIsRoot { alias: String, root: String },
+    // An empty x11 name, source name or alias, described by where it appears
+    EmptyName(String),
+}
+
+impl fmt::Display for MappingConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MappingConflict::DuplicateAlias { alias, roots } => {
+                write!(f, "alias '{}' is listed under {}", alias, roots.join(", "))
+            }
+            MappingConflict::AliasIsRoot { alias, root } => write!(
+                f,
+                "alias '{}' under '{}' is also a mapped cursor",
+                alias, root
+            ),
+            MappingConflict::EmptyName(place) => write!(f, "empty name in {}", place),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CursorMapping {
     #[serde(default = "default_x11_to_win")]
@@ -30,6 +58,88 @@ impl CursorMapping {
         self.x11_to_win.insert(x11_name, win_name);
     }
 
+    /// Resolve a cursor name to the mapped x11 name it is built from, following symlinks
+    pub fn resolve_root<'a>(&'a self, name: &'a str) -> Option<&'a str> {
+        if self.x11_to_win.contains_key(name) {
+            return Some(name);
+        }
+        self.symlinks
+            .iter()
+            .find(|(_, links)| links.iter().any(|link| link == name))
+            .map(|(root, _)| root.as_str())
+    }
+
+    /// Lists duplicate aliases, aliases that shadow mapped cursors and empty names
+    pub fn validate(&self) -> Vec<MappingConflict> {
+        let mut conflicts = Vec::new();
+
+        for (x11_name, win_name) in &self.x11_to_win {
+            if x11_name.trim().is_empty() {
+                conflicts.push(MappingConflict::EmptyName("x11_to_win".to_string()));
+            } else if win_name.trim().is_empty() {
+                conflicts.push(MappingConflict::EmptyName(format!(
+                    "the source for '{}'",
+                    x11_name
+                )));
+            }
+        }
+
+        let mut roots_by_alias: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for (root, aliases) in &self.symlinks {
+            if root.trim().is_empty() {
+                conflicts.push(MappingConflict::EmptyName("symlinks".to_string()));
+            }
+            for alias in aliases {
+                if alias.trim().is_empty() {
+                    conflicts.push(MappingConflict::EmptyName(format!(
+                        "the aliases of '{}'",
+                        root
+                    )));
+                } else if self.x11_to_win.contains_key(alias) {
+                    conflicts.push(MappingConflict::AliasIsRoot {
+                        alias: alias.clone(),
+                        root: root.clone(),
+                    });
+                }
+                roots_by_alias.entry(alias).or_default().push(root.clone());
+            }
+        }
+
+        for (alias, mut roots) in roots_by_alias {
+            roots.dedup();
+            if roots.len() > 1 && !alias.trim().is_empty() {
+                conflicts.push(MappingConflict::DuplicateAlias {
+                    alias: alias.to_string(),
+                    roots,
+                });
+            }
+        }
+
+        conflicts
+    }
+
+    /// A copy without the cursors whose X11 names are in `excluded`, and without their
+    /// symlinks
+    pub fn excluding(&self, excluded: &HashSet<String>) -> CursorMapping {
+        let x11_to_win: BTreeMap<String, String> = self
+            .x11_to_win
+            .iter()
+            .filter(|(x11_name, _)| !excluded.contains(*x11_name))
+            .map(|(x11_name, win_name)| (x11_name.clone(), win_name.clone()))
+            .collect();
+        let symlinks = self
+            .symlinks
+            .iter()
+            .filter(|(root, _)| x11_to_win.contains_key(*root))
+            .map(|(root, links)| (root.clone(), links.clone()))
+            .collect();
+
+        CursorMapping {
+            x11_to_win,
+            symlinks,
+        }
+    }
+
     pub fn get_symlinks(&self, x11_name: &str) -> Vec<String> {
         self.symlinks.get(x11_name).cloned().unwrap_or_default()
     }
@@ -43,6 +153,9 @@ impl CursorMapping {
     }
 
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)?;
+        }
         let content = self.to_toml_string().map_err(std::io::Error::other)?;
         fs::write(path, content)
     }
@@ -168,7 +281,6 @@ fn default_symlinks() -> BTreeMap<String, Vec<String>> {
     map.insert(
         "question_arrow".to_string(),
         vec![
-            "help".to_string(),
             "whats_this".to_string(),
             "left_ptr_help".to_string(),
             "5c6cd98b3f3ebcb1f9c7f1c204630408".to_string(),
@@ -317,3 +429,93 @@ fn default_symlinks() -> BTreeMap<String, Vec<String>> {
 
     map
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_mapping_file_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("nested").join("mapping.toml");
+
+        let mut mapping = CursorMapping::default();
+        mapping.set_mapping("left_ptr".to_string(), "My Arrow".to_string());
+        mapping
+            .symlinks
+            .insert("left_ptr".to_string(), vec!["arrow".to_string()]);
+        mapping.save_to_file(&path).unwrap();
+
+        let loaded = CursorMapping::load_from_file(&path).unwrap();
+        assert_eq!(loaded.x11_to_win, mapping.x11_to_win);
+        assert_eq!(loaded.symlinks, mapping.symlinks);
+    }
+
+    #[test]
+    fn test_excluding_drops_cursors_and_their_symlinks() {
+        let mapping = CursorMapping::default();
+        // "Busy" is a source name, not an X11 one, so it excludes nothing
+        let excluded: HashSet<String> = ["Busy", "wait", "text"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let filtered = mapping.excluding(&excluded);
+        assert!(!filtered.x11_to_win.contains_key("wait"));
+        assert!(!filtered.x11_to_win.contains_key("text"));
+        assert!(!filtered.symlinks.contains_key("wait"));
+        assert!(!filtered.symlinks.contains_key("text"));
+        assert_eq!(filtered.x11_to_win.len(), mapping.x11_to_win.len() - 2);
+        assert_eq!(
+            filtered.get_symlinks("left_ptr"),
+            mapping.get_symlinks("left_ptr")
+        );
+    }
+
+    #[test]
+    fn test_default_mapping_is_valid() {
+        assert_eq!(CursorMapping::default().validate(), vec![]);
+    }
+
+    #[test]
+    fn test_validate_reports_duplicate_alias() {
+        let mut mapping = CursorMapping::default();
+        mapping
+            .symlinks
+            .entry("pointer".to_string())
+            .or_default()
+            .push("arrow".to_string());
+        mapping
+            .symlinks
+            .entry("left_ptr".to_string())
+            .or_default()
+            .push("arrow".to_string());
+
+        assert_eq!(
+            mapping.validate(),
+            vec![MappingConflict::DuplicateAlias {
+                alias: "arrow".to_string(),
+                roots: vec!["left_ptr".to_string(), "pointer".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_validate_reports_alias_equal_to_root() {
+        let mut mapping = CursorMapping::default();
+        mapping
+            .symlinks
+            .entry("left_ptr".to_string())
+            .or_default()
+            .push("text".to_string());
+
+        assert_eq!(
+            mapping.validate(),
+            vec![MappingConflict::AliasIsRoot {
+                alias: "text".to_string(),
+                root: "left_ptr".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,150 @@
+// Fuzzy matching of Windows cursor file names against the standard role names, used to
+// fill in the mapping automatically
+
+// Words are split on whitespace, '-' and '_'; single characters are ignored
+fn words(name: &str) -> Vec<String> {
+    name.to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+        .filter(|w| w.len() >= 2)
+        .map(str::to_string)
+        .collect()
+}
+
+/// Scores how well `source` matches the standard name `target`, case-insensitively.
+///
+/// Each word of `target` is compared with every word of `source` and keeps its best score:
+/// an exact word match scores 10 per character, a word that is a prefix of the other 5 per
+/// character of the shorter word, and one contained in the other 2 per character. The
+/// result is the sum over the target's words, or `None` if no word matched at all.
+pub fn score_match(source: &str, target: &str) -> Option<usize> {
+    let source_words = words(source);
+    let target_words = words(target);
+
+    let mut total_score = 0usize;
+    let mut matched_any = false;
+
+    for target_word in &target_words {
+        let mut best_word_score = 0usize;
+
+        for source_word in &source_words {
+            let common_len = source_word.len().min(target_word.len());
+            let score = if source_word == target_word {
+                target_word.len() * 10
+            } else if source_word.starts_with(target_word.as_str())
+                || target_word.starts_with(source_word.as_str())
+            {
+                common_len * 5
+            } else if source_word.contains(target_word.as_str())
+                || target_word.contains(source_word.as_str())
+            {
+                common_len * 2
+            } else {
+                0
+            };
+
+            best_word_score = best_word_score.max(score);
+        }
+
+        if best_word_score > 0 {
+            matched_any = true;
+            total_score += best_word_score;
+        }
+    }
+
+    matched_any.then_some(total_score)
+}
+
+/// Lowest score auto-matching accepts by default. It takes a prefix of at least 3
+/// characters or a substring of at least 6; shorter overlaps are too often coincidental.
+pub const DEFAULT_MIN_MATCH_SCORE: usize = 12;
+
+/// The source with the highest `score_match` against `target`, if that score reaches
+/// `min_score`. Ties go to the shorter name, since extra words usually mean a more specific
+/// cursor.
+pub fn find_best_match<'a>(
+    sources: &'a [String],
+    target: &str,
+    min_score: usize,
+) -> Option<&'a String> {
+    sources
+        .iter()
+        .filter_map(|source| score_match(source, target).map(|score| (source, score)))
+        .filter(|(_, score)| *score >= min_score)
+        .max_by(|(src_a, score_a), (src_b, score_b)| {
+            score_a
+                .cmp(score_b)
+                .then_with(|| src_b.len().cmp(&src_a.len()))
+        })
+        .map(|(source, _)| source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_score_exact_prefix_and_substring() {
+        assert_eq!(score_match("Normal", "normal"), Some(60));
+        // "work" starts "working": 4 shared characters
+        assert_eq!(score_match("Working", "work"), Some(20));
+        assert_eq!(score_match("work", "Working"), Some(20));
+        // "avail" inside "unavailable"
+        assert_eq!(score_match("Unavailable", "avail"), Some(10));
+    }
+
+    #[test]
+    fn test_score_sums_words_and_ignores_separators() {
+        assert_eq!(
+            score_match("Diagonal_Resize-1", "diagonal resize"),
+            Some(140)
+        );
+        assert_eq!(score_match("HELP select", "Help_Select"), Some(100));
+    }
+
+    #[test]
+    fn test_score_no_match() {
+        assert_eq!(score_match("Normal", "text"), None);
+        // Single-character words never count
+        assert_eq!(score_match("a", "a"), None);
+        assert_eq!(score_match("", "normal"), None);
+    }
+
+    #[test]
+    fn test_best_match_prefers_higher_score() {
+        let sources = names(&["Working", "Work", "Normal"]);
+        assert_eq!(find_best_match(&sources, "work", 0).unwrap(), "Work");
+        assert_eq!(find_best_match(&sources, "busy", 0), None);
+    }
+
+    #[test]
+    fn test_best_match_ties_go_to_shorter_name() {
+        let sources = names(&["Link Select", "Link", "Link Alt"]);
+        assert_eq!(find_best_match(&sources, "link", 0).unwrap(), "Link");
+    }
+
+    #[test]
+    fn test_best_match_rejects_weak_overlap() {
+        // "pen" inside "open" is a 3-character substring, worth only 6
+        let sources = names(&["Pen", "Normal"]);
+        assert_eq!(find_best_match(&sources, "Open", 0).unwrap(), "Pen");
+        assert_eq!(
+            find_best_match(&sources, "Open", DEFAULT_MIN_MATCH_SCORE),
+            None
+        );
+
+        // A 2-character prefix ("he" of "help") is rejected too, a real word is not
+        let sources = names(&["He", "Help Select"]);
+        assert_eq!(
+            find_best_match(&sources, "Help", DEFAULT_MIN_MATCH_SCORE).unwrap(),
+            "Help Select"
+        );
+        assert_eq!(
+            find_best_match(&names(&["He"]), "Help", DEFAULT_MIN_MATCH_SCORE),
+            None
+        );
+    }
+}
@@ -1,3 +1,4 @@
 pub mod cursor;
 pub mod mapping;
+pub mod matcher;
 pub mod theme;
@@ -7,10 +7,14 @@ use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 use xcursor::parser::{Image, parse_xcursor};
 
-use super::cursor_types::{CursorMeta, Frame, SizeVariant};
-use super::win2xcur::{AniParser, CurParser, CursorFormat, cur::CursorFrame};
+use super::cursor_types::{CursorMeta, Frame, SizeVariant, clamp_hotspot};
+use super::hyprcursor::read_hlc;
+use super::log::LogLevel;
+use super::tip::{is_arrow_like, place_missing_tips};
+use super::win2xcur::{AniParser, AniSequence, CurParser, CursorFormat, cur::CursorFrame};
+use super::xcur2png::png_writer::parse_config_line;
 
-type PngFrameData = (PathBuf, u32, (u16, u16));
+type PngFrameData = (PathBuf, u32, (u32, u32));
 
 fn scan_cursor_dir(dir: &Path) -> Result<Vec<PathBuf>> {
     let mut cursor_files = Vec::new();
@@ -83,30 +87,105 @@ fn parse_cursor_file(path: &Path) -> Result<Vec<Image>> {
     parse_xcursor(&data).context("Failed to parse X11 cursor file")
 }
 
-fn parse_windows_cursor_file(path: &Path) -> Result<Vec<CursorFrame>> {
+fn parse_windows_cursor_file<F>(
+    path: &Path,
+    max_dimension: u32,
+    log_fn: &mut F,
+) -> Result<(Vec<CursorFrame>, Option<AniSequence>)>
+where
+    F: FnMut(LogLevel, String),
+{
     let data = fs::read(path).context("Failed to read Windows cursor file")?;
 
     let format =
         CursorFormat::detect(&data).ok_or_else(|| anyhow::anyhow!("Unsupported cursor format"))?;
 
     match format {
-        CursorFormat::Cur => CurParser::parse(&data, |msg| {
-            eprintln!("{}", msg);
-        }),
-        CursorFormat::Ani => AniParser::parse(&data, |msg| {
-            eprintln!("{}", msg);
-        }),
+        CursorFormat::Cur => {
+            CurParser::parse(&data, max_dimension, log_fn).map(|frames| (frames, None))
+        }
+        CursorFormat::Ani => AniParser::parse_detailed(&data, max_dimension, log_fn)
+            .map(|(frames, sequence)| (frames, Some(sequence))),
+    }
+}
+
+/// Gives every frame an image at every nominal size the cursor uses, borrowing the image
+/// from the nearest frame that has one, so each size variant animates with the same frame count
+fn pad_missing_sizes<F>(frames: &mut [CursorFrame], cursor_name: &str, log_fn: &mut F)
+where
+    F: FnMut(LogLevel, String),
+{
+    let mut sizes: Vec<u32> = frames
+        .iter()
+        .flat_map(|frame| frame.images.iter().map(|img| img.nominal_size))
+        .collect();
+    sizes.sort_unstable();
+    sizes.dedup();
+
+    for size in sizes {
+        let has_size: Vec<bool> = frames
+            .iter()
+            .map(|frame| frame.images.iter().any(|img| img.nominal_size == size))
+            .collect();
+        let missing = has_size.iter().filter(|&&has| !has).count();
+        if missing == 0 {
+            continue;
+        }
+
+        log_fn(
+            LogLevel::Warn,
+            format!(
+                "Warning: {} is missing size {} in {} of {} frames, reusing neighbouring frames",
+                cursor_name,
+                size,
+                missing,
+                frames.len()
+            ),
+        );
+
+        for idx in (0..frames.len()).filter(|&i| !has_size[i]) {
+            let nearest = (0..frames.len())
+                .filter(|&i| has_size[i])
+                .min_by_key(|&i| i.abs_diff(idx))
+                .expect("size comes from at least one frame");
+            let image = frames[nearest]
+                .images
+                .iter()
+                .find(|img| img.nominal_size == size)
+                .cloned()
+                .expect("frame was checked for this size");
+            frames[idx].images.push(image);
+        }
     }
 }
 
-fn convert_windows_cursor_to_meta(path: &Path, frames: Vec<CursorFrame>) -> CursorMeta {
+fn convert_windows_cursor_to_meta<F>(
+    path: &Path,
+    mut frames: Vec<CursorFrame>,
+    ani_sequence: Option<AniSequence>,
+    log_fn: &mut F,
+) -> CursorMeta
+where
+    F: FnMut(LogLevel, String),
+{
     let x11_name = path
         .file_stem()
         .and_then(|n| n.to_str())
         .unwrap_or("unknown")
         .to_string();
 
-    // group images by nominal size across all frames
+    pad_missing_sizes(&mut frames, &x11_name, log_fn);
+    if is_arrow_like(&x11_name) && place_missing_tips(&mut frames) {
+        log_fn(
+            LogLevel::Detail,
+            format!(
+                "{}: no hotspot set, placed it on the arrow tip",
+                path.display()
+            ),
+        );
+    }
+
+    // group images by nominal size, keeping the resolved frame sequence order
     let mut size_map: HashMap<u32, Vec<(usize, usize)>> = HashMap::new(); // size -> [(frame_idx, img_idx)]
 
     for (frame_idx, frame) in frames.iter().enumerate() {
@@ -127,20 +206,22 @@ fn convert_windows_cursor_to_meta(path: &Path, frames: Vec<CursorFrame>) -> Curs
             let first_img = &frames[first_frame_idx].images[first_img_idx];
             let hotspot = first_img.hotspot;
 
-            // create frames for this size variant
-            let frame_list: Vec<Frame> = frames
+            // only the frames that carry this size, mirroring what the X11 writer emits
+            let frame_list: Vec<Frame> = indices
                 .iter()
-                .map(|frame| {
-                    let delay = frame.delay;
+                .map(|&(frame_idx, img_idx)| {
+                    let img = &frames[frame_idx].images[img_idx];
                     Frame {
                         png_path: PathBuf::new(), // will be populated when extracted
-                        delay_ms: delay,
+                        delay_ms: frames[frame_idx].delay,
+                        hotspot: (img.hotspot.0 as u32, img.hotspot.1 as u32),
                     }
                 })
                 .collect();
 
             SizeVariant {
                 size,
+                pixel_size: first_img.image.dimensions(),
                 frames: frame_list,
                 hotspot: (hotspot.0 as u32, hotspot.1 as u32),
             }
@@ -155,6 +236,7 @@ fn convert_windows_cursor_to_meta(path: &Path, frames: Vec<CursorFrame>) -> Curs
         win_names: Vec::new(),
         variants,
         src_cursor_path: Some(path.to_path_buf()),
+        ani_sequence,
     }
 }
 
@@ -176,10 +258,10 @@ fn convert_to_cursor_meta(path: &Path, images: Vec<Image>) -> CursorMeta {
     let mut variants: Vec<SizeVariant> = size_map
         .into_iter()
         .map(|(size, imgs)| {
-            let hotspot = if let Some(first) = imgs.first() {
-                (first.xhot, first.yhot)
+            let (hotspot, pixel_size) = if let Some(first) = imgs.first() {
+                ((first.xhot, first.yhot), (first.width, first.height))
             } else {
-                (0, 0)
+                ((0, 0), (size, size))
             };
 
             let frames: Vec<Frame> = imgs
@@ -187,11 +269,13 @@ fn convert_to_cursor_meta(path: &Path, images: Vec<Image>) -> CursorMeta {
                 .map(|img| Frame {
                     png_path: PathBuf::new(), // will be populated when we extract frames
                     delay_ms: img.delay,
+                    hotspot: (img.xhot, img.yhot),
                 })
                 .collect();
 
             SizeVariant {
                 size,
+                pixel_size,
                 frames,
                 hotspot,
             }
@@ -206,26 +290,84 @@ fn convert_to_cursor_meta(path: &Path, images: Vec<Image>) -> CursorMeta {
         win_names: Vec::new(), // will be populated from mapping config
         variants,
         src_cursor_path: Some(path.to_path_buf()),
+        ani_sequence: None,
+    }
+}
+
+/// Whether `path` is a zip archive that can stand in for an input directory
+pub fn is_cursor_archive(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+}
+
+/// Extracts the `.ani` and `.cur` entries of a zip archive straight into `dest`, dropping
+/// the folders they sit in so the result reads like a flat input directory. When two
+/// entries share a file name the first one is kept. Returns how many files were written.
+pub fn extract_cursor_archive(archive: &Path, dest: &Path) -> Result<usize> {
+    let file =
+        fs::File::open(archive).with_context(|| format!("Failed to open {}", archive.display()))?;
+    let mut zip = zip::ZipArchive::new(file)
+        .with_context(|| format!("{} is not a readable zip archive", archive.display()))?;
+    fs::create_dir_all(dest)?;
+
+    let mut extracted = 0;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)?;
+        if !entry.is_file() {
+            continue;
+        }
+        // Only the file name is used, so entries can't reach outside `dest`
+        let Some(name) = entry
+            .enclosed_name()
+            .and_then(|path| path.file_name().map(PathBuf::from))
+        else {
+            continue;
+        };
+        let is_cursor = name
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("ani") || ext.eq_ignore_ascii_case("cur"));
+        let out = dest.join(&name);
+        if !is_cursor || out.exists() {
+            continue;
+        }
+
+        std::io::copy(&mut entry, &mut fs::File::create(&out)?)?;
+        extracted += 1;
     }
+
+    Ok(extracted)
 }
 
-/// load all cursor files from a directory
-pub fn load_cursor_folder(dir: &Path) -> Result<Vec<CursorMeta>> {
+/// load all cursor files from a directory; files that fail to parse are reported to `log_fn`
+pub fn load_cursor_folder<F>(
+    dir: &Path,
+    max_dimension: u32,
+    mut log_fn: F,
+) -> Result<Vec<CursorMeta>>
+where
+    F: FnMut(LogLevel, String),
+{
     let cursor_files = scan_cursor_dir(dir)?;
     let mut cursors = Vec::new();
 
     for path in cursor_files {
         if is_windows_cursor_file(&path) {
-            match parse_windows_cursor_file(&path) {
-                Ok(frames) => {
-                    let meta = convert_windows_cursor_to_meta(&path, frames);
+            match parse_windows_cursor_file(&path, max_dimension, &mut log_fn) {
+                Ok((frames, ani_sequence)) => {
+                    let meta =
+                        convert_windows_cursor_to_meta(&path, frames, ani_sequence, &mut log_fn);
                     cursors.push(meta);
                 }
                 Err(e) => {
-                    eprintln!(
-                        "Warning: Failed to parse Windows cursor {}: {}",
-                        path.display(),
-                        e
+                    log_fn(
+                        LogLevel::Warn,
+                        format!(
+                            "Warning: Failed to parse Windows cursor {}: {}",
+                            path.display(),
+                            e
+                        ),
                     );
                 }
             }
@@ -238,10 +380,13 @@ pub fn load_cursor_folder(dir: &Path) -> Result<Vec<CursorMeta>> {
                     }
                 }
                 Err(e) => {
-                    eprintln!(
-                        "Warning: Failed to parse X11 cursor {}: {}",
-                        path.display(),
-                        e
+                    log_fn(
+                        LogLevel::Warn,
+                        format!(
+                            "Warning: Failed to parse X11 cursor {}: {}",
+                            path.display(),
+                            e
+                        ),
                     );
                 }
             }
@@ -252,7 +397,10 @@ pub fn load_cursor_folder(dir: &Path) -> Result<Vec<CursorMeta>> {
 }
 
 /// load cursors from a PNG extraction directory (for preview)
-pub fn load_cursor_folder_from_pngs(dir: &Path) -> Result<Vec<CursorMeta>> {
+pub fn load_cursor_folder_from_pngs<F>(dir: &Path, mut log_fn: F) -> Result<Vec<CursorMeta>>
+where
+    F: FnMut(LogLevel, String),
+{
     let mut cursors = Vec::new();
 
     for entry in fs::read_dir(dir)? {
@@ -278,69 +426,52 @@ pub fn load_cursor_folder_from_pngs(dir: &Path) -> Result<Vec<CursorMeta>> {
         let conf_content = fs::read_to_string(&conf_file)?;
         let mut variants_map: HashMap<u32, Vec<PngFrameData>> = HashMap::new();
 
-        for line in conf_content.lines() {
-            let line = line.trim();
-            if line.is_empty() || line.starts_with('#') {
-                continue;
-            }
-
-            let all_parts: Vec<&str> = line.split_whitespace().collect();
-
-            if all_parts.len() < 4 {
-                continue;
-            }
+        for config in conf_content.lines().filter_map(parse_config_line) {
+            // resolve PNG path relative to cursor directory
+            let png_path = if Path::new(&config.filename).is_absolute() {
+                PathBuf::from(config.filename)
+            } else {
+                cursor_dir.join(&config.filename)
+            };
 
-            let size_str = all_parts[0];
-            let hotspot_x_str = all_parts[1];
-            let hotspot_y_str = all_parts[2];
-
-            let (png_filename, delay_str) =
-                if all_parts.len() >= 5 && all_parts.last().unwrap().parse::<u32>().is_ok() {
-                    (
-                        all_parts[3..all_parts.len() - 1].join(" "),
-                        Some(all_parts.last().unwrap()),
-                    )
-                } else {
-                    (all_parts[3..].join(" "), None)
-                };
-
-            if let (Ok(size), Ok(hotspot_x), Ok(hotspot_y)) = (
-                size_str.parse::<u32>(),
-                hotspot_x_str.parse::<u16>(),
-                hotspot_y_str.parse::<u16>(),
-            ) {
-                // resolve PNG path relative to cursor directory
-                let png_path = if Path::new(&png_filename).is_absolute() {
-                    PathBuf::from(png_filename)
-                } else {
-                    cursor_dir.join(&png_filename)
-                };
-
-                let delay_ms = delay_str.and_then(|s| s.parse::<u32>().ok()).unwrap_or(50);
-
-                variants_map.entry(size).or_default().push((
-                    png_path,
-                    delay_ms,
-                    (hotspot_x, hotspot_y),
-                ));
-            }
+            variants_map.entry(config.size).or_default().push((
+                png_path,
+                config.delay,
+                (config.xhot, config.yhot),
+            ));
         }
 
         let mut variants = Vec::new();
         for (size, frames_data) in variants_map {
             let hotspot = frames_data.first().map(|(_, _, h)| *h).unwrap_or((0, 0));
+            // Hotspots are in image pixels, so the editor bounds them by the real dimensions
+            let pixel_size = frames_data
+                .first()
+                .and_then(|(path, _, _)| image::image_dimensions(path).ok())
+                .unwrap_or((size, size));
+            if pixel_size != (size, size) {
+                log_fn(
+                    LogLevel::Warn,
+                    format!(
+                        "Warning: {} size {} is drawn at {}x{} px",
+                        cursor_name, size, pixel_size.0, pixel_size.1
+                    ),
+                );
+            }
             let frames = frames_data
                 .into_iter()
-                .map(|(path, delay, _)| Frame {
+                .map(|(path, delay, hotspot)| Frame {
                     png_path: path,
                     delay_ms: delay,
+                    hotspot,
                 })
                 .collect();
 
             variants.push(SizeVariant {
                 size,
+                pixel_size,
                 frames,
-                hotspot: (hotspot.0 as u32, hotspot.1 as u32),
+                hotspot,
             });
         }
 
@@ -350,9 +481,340 @@ pub fn load_cursor_folder_from_pngs(dir: &Path) -> Result<Vec<CursorMeta>> {
                 win_names: vec![cursor_name],
                 variants,
                 src_cursor_path: Some(cursor_dir),
+                ani_sequence: None,
             });
         }
     }
 
     Ok(cursors)
 }
+
+/// Load a built Hyprcursor theme, either its root or its `hyprcursors/` directory. The
+/// images of every `.hlc` are unpacked into `unpack_dir`, which has to outlive the preview.
+pub fn load_cursor_folder_from_hlc<F>(
+    dir: &Path,
+    unpack_dir: &Path,
+    mut log_fn: F,
+) -> Result<Vec<CursorMeta>>
+where
+    F: FnMut(LogLevel, String),
+{
+    let hlc_dir = if dir.join("hyprcursors").is_dir() {
+        dir.join("hyprcursors")
+    } else {
+        dir.to_path_buf()
+    };
+
+    let mut hlc_files: Vec<PathBuf> = fs::read_dir(&hlc_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "hlc"))
+        .collect();
+    if hlc_files.is_empty() {
+        return Ok(Vec::new());
+    }
+    hlc_files.sort();
+
+    let mut cursors = Vec::new();
+    for path in hlc_files {
+        let cursor_name = path
+            .file_stem()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+            .to_string();
+
+        let shape = match read_hlc(&path) {
+            Ok(shape) => shape,
+            Err(e) => {
+                log_fn(
+                    LogLevel::Warn,
+                    format!("Warning: Failed to read {}: {}", path.display(), e),
+                );
+                continue;
+            }
+        };
+
+        let cursor_dir = unpack_dir.join(&cursor_name);
+        fs::create_dir_all(&cursor_dir)?;
+
+        let mut variants_map: HashMap<u32, ((u32, u32), Vec<Frame>)> = HashMap::new();
+        for img in shape.images {
+            // SVG shapes have no fixed size and nothing here can show them
+            let Ok((width, height)) = image::ImageReader::new(std::io::Cursor::new(&img.data))
+                .with_guessed_format()
+                .map_err(anyhow::Error::from)
+                .and_then(|reader| Ok(reader.into_dimensions()?))
+            else {
+                log_fn(
+                    LogLevel::Warn,
+                    format!(
+                        "Warning: Skipping {} in {}, not a raster image",
+                        img.file,
+                        path.display()
+                    ),
+                );
+                continue;
+            };
+
+            let png_path = cursor_dir.join(&img.file);
+            fs::write(&png_path, &img.data)?;
+
+            // hyprcursor hotspots are fractions of each image's size
+            let hotspot = clamp_hotspot(
+                (
+                    (shape.hotspot.0 * width as f32).round() as u32,
+                    (shape.hotspot.1 * height as f32).round() as u32,
+                ),
+                (width, height),
+            );
+            let (_, frames) = variants_map
+                .entry(img.size)
+                .or_insert_with(|| ((width, height), Vec::new()));
+            frames.push(Frame {
+                png_path,
+                delay_ms: img.delay,
+                hotspot,
+            });
+        }
+
+        let mut variants: Vec<SizeVariant> = variants_map
+            .into_iter()
+            .map(|(size, (pixel_size, frames))| SizeVariant {
+                size,
+                pixel_size,
+                hotspot: frames[0].hotspot,
+                frames,
+            })
+            .collect();
+        variants.sort_by_key(|v| v.size);
+
+        if !variants.is_empty() {
+            cursors.push(CursorMeta {
+                x11_name: cursor_name.clone(),
+                win_names: vec![cursor_name],
+                variants,
+                src_cursor_path: Some(path),
+                ani_sequence: None,
+            });
+        }
+    }
+
+    Ok(cursors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::win2xcur::cur::CursorImage;
+    use image::RgbaImage;
+
+    fn frame(sizes: &[u32], delay: u32) -> CursorFrame {
+        CursorFrame {
+            images: sizes
+                .iter()
+                .map(|&size| CursorImage {
+                    image: RgbaImage::new(size, size),
+                    hotspot: (delay as u16, 0),
+                    nominal_size: size,
+                })
+                .collect(),
+            delay,
+        }
+    }
+
+    #[test]
+    fn test_missing_frame_size_is_padded() {
+        let frames = vec![frame(&[32, 48], 1), frame(&[32], 2), frame(&[32, 48], 3)];
+        let mut warnings = Vec::new();
+        let meta = convert_windows_cursor_to_meta(
+            Path::new("wait.ani"),
+            frames,
+            None,
+            &mut |level, msg| warnings.push((level, msg)),
+        );
+
+        assert_eq!(
+            warnings,
+            vec![(
+                LogLevel::Warn,
+                "Warning: wait is missing size 48 in 1 of 3 frames, reusing neighbouring frames"
+                    .to_string()
+            )]
+        );
+        assert_eq!(meta.variants.len(), 2);
+        for variant in &meta.variants {
+            assert_eq!(variant.frames.len(), 3, "size {}", variant.size);
+        }
+
+        // The padded middle frame keeps its own delay but borrows frame 0's image
+        let large = &meta.variants[1];
+        let delays: Vec<u32> = large.frames.iter().map(|f| f.delay_ms).collect();
+        let hotspots: Vec<u32> = large.frames.iter().map(|f| f.hotspot.0).collect();
+        assert_eq!(delays, vec![1, 2, 3]);
+        assert_eq!(hotspots, vec![1, 1, 3]);
+    }
+
+    #[test]
+    fn test_arrow_without_hotspot_gets_its_tip() {
+        let mut image = RgbaImage::new(32, 32);
+        image.put_pixel(4, 2, image::Rgba([255, 255, 255, 255]));
+        let frames = vec![CursorFrame {
+            images: vec![CursorImage {
+                image,
+                hotspot: (0, 0),
+                nominal_size: 32,
+            }],
+            delay: 0,
+        }];
+
+        let mut messages = Vec::new();
+        let meta = convert_windows_cursor_to_meta(
+            Path::new("Normal.cur"),
+            frames.clone(),
+            None,
+            &mut |_, msg| messages.push(msg),
+        );
+        assert_eq!(meta.variants[0].hotspot, (4, 2));
+        assert_eq!(messages.len(), 1);
+
+        // Other cursors keep the (0, 0) they came with
+        let meta =
+            convert_windows_cursor_to_meta(Path::new("Busy.cur"), frames, None, &mut |_, _| {});
+        assert_eq!(meta.variants[0].hotspot, (0, 0));
+    }
+
+    #[test]
+    fn test_png_dir_keeps_real_image_size_apart_from_nominal() {
+        let dir = tempfile::tempdir().unwrap();
+        let cursor_dir = dir.path().join("hand2");
+        fs::create_dir_all(&cursor_dir).unwrap();
+        // A 32px image listed under size 48, as some HiDPI themes do
+        RgbaImage::new(32, 32)
+            .save(cursor_dir.join("hand2_000.png"))
+            .unwrap();
+        RgbaImage::new(24, 24)
+            .save(cursor_dir.join("hand2_001.png"))
+            .unwrap();
+        fs::write(
+            cursor_dir.join("hand2.conf"),
+            "48\t30\t2\thand2_000.png\t50\n24 3 1 hand2_001.png 50\n",
+        )
+        .unwrap();
+
+        let mut warnings = Vec::new();
+        let mut cursors =
+            load_cursor_folder_from_pngs(dir.path(), |_, msg| warnings.push(msg)).unwrap();
+        assert_eq!(
+            warnings,
+            vec!["Warning: hand2 size 48 is drawn at 32x32 px"]
+        );
+        assert_eq!(cursors.len(), 1);
+        let variants = &mut cursors[0].variants;
+        variants.sort_by_key(|v| v.size);
+        assert_eq!((variants[0].size, variants[0].pixel_size), (24, (24, 24)));
+        assert_eq!((variants[1].size, variants[1].pixel_size), (48, (32, 32)));
+        assert_eq!(variants[1].hotspot, (30, 2));
+    }
+
+    #[test]
+    fn test_hlc_theme_round_trips_to_cursor_meta() {
+        use crate::pipeline::hyprcursor::{HlcCompression, process_shape};
+
+        let dir = tempfile::tempdir().unwrap();
+        let shape_dir = dir.path().join("src").join("left_ptr");
+        let hypr_dir = dir.path().join("theme").join("hyprcursors");
+        fs::create_dir_all(&shape_dir).unwrap();
+        fs::create_dir_all(&hypr_dir).unwrap();
+
+        fs::write(
+            shape_dir.join("meta.hl"),
+            "resize_algorithm = none\n\
+             hotspot_x = 0.25\n\
+             hotspot_y = 0.50\n\
+             define_size = 32, a.png, 40\n\
+             define_size = 32, b.png, 60\n\
+             define_size = 64, c.png, 40\n",
+        )
+        .unwrap();
+        RgbaImage::new(32, 32)
+            .save(shape_dir.join("a.png"))
+            .unwrap();
+        RgbaImage::new(32, 32)
+            .save(shape_dir.join("b.png"))
+            .unwrap();
+        RgbaImage::new(64, 64)
+            .save(shape_dir.join("c.png"))
+            .unwrap();
+        process_shape(
+            &shape_dir,
+            &hypr_dir,
+            "left_ptr",
+            HlcCompression::Deflate,
+            |_, _| {},
+        )
+        .unwrap();
+
+        let unpacked = tempfile::tempdir().unwrap();
+        let cursors =
+            load_cursor_folder_from_hlc(&dir.path().join("theme"), unpacked.path(), |_, _| {})
+                .unwrap();
+        assert_eq!(cursors.len(), 1);
+        let cursor = &cursors[0];
+        assert_eq!(cursor.x11_name, "left_ptr");
+
+        let summary: Vec<(u32, Vec<u32>, (u32, u32))> = cursor
+            .variants
+            .iter()
+            .map(|v| {
+                (
+                    v.size,
+                    v.frames.iter().map(|f| f.delay_ms).collect(),
+                    v.hotspot,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(32, vec![40, 60], (8, 16)), (64, vec![40], (16, 32))]
+        );
+        assert!(
+            cursor.variants[0].frames[1]
+                .png_path
+                .starts_with(unpacked.path())
+        );
+        assert!(cursor.variants[0].frames[1].png_path.exists());
+    }
+
+    #[test]
+    fn test_extract_cursor_archive_flattens_cursor_entries() {
+        use std::io::Write;
+        use zip::write::SimpleFileOptions;
+
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("pack.zip");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+        for (name, data) in [
+            ("Pack/Normal.ani", b"normal".as_slice()),
+            ("Pack/Extras/Busy.CUR", b"busy"),
+            ("Pack/readme.txt", b"hello"),
+            ("Other/Normal.ani", b"shadowed"),
+            ("../Escape.cur", b"escape"),
+        ] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap();
+        assert!(is_cursor_archive(&archive));
+
+        let dest = dir.path().join("extracted");
+        assert_eq!(extract_cursor_archive(&archive, &dest).unwrap(), 2);
+
+        let mut names: Vec<String> = fs::read_dir(&dest)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["Busy.CUR", "Normal.ani"]);
+        assert_eq!(fs::read(dest.join("Normal.ani")).unwrap(), b"normal");
+        assert!(!dir.path().join("Escape.cur").exists());
+    }
+}
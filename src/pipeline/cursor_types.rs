@@ -2,15 +2,21 @@
 
 use std::path::PathBuf;
 
+use super::win2xcur::AniSequence;
+
 #[derive(Debug, Clone)]
 pub struct Frame {
     pub png_path: PathBuf,
     pub delay_ms: u32,
+    // images sharing a nominal size may still point at different pixels
+    pub hotspot: (u32, u32),
 }
 
 #[derive(Debug, Clone)]
 pub struct SizeVariant {
     pub size: u32,
+    // Real image dimensions, which may differ from the nominal `size`
+    pub pixel_size: (u32, u32),
     pub frames: Vec<Frame>,
     pub hotspot: (u32, u32),
 }
@@ -21,4 +27,15 @@ pub struct CursorMeta {
     pub win_names: Vec<String>,
     pub variants: Vec<SizeVariant>,
     pub src_cursor_path: Option<PathBuf>,
+    // Raw frame order and rates, only for cursors loaded straight from an .ani file
+    pub ani_sequence: Option<AniSequence>,
+}
+
+/// Moves `hotspot` onto the last pixel of an image of `pixel_size` if it lies past it. The
+/// editor, the loaders and the converter all clamp this way.
+pub fn clamp_hotspot(hotspot: (u32, u32), pixel_size: (u32, u32)) -> (u32, u32) {
+    (
+        hotspot.0.min(pixel_size.0.saturating_sub(1)),
+        hotspot.1.min(pixel_size.1.saturating_sub(1)),
+    )
 }
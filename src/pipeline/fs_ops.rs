@@ -1,5 +1,6 @@
 use std::fs;
-use std::path::Path;
+use std::io;
+use std::path::{Component, Path, PathBuf};
 
 pub fn ensure_dir<P: AsRef<Path>>(p: P) -> std::io::Result<()> {
     if !p.as_ref().exists() {
@@ -7,3 +8,174 @@ pub fn ensure_dir<P: AsRef<Path>>(p: P) -> std::io::Result<()> {
     }
     Ok(())
 }
+
+/// Recursively copies `src` into `dst`, recreating symlinks instead of following them
+pub fn copy_dir_all(src: &Path, dst: &Path) -> std::io::Result<()> {
+    copy_dir_except(src, dst, &[])
+}
+
+/// Like `copy_dir_all`, but leaves out the entries directly inside `src` named in `skip`
+pub fn copy_dir_except(src: &Path, dst: &Path, skip: &[&str]) -> std::io::Result<()> {
+    fs::create_dir_all(dst)?;
+
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        if skip.iter().any(|name| entry.file_name() == *name) {
+            continue;
+        }
+        let ty = entry.file_type()?;
+        let dst_path = dst.join(entry.file_name());
+
+        if ty.is_dir() {
+            copy_dir_all(&entry.path(), &dst_path)?;
+        } else if ty.is_symlink() {
+            let target = fs::read_link(entry.path())?;
+            symlink_or_copy(&target, &entry.path(), &dst_path)?;
+        } else {
+            fs::copy(entry.path(), dst_path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates `link` pointing at `target`. Where symlinks are unavailable (non-Unix platforms,
+/// or filesystems like FAT that reject them) `resolved`, the file the link stands for, is
+/// copied to `link` instead.
+pub fn symlink_or_copy(target: &Path, resolved: &Path, link: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    match std::os::unix::fs::symlink(target, link) {
+        Ok(()) => return Ok(()),
+        Err(e) if !symlinks_unsupported(&e) => return Err(e),
+        Err(_) => {}
+    }
+    #[cfg(not(unix))]
+    let _ = target;
+
+    if resolved.is_dir() {
+        copy_dir_all(resolved, link)
+    } else {
+        fs::copy(resolved, link).map(|_| ())
+    }
+}
+
+/// Creates `link` as a relative symlink to `target`, wherever the two sit relative to each
+/// other. Falls back to a copy like `symlink_or_copy`.
+pub fn symlink_relative(target: &Path, link: &Path) -> io::Result<()> {
+    symlink_or_copy(&relative_link_target(link, target), target, link)
+}
+
+/// What a symlink at `link` has to contain to resolve to `target`. Both paths must be
+/// absolute, or relative to the same directory.
+pub fn relative_link_target(link: &Path, target: &Path) -> PathBuf {
+    relative_path(link.parent().unwrap_or(Path::new("")), target)
+}
+
+/// Path from the directory `base` to `target`, going up with `..` where they diverge
+pub fn relative_path(base: &Path, target: &Path) -> PathBuf {
+    let base: Vec<Component> = base.components().collect();
+    let target: Vec<Component> = target.components().collect();
+    let common = base.iter().zip(&target).take_while(|(a, b)| a == b).count();
+
+    let mut relative = PathBuf::new();
+    for _ in common..base.len() {
+        relative.push("..");
+    }
+    for component in &target[common..] {
+        relative.push(component);
+    }
+    relative
+}
+
+#[cfg(unix)]
+fn symlinks_unsupported(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::PermissionDenied | io::ErrorKind::Unsupported
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_relative_link_target_same_dir() {
+        assert_eq!(
+            relative_link_target(
+                Path::new("/theme/cursors/hand1"),
+                Path::new("/theme/cursors/pointer")
+            ),
+            PathBuf::from("pointer")
+        );
+        assert_eq!(
+            relative_link_target(Path::new("hand1"), Path::new("pointer")),
+            PathBuf::from("pointer")
+        );
+    }
+
+    #[test]
+    fn test_relative_link_target_nested_dirs() {
+        assert_eq!(
+            relative_link_target(
+                Path::new("/theme/cursors/aliases/hand1"),
+                Path::new("/theme/cursors/pointer")
+            ),
+            PathBuf::from("../pointer")
+        );
+        assert_eq!(
+            relative_link_target(
+                Path::new("/theme/cursors/hand1"),
+                Path::new("/theme/cursors/shapes/pointer")
+            ),
+            PathBuf::from("shapes/pointer")
+        );
+        assert_eq!(
+            relative_link_target(Path::new("/theme/a/b/link"), Path::new("/theme/c/target")),
+            PathBuf::from("../../c/target")
+        );
+    }
+
+    #[test]
+    fn test_symlink_relative_resolves_from_nested_dir() {
+        let dir = tempdir().unwrap();
+        let cursors = dir.path().join("cursors");
+        fs::create_dir_all(cursors.join("aliases")).unwrap();
+        fs::write(cursors.join("pointer"), b"cursor").unwrap();
+
+        let link = cursors.join("aliases").join("hand1");
+        symlink_relative(&cursors.join("pointer"), &link).unwrap();
+        assert_eq!(fs::read(&link).unwrap(), b"cursor");
+
+        // Still valid after moving the whole tree
+        #[cfg(unix)]
+        {
+            assert_eq!(fs::read_link(&link).unwrap(), PathBuf::from("../pointer"));
+            let moved = dir.path().join("moved");
+            fs::rename(&cursors, &moved).unwrap();
+            assert_eq!(
+                fs::read(moved.join("aliases").join("hand1")).unwrap(),
+                b"cursor"
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_dir_all_keeps_links() {
+        let dir = tempdir().unwrap();
+        let src = dir.path().join("cursors");
+        fs::create_dir_all(&src).unwrap();
+        fs::write(src.join("size_hor"), b"arrows").unwrap();
+        symlink_relative(&src.join("size_hor"), &src.join("size_ver")).unwrap();
+
+        // Installing a theme copies its cursors this way; linked duplicates stay links
+        let dst = dir.path().join("installed");
+        copy_dir_all(&src, &dst).unwrap();
+        assert_eq!(
+            fs::read_link(dst.join("size_ver")).unwrap(),
+            PathBuf::from("size_hor")
+        );
+    }
+}
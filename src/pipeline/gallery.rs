@@ -0,0 +1,149 @@
+// Static HTML overview of a converted theme, for a quick visual check
+
+use anyhow::{Result, bail};
+use std::fs;
+use std::path::Path;
+
+use super::cursor_io::load_cursor_folder_from_pngs;
+use super::fs_ops::relative_path;
+use super::log::LogLevel;
+
+/// Writes `theme_dir/index.html` with one tile per cursor in `png_dir`, showing the first
+/// frame of its largest size. Images are linked relative to the theme directory rather than
+/// copied. Returns how many cursors were listed; warnings from reading them go to `log_fn`.
+pub fn write_gallery<F>(
+    png_dir: &Path,
+    theme_dir: &Path,
+    theme_name: &str,
+    log_fn: F,
+) -> Result<usize>
+where
+    F: FnMut(LogLevel, String),
+{
+    let mut cursors = load_cursor_folder_from_pngs(png_dir, log_fn)?;
+    if cursors.is_empty() {
+        bail!("No extracted cursors in {}", png_dir.display());
+    }
+    cursors.sort_by(|a, b| a.x11_name.cmp(&b.x11_name));
+    fs::create_dir_all(theme_dir)?;
+
+    let base = theme_dir.canonicalize()?;
+    let mut tiles = String::new();
+    let mut listed = 0;
+    for cursor in &cursors {
+        let Some(variant) = cursor.variants.iter().max_by_key(|v| v.size) else {
+            continue;
+        };
+        let Some(frame) = variant.frames.first() else {
+            continue;
+        };
+
+        let src = match frame.png_path.canonicalize() {
+            Ok(png) => url_path(&relative_path(&base, &png)),
+            Err(_) => continue,
+        };
+        tiles.push_str(&format!(
+            "<figure><img src=\"{}\" alt=\"{}\"><figcaption>{}</figcaption></figure>\n",
+            html_escape(&src),
+            html_escape(&cursor.x11_name),
+            html_escape(&cursor.x11_name)
+        ));
+        listed += 1;
+    }
+
+    let title = html_escape(theme_name);
+    let html = format!(
+        "<!DOCTYPE html>\n\
+         <html>\n\
+         <head>\n\
+         <meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n\
+         <style>\n\
+         body {{ font-family: sans-serif; background: #ddd; }}\n\
+         main {{ display: flex; flex-wrap: wrap; gap: 12px; }}\n\
+         figure {{ margin: 0; padding: 8px; width: 112px; text-align: center; background: #fff; }}\n\
+         img {{ max-width: 96px; max-height: 96px; image-rendering: pixelated; }}\n\
+         figcaption {{ font-size: 12px; word-break: break-all; }}\n\
+         </style>\n\
+         </head>\n\
+         <body>\n\
+         <h1>{title}</h1>\n\
+         <main>\n\
+         {tiles}\
+         </main>\n\
+         </body>\n\
+         </html>\n"
+    );
+    fs::write(theme_dir.join("index.html"), html)?;
+
+    Ok(listed)
+}
+
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Percent-encodes each segment so names with spaces, `#` or `?` stay one URL path
+fn url_path(path: &Path) -> String {
+    path.components()
+        .map(|c| {
+            c.as_os_str()
+                .to_string_lossy()
+                .bytes()
+                .map(|b| match b {
+                    b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                        (b as char).to_string()
+                    }
+                    _ => format!("%{:02X}", b),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbaImage;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_gallery_escapes_names_and_links_relative_pngs() {
+        let dir = tempdir().unwrap();
+        let png_dir = dir.path().join("png_intermediate");
+        let cursor_dir = png_dir.join("a<b>&c");
+        fs::create_dir_all(&cursor_dir).unwrap();
+        RgbaImage::new(4, 4)
+            .save(cursor_dir.join("frame 1.png"))
+            .unwrap();
+        fs::write(
+            cursor_dir.join("a<b>&c.conf"),
+            "#size\txhot\tyhot\tPath to PNG image\tdelay\n32\t0\t0\tframe 1.png\t50\n",
+        )
+        .unwrap();
+
+        let theme_dir = dir.path().join("Theme");
+        assert_eq!(
+            write_gallery(&png_dir, &theme_dir, "My \"Theme\"", |_, _| {}).unwrap(),
+            1
+        );
+
+        let html = fs::read_to_string(theme_dir.join("index.html")).unwrap();
+        assert!(html.contains("<title>My &quot;Theme&quot;</title>"));
+        assert!(html.contains("<figcaption>a&lt;b&gt;&amp;c</figcaption>"));
+        assert!(html.contains("src=\"../png_intermediate/a%3Cb%3E%26c/frame%201.png\""));
+        assert!(!html.contains("a<b>"));
+    }
+}
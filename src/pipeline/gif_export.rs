@@ -0,0 +1,84 @@
+// Animated GIF export of an editor variant, for sharing previews
+
+use anyhow::{Result, bail};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame as GifFrame};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use crate::model::cursor::Frame;
+
+/// Writes `frames` to `out` as a looping GIF and returns the frames whose PNG could not be
+/// read. A single readable frame gives a static GIF.
+pub fn export_gif(frames: &[Frame], out: &Path) -> Result<Vec<PathBuf>> {
+    let mut skipped = Vec::new();
+    let mut gif_frames = Vec::new();
+
+    for frame in frames {
+        match image::open(&frame.png_path) {
+            Ok(img) => gif_frames.push(GifFrame::from_parts(
+                img.to_rgba8(),
+                0,
+                0,
+                Delay::from_numer_denom_ms(frame.delay_ms, 1),
+            )),
+            Err(_) => skipped.push(frame.png_path.clone()),
+        }
+    }
+
+    if gif_frames.is_empty() {
+        bail!("None of the {} frames have a readable PNG", frames.len());
+    }
+
+    let mut encoder = GifEncoder::new(BufWriter::new(File::create(out)?));
+    if gif_frames.len() > 1 {
+        encoder.set_repeat(Repeat::Infinite)?;
+    }
+    encoder.encode_frames(gif_frames)?;
+
+    Ok(skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::codecs::gif::GifDecoder;
+    use image::{AnimationDecoder, Rgba, RgbaImage};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_export_gif_keeps_delays_and_skips_missing_frames() {
+        let dir = tempdir().unwrap();
+        let mut frames = Vec::new();
+        for (i, delay) in [40u32, 120].into_iter().enumerate() {
+            let png_path = dir.path().join(format!("frame_{}.png", i));
+            RgbaImage::from_pixel(4, 4, Rgba([255, 0, 0, 255]))
+                .save(&png_path)
+                .unwrap();
+            frames.push(Frame {
+                png_path,
+                delay_ms: delay,
+                hotspot: (0, 0),
+            });
+        }
+        let missing = dir.path().join("gone.png");
+        frames.push(Frame {
+            png_path: missing.clone(),
+            delay_ms: 50,
+            hotspot: (0, 0),
+        });
+
+        let out = dir.path().join("wait.gif");
+        let skipped = export_gif(&frames, &out).unwrap();
+        assert_eq!(skipped, vec![missing]);
+
+        let decoded = GifDecoder::new(std::io::BufReader::new(File::open(&out).unwrap()))
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap();
+        let delays: Vec<(u32, u32)> = decoded.iter().map(|f| f.delay().numer_denom_ms()).collect();
+        assert_eq!(delays, vec![(40, 1), (120, 1)]);
+    }
+}
@@ -1,11 +1,135 @@
 use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs::{self, File};
-use std::io::{BufRead, BufReader, Write};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::Path;
-use zip::ZipWriter;
+use std::sync::atomic::AtomicBool;
 use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, SUPPORTED_COMPRESSION_METHODS, ZipArchive, ZipWriter};
 
+use crate::pipeline::limits::DEFAULT_MAX_DIMENSION;
+use crate::pipeline::log::LogLevel;
+use crate::pipeline::theme_build::check_cancelled;
 use crate::pipeline::xcur2png::extractor::{ExtractOptions, extract_to_pngs};
+use crate::pipeline::xcur2png::png_writer::parse_config_line;
+
+/// Compression used for the entries of generated `.hlc` archives
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HlcCompression {
+    #[default]
+    Deflate,
+    // What hyprcursor-util produces; smaller for large themes
+    Zstd,
+    Stored,
+}
+
+impl HlcCompression {
+    pub fn all() -> [HlcCompression; 3] {
+        [
+            HlcCompression::Deflate,
+            HlcCompression::Zstd,
+            HlcCompression::Stored,
+        ]
+    }
+
+    /// The methods the zip crate was built to write, in `all()` order
+    pub fn available() -> Vec<HlcCompression> {
+        Self::all()
+            .into_iter()
+            .filter(|c| SUPPORTED_COMPRESSION_METHODS.contains(&c.method()))
+            .collect()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HlcCompression::Deflate => "Deflate",
+            HlcCompression::Zstd => "Zstd",
+            HlcCompression::Stored => "Stored",
+        }
+    }
+
+    fn method(&self) -> CompressionMethod {
+        match self {
+            // The constants exist whether or not zip was built with the method
+            HlcCompression::Deflate => CompressionMethod::DEFLATE,
+            HlcCompression::Zstd => CompressionMethod::ZSTD,
+            HlcCompression::Stored => CompressionMethod::STORE,
+        }
+    }
+}
+
+/// Value written to the `resize_algorithm` line of generated meta.hl files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResizeAlgorithm {
+    #[default]
+    None,
+    Bilinear,
+    Nearest,
+}
+
+impl ResizeAlgorithm {
+    pub fn all() -> [ResizeAlgorithm; 3] {
+        [
+            ResizeAlgorithm::None,
+            ResizeAlgorithm::Bilinear,
+            ResizeAlgorithm::Nearest,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResizeAlgorithm::None => "none",
+            ResizeAlgorithm::Bilinear => "bilinear",
+            ResizeAlgorithm::Nearest => "nearest",
+        }
+    }
+}
+
+/// Syntax of the manifest and per-shape meta files written when extracting a theme
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ManifestFormat {
+    #[default]
+    Hl,
+    Toml,
+}
+
+impl ManifestFormat {
+    pub fn all() -> [ManifestFormat; 2] {
+        [ManifestFormat::Hl, ManifestFormat::Toml]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ManifestFormat::Hl => "hyprlang",
+            ManifestFormat::Toml => "TOML",
+        }
+    }
+
+    pub fn manifest_file(&self) -> &'static str {
+        match self {
+            ManifestFormat::Hl => "manifest.hl",
+            ManifestFormat::Toml => "manifest.toml",
+        }
+    }
+
+    pub fn meta_file(&self) -> &'static str {
+        match self {
+            ManifestFormat::Hl => "meta.hl",
+            ManifestFormat::Toml => "meta.toml",
+        }
+    }
+}
+
+/// Settings for the Hyprcursor half of a theme build
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HyprcursorOptions {
+    pub compression: HlcCompression,
+    pub resize_algorithm: ResizeAlgorithm,
+    // Per-shape algorithms by X11 name, taking precedence over `resize_algorithm`
+    pub resize_overrides: BTreeMap<String, ResizeAlgorithm>,
+    pub manifest_format: ManifestFormat,
+}
 
 #[derive(Debug, Clone)]
 struct HyprManifest {
@@ -18,15 +142,34 @@ struct HyprManifest {
 impl HyprManifest {
     fn log_info<F>(&self, mut log_fn: F)
     where
-        F: FnMut(String),
+        F: FnMut(LogLevel, String),
     {
-        log_fn(format!(
-            "Manifest: {} v{} - {}",
-            self.name, self.version, self.description
-        ));
+        log_fn(
+            LogLevel::Detail,
+            format!(
+                "Manifest: {} v{} - {}",
+                self.name, self.version, self.description
+            ),
+        );
     }
 }
 
+/// One image of a shape read back from a `.hlc` archive
+#[derive(Debug, Clone)]
+pub struct HlcImage {
+    pub file: String,
+    pub size: u32,
+    pub delay: u32,
+    pub data: Vec<u8>,
+}
+
+/// A compiled shape: hotspot as a fraction of the image size, images in meta order
+#[derive(Debug, Clone)]
+pub struct HlcShape {
+    pub hotspot: (f32, f32),
+    pub images: Vec<HlcImage>,
+}
+
 #[derive(Debug, Clone)]
 struct HyprShape {
     directory: String,
@@ -35,12 +178,14 @@ struct HyprShape {
     resize_algorithm: String,
     images: Vec<HyprImage>,
     overrides: Vec<String>,
+    // Comments and unrecognised lines, written back verbatim when meta.hl is regenerated
+    extra_lines: Vec<String>,
 }
 
 impl HyprShape {
     fn validate<F>(&self, mut log_fn: F) -> Result<()>
     where
-        F: FnMut(String),
+        F: FnMut(LogLevel, String),
     {
         if self.directory.is_empty() {
             return Err(anyhow!("Shape directory cannot be empty"));
@@ -51,16 +196,51 @@ impl HyprShape {
             }
             // Warn if delay is extremely low but not 0
             if img.delay > 0 && img.delay < 10 {
-                log_fn(format!(
-                    "Warning: Very short delay ({}ms) for image {}",
-                    img.delay, img.file
-                ));
+                log_fn(
+                    LogLevel::Warn,
+                    format!(
+                        "Warning: Very short delay ({}ms) for image {}",
+                        img.delay, img.file
+                    ),
+                );
             }
         }
         Ok(())
     }
 }
 
+// Readies the output directory of a build. A directory named after the theme is replaced
+// whole, which a populated one only allows with `allow_clean`. An exact directory belongs to
+// the caller and is written into alongside what it holds; `allow_clean` empties it first.
+fn prepare_output_dir<F>(
+    dir: &Path,
+    exact_output: bool,
+    allow_clean: bool,
+    mut log_fn: F,
+) -> Result<()>
+where
+    F: FnMut(LogLevel, String),
+{
+    let populated = fs::read_dir(dir)
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false);
+    if populated && !exact_output && !allow_clean {
+        return Err(anyhow!(
+            "Output directory {} already exists and is not empty; refusing to clean it",
+            dir.display()
+        ));
+    }
+    if populated && allow_clean {
+        log_fn(
+            LogLevel::Detail,
+            format!("Output directory {:?} exists. Cleaning...", dir),
+        );
+        fs::remove_dir_all(dir)?;
+    }
+    fs::create_dir_all(dir)?;
+    Ok(())
+}
+
 #[derive(Debug, Clone)]
 struct HyprImage {
     file: String,
@@ -77,14 +257,21 @@ struct XConfigEntry {
     delay: u32,
 }
 
+/// Compiles a hyprcursor source tree into a theme. A pre-existing `theme_<name>` output
+/// directory is only wiped and rebuilt when `allow_clean` is set. With `exact_output` the
+/// theme is written into `output_dir` itself, next to its files unless `allow_clean` empties
+/// it first. Setting `cancel` stops before the next shape.
 pub fn create_cursor_theme<F>(
     input_dir: &Path,
     output_dir: Option<&Path>,
     exact_output: bool,
+    allow_clean: bool,
+    compression: HlcCompression,
+    cancel: &AtomicBool,
     mut log_fn: F,
 ) -> Result<()>
 where
-    F: FnMut(String) + Copy,
+    F: FnMut(LogLevel, String) + Copy,
 {
     let input_path = input_dir.canonicalize().context("Invalid input path")?;
 
@@ -116,20 +303,7 @@ where
         parent.join(format!("theme_{}", manifest.name.replace(" ", "_")))
     };
 
-    if out_path.exists() {
-        if !exact_output {
-            log_fn(format!(
-                "Output directory {:?} exists. Cleaning...",
-                out_path
-            ));
-            fs::remove_dir_all(&out_path)?;
-            fs::create_dir_all(&out_path)?;
-        } else {
-            fs::create_dir_all(&out_path)?;
-        }
-    } else {
-        fs::create_dir_all(&out_path)?;
-    }
+    prepare_output_dir(&out_path, exact_output, allow_clean, log_fn)?;
 
     // copy manifest
     fs::copy(
@@ -151,6 +325,7 @@ where
     fs::create_dir_all(&cursors_out_dir)?;
 
     for entry in fs::read_dir(&cursors_src_dir)? {
+        check_cancelled(cancel)?;
         let entry = entry?;
         let path = entry.path();
         if path.is_dir() {
@@ -161,15 +336,18 @@ where
                 .chars()
                 .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.')
             {
-                log_fn(format!("Skipping invalid directory name: {}", dir_name));
+                log_fn(
+                    LogLevel::Detail,
+                    format!("Skipping invalid directory name: {}", dir_name),
+                );
                 continue;
             }
 
-            process_shape(&path, &cursors_out_dir, &dir_name, log_fn)?;
+            process_shape(&path, &cursors_out_dir, &dir_name, compression, log_fn)?;
         }
     }
 
-    log_fn(format!("Theme created at {:?}", out_path));
+    log_fn(LogLevel::Detail, format!("Theme created at {:?}", out_path));
     Ok(())
 }
 
@@ -177,10 +355,11 @@ pub fn process_shape<F>(
     shape_dir: &Path,
     out_dir: &Path,
     shape_name: &str,
+    compression: HlcCompression,
     mut log_fn: F,
 ) -> Result<()>
 where
-    F: FnMut(String),
+    F: FnMut(LogLevel, String),
 {
     // Parse meta
     let meta_path_hl = shape_dir.join("meta.hl");
@@ -223,7 +402,7 @@ where
     let file = File::create(&zip_path)?;
     let mut zip = ZipWriter::new(file);
     let options = SimpleFileOptions::default()
-        .compression_method(zip::CompressionMethod::Deflated)
+        .compression_method(compression.method())
         .unix_permissions(0o644);
 
     // Add meta file
@@ -239,16 +418,20 @@ where
     }
 
     zip.finish()?;
-    log_fn(format!("Created {}.hlc", shape_name));
+    log_fn(LogLevel::Detail, format!("Created {}.hlc", shape_name));
 
     Ok(())
 }
 
+/// Extracts one Xcursor file into a Hyprcursor shape source directory. Annotations from an
+/// existing meta file in that directory, or else from `previous_hlc`, are carried over.
 pub fn extract_xcursor_to_hypr_source(
     xcursor_path: &Path,
     output_dir: &Path,
-    resize_algo: Option<&str>,
+    hyprcursor: &HyprcursorOptions,
     overrides: Vec<String>,
+    previous_hlc: Option<&Path>,
+    max_dimension: u32,
 ) -> Result<()> {
     let stem = xcursor_path
         .file_stem()
@@ -258,7 +441,12 @@ pub fn extract_xcursor_to_hypr_source(
     let shape_dir = output_dir.join(&stem);
     fs::create_dir_all(&shape_dir)?;
 
-    let options = ExtractOptions::new().with_prefix(&stem).with_config(true);
+    let previous = previous_shape(&shape_dir, previous_hlc, &stem);
+
+    let options = ExtractOptions::new()
+        .with_prefix(&stem)
+        .with_config(true)
+        .with_max_dimension(max_dimension);
     extract_to_pngs(xcursor_path, &shape_dir, &options)?;
 
     let config_path = shape_dir.join(format!("{}.conf", stem));
@@ -271,61 +459,185 @@ pub fn extract_xcursor_to_hypr_source(
         return Err(anyhow!("Empty config for {}", stem));
     }
 
-    let meta_path = shape_dir.join("meta.hl");
-    let mut meta_file = File::create(meta_path)?;
+    write_meta(
+        &shape_dir,
+        hyprcursor.manifest_format,
+        &entries,
+        shape_resize_algo(
+            &stem,
+            hyprcursor.resize_algorithm.name(),
+            &hyprcursor.resize_overrides,
+        ),
+        &overrides,
+        previous.as_ref(),
+    )?;
 
-    let algo = resize_algo.unwrap_or("none");
-    writeln!(meta_file, "resize_algorithm = {}", algo)?;
+    fs::remove_file(config_path)?;
+    Ok(())
+}
 
-    let first = &entries[0];
-    if first.size > 0 {
-        writeln!(
-            meta_file,
-            "hotspot_x = {:.2}",
-            first.hotspot_x as f32 / first.size as f32
-        )?;
-        writeln!(
-            meta_file,
-            "hotspot_y = {:.2}",
-            first.hotspot_y as f32 / first.size as f32
-        )?;
-    } else {
-        writeln!(meta_file, "hotspot_x = 0.0")?;
-        writeln!(meta_file, "hotspot_y = 0.0")?;
-    }
-    writeln!(meta_file)?;
+// An overridden shape always gets its own algorithm written, even `none`; the rest use
+// `resize_algo`
+fn shape_resize_algo<'a>(
+    shape_name: &str,
+    resize_algo: &'a str,
+    resize_overrides: &BTreeMap<String, ResizeAlgorithm>,
+) -> &'a str {
+    resize_overrides
+        .get(shape_name)
+        .map_or(resize_algo, |algo| algo.name())
+}
 
-    for entry in &entries {
-        let file_name = Path::new(&entry.image)
-            .file_name()
-            .ok_or_else(|| anyhow!("Invalid image path: {}", entry.image))?
-            .to_string_lossy();
-        writeln!(
-            meta_file,
-            "define_size = {}, {}, {}",
-            entry.size, file_name, entry.delay
-        )?;
+// The shape's earlier meta, so regenerating it keeps hand-written annotations
+fn previous_shape(
+    shape_dir: &Path,
+    hlc_path: Option<&Path>,
+    shape_name: &str,
+) -> Option<HyprShape> {
+    for format in ManifestFormat::all() {
+        let meta_path = shape_dir.join(format.meta_file());
+        if meta_path.exists() {
+            let content = fs::read_to_string(&meta_path).ok()?;
+            return parse_meta_str(&content, shape_name, format).ok();
+        }
     }
-    writeln!(meta_file)?;
 
-    for ov in overrides {
-        writeln!(meta_file, "define_override = {}", ov)?;
+    let file = File::open(hlc_path?).ok()?;
+    let mut archive = ZipArchive::new(file).ok()?;
+    for format in ManifestFormat::all() {
+        if let Ok(mut meta) = archive.by_name(format.meta_file()) {
+            let mut content = String::new();
+            meta.read_to_string(&mut content).ok()?;
+            return parse_meta_str(&content, shape_name, format).ok();
+        }
     }
+    None
+}
 
-    fs::remove_file(config_path)?;
+// Writes the shape's meta in `format`, removing one in the other format that would
+// otherwise take precedence when the shape is compiled
+fn write_meta(
+    shape_dir: &Path,
+    format: ManifestFormat,
+    entries: &[XConfigEntry],
+    resize_algo: &str,
+    overrides: &[String],
+    previous: Option<&HyprShape>,
+) -> Result<()> {
+    let meta = match format {
+        ManifestFormat::Hl => format_meta_hl(entries, resize_algo, overrides, previous)?,
+        ManifestFormat::Toml => format_meta_toml(entries, resize_algo, overrides, previous)?,
+    };
+    fs::write(shape_dir.join(format.meta_file()), meta)?;
+
+    for other in ManifestFormat::all().into_iter().filter(|f| *f != format) {
+        let stale = shape_dir.join(other.meta_file());
+        if stale.exists() {
+            fs::remove_file(stale)?;
+        }
+    }
     Ok(())
 }
 
+/// Reads a compiled `.hlc` shape back, using its meta.hl or meta.toml
+pub fn read_hlc(path: &Path) -> Result<HlcShape> {
+    let shape_name = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("cursor");
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut archive = ZipArchive::new(file)?;
+
+    let mut content = String::new();
+    let shape = if let Ok(mut meta) = archive.by_name("meta.hl") {
+        meta.read_to_string(&mut content)?;
+        parse_meta_hl_str(&content, shape_name)
+    } else if let Ok(mut meta) = archive.by_name("meta.toml") {
+        meta.read_to_string(&mut content)?;
+        parse_meta_toml_str(&content, shape_name)?
+    } else {
+        return Err(anyhow!("No meta file found in {}", path.display()));
+    };
+
+    let mut images = Vec::new();
+    for img in &shape.images {
+        let mut data = Vec::new();
+        archive
+            .by_name(&img.file)
+            .with_context(|| format!("Image {} missing from {}", img.file, path.display()))?
+            .read_to_end(&mut data)?;
+        images.push(HlcImage {
+            file: img.file.clone(),
+            size: img.size,
+            delay: img.delay,
+            data,
+        });
+    }
+
+    Ok(HlcShape {
+        hotspot: (shape.hotspot_x, shape.hotspot_y),
+        images,
+    })
+}
+
+/// Description written to generated manifests when none is given
+const DEFAULT_DESCRIPTION: &str = "Automatically extracted with ani2hyprtui";
+
+/// What `extract_xcursor_theme` writes, and where
+#[derive(Debug, Clone, Copy)]
+pub struct ThemeExtractOptions<'a> {
+    /// Resize algorithms and manifest format of the written sources
+    pub hyprcursor: &'a HyprcursorOptions,
+    /// Manifest description; `None` or blank uses a generic one
+    pub description: Option<&'a str>,
+    /// Write straight into the output directory instead of `extracted_<name>` inside it,
+    /// keeping the files already there
+    pub exact_output: bool,
+    /// Let a populated output directory be emptied first; without it an existing
+    /// `extracted_<name>` is refused and an exact directory is written into as it is
+    pub allow_clean: bool,
+    /// Largest image width or height read from the theme's cursors
+    pub max_dimension: u32,
+}
+
+impl<'a> ThemeExtractOptions<'a> {
+    pub fn new(hyprcursor: &'a HyprcursorOptions) -> Self {
+        Self {
+            hyprcursor,
+            description: None,
+            exact_output: false,
+            allow_clean: false,
+            max_dimension: DEFAULT_MAX_DIMENSION,
+        }
+    }
+}
+
+/// Unpacks an XCursor theme into hyprcursor sources. As with `create_cursor_theme`, an
+/// existing `extracted_<name>` directory is only replaced when `allow_clean` is set, and an
+/// exact output directory is only emptied with it.
+/// Shapes named in the resize overrides get their own resize algorithm. Setting `cancel`
+/// stops before the next cursor.
 pub fn extract_xcursor_theme<F>(
     input_path: &Path,
     output_dir: Option<&Path>,
-    resize_algo: Option<&str>,
-    exact_output: bool,
+    options: &ThemeExtractOptions,
+    cancel: &AtomicBool,
     mut log_fn: F,
 ) -> Result<()>
 where
-    F: FnMut(String),
+    F: FnMut(LogLevel, String),
 {
+    let ThemeExtractOptions {
+        hyprcursor,
+        description,
+        exact_output,
+        allow_clean,
+        max_dimension,
+    } = *options;
+    let resize_algo = hyprcursor.resize_algorithm.name();
+    let resize_overrides = &hyprcursor.resize_overrides;
+    let format = hyprcursor.manifest_format;
+
     let input_path = input_path.canonicalize().context("Invalid input path")?;
 
     let cursors_path = input_path.join("cursors");
@@ -351,36 +663,24 @@ where
         parent.join(format!("extracted_{}", theme_name))
     };
 
-    if out_dir.exists() {
-        if !exact_output {
-            log_fn(format!(
-                "Output directory {:?} exists. Cleaning...",
-                out_dir
-            ));
-            fs::remove_dir_all(&out_dir)?;
-            fs::create_dir_all(&out_dir)?;
-        } else {
-            fs::create_dir_all(&out_dir)?;
-        }
-    } else {
-        fs::create_dir_all(&out_dir)?;
-    }
+    prepare_output_dir(&out_dir, exact_output, allow_clean, &mut log_fn)?;
 
     // Write Manifest
-    let manifest_path = out_dir.join("manifest.hl");
-    let mut manifest_file = File::create(manifest_path)?;
-    writeln!(manifest_file, "name = {}", theme_name)?;
-    writeln!(
-        manifest_file,
-        "description = Automatically extracted with ani2hyprtui"
-    )?;
-    writeln!(manifest_file, "version = 1.0")?;
-    writeln!(manifest_file, "cursors_directory = hyprcursors")?;
+    let description = description
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .unwrap_or(DEFAULT_DESCRIPTION);
+    let manifest = match format {
+        ManifestFormat::Hl => format_manifest_hl(&theme_name, description),
+        ManifestFormat::Toml => format_manifest_toml(&theme_name, description),
+    };
+    fs::write(out_dir.join(format.manifest_file()), manifest)?;
 
     let hyprcursors_dir = out_dir.join("hyprcursors");
     fs::create_dir_all(&hyprcursors_dir)?;
 
     for entry in fs::read_dir(&cursors_path)? {
+        check_cancelled(cancel)?;
         let entry = entry?;
         let path = entry.path();
 
@@ -394,69 +694,44 @@ where
             .ok_or_else(|| anyhow!("Invalid cursor filename"))?
             .to_string_lossy()
             .to_string();
-        log_fn(format!("Processing {}", stem));
+        log_fn(LogLevel::Detail, format!("Processing {}", stem));
 
         let shape_dir = hyprcursors_dir.join(&stem);
         fs::create_dir_all(&shape_dir)?;
 
+        // A theme built by us before still has its compiled shapes next to `cursors`
+        let previous_hlc = input_path.join("hyprcursors").join(format!("{}.hlc", stem));
+        let previous = previous_shape(&shape_dir, Some(&previous_hlc), &stem);
+
         // extract using xcur2png logic
-        let options = ExtractOptions::new().with_prefix(&stem).with_config(true);
+        let options = ExtractOptions::new()
+            .with_prefix(&stem)
+            .with_config(true)
+            .with_max_dimension(max_dimension);
 
         extract_to_pngs(&path, &shape_dir, &options)?;
 
-        // read the generated config to build meta.hl
+        // read the generated config to build the shape's meta
         let config_path = shape_dir.join(format!("{}.conf", stem));
         if !config_path.exists() {
-            log_fn(format!("Warning: No config generated for {}", stem));
+            log_fn(
+                LogLevel::Warn,
+                format!("Warning: No config generated for {}", stem),
+            );
             continue;
         }
 
         let entries = parse_xconfig(&config_path)?;
         if entries.is_empty() {
-            log_fn(format!("Warning: Empty config for {}", stem));
+            log_fn(
+                LogLevel::Warn,
+                format!("Warning: Empty config for {}", stem),
+            );
             continue;
         }
 
-        // Generate meta.hl
-        let meta_path = shape_dir.join("meta.hl");
-        let mut meta_file = File::create(meta_path)?;
-
-        let algo = resize_algo.unwrap_or("none");
-        writeln!(meta_file, "resize_algorithm = {}", algo)?;
-
-        // Calculate relative hotspot from the first entry
-        let first = &entries[0];
-        if first.size > 0 {
-            writeln!(
-                meta_file,
-                "hotspot_x = {:.2}",
-                first.hotspot_x as f32 / first.size as f32
-            )?;
-            writeln!(
-                meta_file,
-                "hotspot_y = {:.2}",
-                first.hotspot_y as f32 / first.size as f32
-            )?;
-        } else {
-            writeln!(meta_file, "hotspot_x = 0.0")?;
-            writeln!(meta_file, "hotspot_y = 0.0")?;
-        }
-        writeln!(meta_file)?;
-
-        for entry in &entries {
-            let file_name = Path::new(&entry.image)
-                .file_name()
-                .ok_or_else(|| anyhow!("Invalid image path: {}", entry.image))?
-                .to_string_lossy();
-            writeln!(
-                meta_file,
-                "define_size = {}, {}, {}",
-                entry.size, file_name, entry.delay
-            )?;
-        }
-        writeln!(meta_file)?;
-
         // Find symlinks pointing to this file
+        let mut overrides = Vec::new();
         for sub_entry in fs::read_dir(&cursors_path)? {
             let sub_entry = sub_entry?;
             let sub_path = sub_entry.path();
@@ -467,18 +742,180 @@ where
                 let sym_name = sub_path
                     .file_stem()
                     .ok_or_else(|| anyhow!("Invalid symlink filename"))?
-                    .to_string_lossy();
-                writeln!(meta_file, "define_override = {}", sym_name)?;
+                    .to_string_lossy()
+                    .to_string();
+                overrides.push(sym_name);
             }
         }
+        overrides.sort();
+
+        write_meta(
+            &shape_dir,
+            format,
+            &entries,
+            shape_resize_algo(&stem, resize_algo, resize_overrides),
+            &overrides,
+            previous.as_ref(),
+        )?;
 
         fs::remove_file(config_path)?;
     }
 
-    log_fn(format!("Extracted to {:?}", out_dir));
+    log_fn(LogLevel::Detail, format!("Extracted to {:?}", out_dir));
     Ok(())
 }
 
+// Manifest in the same key order `hyprcursor-util --extract` writes. hyprlang reads `#` as
+// the start of a comment, so it is doubled to keep it in the description.
+fn format_manifest_hl(theme_name: &str, description: &str) -> String {
+    format!(
+        "name = {}\n\
+         description = {}\n\
+         version = 0.1\n\
+         cursors_directory = hyprcursors\n",
+        theme_name,
+        description.replace('#', "##")
+    )
+}
+
+// Same keys as `format_manifest_hl`, under the `[General]` table hyprcursor reads
+fn format_manifest_toml(theme_name: &str, description: &str) -> String {
+    format!(
+        "[General]\n\
+         name = {}\n\
+         description = {}\n\
+         version = \"0.1\"\n\
+         cursors_directory = \"hyprcursors\"\n",
+        toml_string(theme_name),
+        toml_string(description)
+    )
+}
+
+// Shape meta laid out like `hyprcursor-util --extract`: resize algorithm, relative hotspot
+// from the first image, a blank line, one define_size per image, a blank line, overrides.
+// Comments and unknown keys from `previous` go first; its resize algorithm is replaced by
+// `resize_algo`, so per-shape choices belong in the resize overrides.
+fn format_meta_hl(
+    entries: &[XConfigEntry],
+    resize_algo: &str,
+    overrides: &[String],
+    previous: Option<&HyprShape>,
+) -> Result<String> {
+    let mut meta = String::new();
+    if let Some(previous) = previous.filter(|p| !p.extra_lines.is_empty()) {
+        for line in &previous.extra_lines {
+            meta.push_str(line);
+            meta.push('\n');
+        }
+        meta.push('\n');
+    }
+
+    meta.push_str(&format!("resize_algorithm = {}\n", resize_algo));
+
+    let (hotspot_x, hotspot_y) = match entries.first() {
+        Some(first) if first.size > 0 => (
+            first.hotspot_x as f32 / first.size as f32,
+            first.hotspot_y as f32 / first.size as f32,
+        ),
+        _ => (0.0, 0.0),
+    };
+    meta.push_str(&format!(
+        "hotspot_x = {:.2}\nhotspot_y = {:.2}\n\n",
+        hotspot_x, hotspot_y
+    ));
+
+    for entry in entries {
+        let file_name = Path::new(&entry.image)
+            .file_name()
+            .ok_or_else(|| anyhow!("Invalid image path: {}", entry.image))?
+            .to_string_lossy();
+        meta.push_str(&format!(
+            "define_size = {}, {}, {}\n",
+            entry.size, file_name, entry.delay
+        ));
+    }
+    meta.push('\n');
+
+    for ov in overrides {
+        meta.push_str(&format!("define_override = {}\n", ov));
+    }
+
+    Ok(meta)
+}
+
+// TOML counterpart of `format_meta_hl` in hyprcursor's schema: the keys sit under
+// `[General]`, and define_size and define_override hold every entry joined by ';'.
+// Only comment lines carry over from `previous`, since its unknown keys may be
+// hyprlang syntax.
+fn format_meta_toml(
+    entries: &[XConfigEntry],
+    resize_algo: &str,
+    overrides: &[String],
+    previous: Option<&HyprShape>,
+) -> Result<String> {
+    let mut meta = String::new();
+    let comments: Vec<&String> = previous
+        .map(|p| {
+            p.extra_lines
+                .iter()
+                .filter(|l| l.trim_start().starts_with('#'))
+                .collect()
+        })
+        .unwrap_or_default();
+    if !comments.is_empty() {
+        for line in comments {
+            meta.push_str(line);
+            meta.push('\n');
+        }
+        meta.push('\n');
+    }
+
+    meta.push_str("[General]\n");
+    meta.push_str(&format!(
+        "resize_algorithm = {}\n",
+        toml_string(resize_algo)
+    ));
+
+    let (hotspot_x, hotspot_y) = match entries.first() {
+        Some(first) if first.size > 0 => (
+            first.hotspot_x as f32 / first.size as f32,
+            first.hotspot_y as f32 / first.size as f32,
+        ),
+        _ => (0.0, 0.0),
+    };
+    meta.push_str(&format!(
+        "hotspot_x = {:.2}\nhotspot_y = {:.2}\n",
+        hotspot_x, hotspot_y
+    ));
+
+    let mut sizes = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let file_name = Path::new(&entry.image)
+            .file_name()
+            .ok_or_else(|| anyhow!("Invalid image path: {}", entry.image))?
+            .to_string_lossy();
+        sizes.push(format!("{}, {}, {}", entry.size, file_name, entry.delay));
+    }
+    meta.push_str(&format!(
+        "define_size = {}\n",
+        toml_string(&sizes.join(";"))
+    ));
+
+    if !overrides.is_empty() {
+        meta.push_str(&format!(
+            "define_override = {}\n",
+            toml_string(&overrides.join(";"))
+        ));
+    }
+
+    Ok(meta)
+}
+
+// Quoted and escaped TOML string literal
+fn toml_string(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
 fn parse_manifest_hl(path: &Path) -> Result<HyprManifest> {
     let file = File::open(path)?;
     let reader = BufReader::new(file);
@@ -500,7 +937,8 @@ fn parse_manifest_hl(path: &Path) -> Result<HyprManifest> {
             let val = val.trim();
             match key {
                 "name" => name = val.to_string(),
-                "description" => description = val.to_string(),
+                // `format_manifest_hl` doubles `#` to keep it out of hyprlang comments
+                "description" => description = val.replace("##", "#"),
                 "version" => version = val.to_string(),
                 "cursors_directory" => cursors_directory = val.to_string(),
                 _ => {}
@@ -518,7 +956,8 @@ fn parse_manifest_hl(path: &Path) -> Result<HyprManifest> {
 
 fn parse_manifest_toml(path: &Path) -> Result<HyprManifest> {
     let content = fs::read_to_string(path)?;
-    let value = content.parse::<toml::Table>()?;
+    let table = content.parse::<toml::Table>()?;
+    let value = general_table(&table).with_context(|| path.display().to_string())?;
 
     Ok(HyprManifest {
         name: value
@@ -544,10 +983,37 @@ fn parse_manifest_toml(path: &Path) -> Result<HyprManifest> {
     })
 }
 
+// hyprcursor keeps its TOML keys under `[General]`
+fn general_table(table: &toml::Table) -> Result<&toml::Table> {
+    table
+        .get("General")
+        .and_then(|v| v.as_table())
+        .ok_or_else(|| anyhow!("Missing [General] table"))
+}
+
+// One `define_size` value: size, file[, delay]
+fn parse_define_size(val: &str) -> Option<HyprImage> {
+    let parts: Vec<&str> = val.split(',').map(|s| s.trim()).collect();
+    if parts.len() < 2 {
+        return None;
+    }
+    Some(HyprImage {
+        size: parts[0].parse().unwrap_or(0),
+        file: parts[1].to_string(),
+        delay: parts.get(2).and_then(|d| d.parse().ok()).unwrap_or(0),
+    })
+}
+
+// Entries of a ';'-separated define_size or define_override value
+fn split_defines(val: &str) -> impl Iterator<Item = &str> {
+    val.split(';').map(|s| s.trim()).filter(|s| !s.is_empty())
+}
+
 fn parse_meta_hl(path: &Path, shape_name: &str) -> Result<HyprShape> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
+    Ok(parse_meta_hl_str(&fs::read_to_string(path)?, shape_name))
+}
 
+fn parse_meta_hl_str(content: &str, shape_name: &str) -> HyprShape {
     let mut shape = HyprShape {
         directory: shape_name.to_string(),
         hotspot_x: 0.0,
@@ -555,12 +1021,16 @@ fn parse_meta_hl(path: &Path, shape_name: &str) -> Result<HyprShape> {
         resize_algorithm: "none".to_string(),
         images: Vec::new(),
         overrides: Vec::new(),
+        extra_lines: Vec::new(),
     };
 
-    for line in reader.lines() {
-        let line = line?;
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') {
+    for raw_line in content.lines() {
+        let line = raw_line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('#') {
+            shape.extra_lines.push(raw_line.trim_end().to_string());
             continue;
         }
 
@@ -571,40 +1041,46 @@ fn parse_meta_hl(path: &Path, shape_name: &str) -> Result<HyprShape> {
                 "hotspot_x" => shape.hotspot_x = val.parse().unwrap_or(0.0),
                 "hotspot_y" => shape.hotspot_y = val.parse().unwrap_or(0.0),
                 "resize_algorithm" => shape.resize_algorithm = val.to_string(),
-                "define_size" => {
-                    // val = size, file, delay
-                    let parts: Vec<&str> = val.split(',').map(|s| s.trim()).collect();
-                    if parts.len() >= 3 {
-                        shape.images.push(HyprImage {
-                            size: parts[0].parse().unwrap_or(0),
-                            file: parts[1].to_string(),
-                            delay: parts[2].parse().unwrap_or(0),
-                        });
-                    }
-                }
-                "define_override" => shape.overrides.push(val.to_string()),
-                _ => {}
+                "define_size" => shape.images.extend(parse_define_size(val)),
+                // hyprlang allows several overrides separated by ';'
+                "define_override" => shape
+                    .overrides
+                    .extend(split_defines(val).map(|s| s.to_string())),
+                _ => shape.extra_lines.push(raw_line.trim_end().to_string()),
             }
+        } else {
+            shape.extra_lines.push(raw_line.trim_end().to_string());
         }
     }
 
-    Ok(shape)
+    shape
+}
+
+fn parse_meta_str(content: &str, shape_name: &str, format: ManifestFormat) -> Result<HyprShape> {
+    match format {
+        ManifestFormat::Hl => Ok(parse_meta_hl_str(content, shape_name)),
+        ManifestFormat::Toml => parse_meta_toml_str(content, shape_name),
+    }
 }
 
 fn parse_meta_toml(path: &Path, shape_name: &str) -> Result<HyprShape> {
-    let content = fs::read_to_string(path)?;
-    let table = content.parse::<toml::Table>()?;
+    parse_meta_toml_str(&fs::read_to_string(path)?, shape_name)
+}
+
+fn parse_meta_toml_str(content: &str, shape_name: &str) -> Result<HyprShape> {
+    let root = content.parse::<toml::Table>()?;
+    let table = general_table(&root)?;
+    let float = |key: &str| {
+        table
+            .get(key)
+            .and_then(|v| v.as_float().or(v.as_integer().map(|i| i as f64)))
+            .unwrap_or(0.0) as f32
+    };
 
     let mut shape = HyprShape {
         directory: shape_name.to_string(),
-        hotspot_x: table
-            .get("hotspot_x")
-            .and_then(|v| v.as_float())
-            .unwrap_or(0.0) as f32,
-        hotspot_y: table
-            .get("hotspot_y")
-            .and_then(|v| v.as_float())
-            .unwrap_or(0.0) as f32,
+        hotspot_x: float("hotspot_x"),
+        hotspot_y: float("hotspot_y"),
         resize_algorithm: table
             .get("resize_algorithm")
             .and_then(|v| v.as_str())
@@ -612,30 +1088,23 @@ fn parse_meta_toml(path: &Path, shape_name: &str) -> Result<HyprShape> {
             .to_string(),
         images: Vec::new(),
         overrides: Vec::new(),
+        extra_lines: content
+            .lines()
+            .filter(|l| l.trim_start().starts_with('#'))
+            .map(|l| l.trim_end().to_string())
+            .collect(),
     };
 
-    if let Some(sizes) = table.get("sizes").and_then(|v| v.as_array()) {
-        for size_entry in sizes {
-            if let Some(entry) = size_entry.as_table() {
-                shape.images.push(HyprImage {
-                    size: entry.get("size").and_then(|v| v.as_integer()).unwrap_or(0) as u32,
-                    file: entry
-                        .get("file")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("")
-                        .to_string(),
-                    delay: entry.get("delay").and_then(|v| v.as_integer()).unwrap_or(0) as u32,
-                });
-            }
-        }
+    if let Some(sizes) = table.get("define_size").and_then(|v| v.as_str()) {
+        shape
+            .images
+            .extend(split_defines(sizes).filter_map(parse_define_size));
     }
 
-    if let Some(overrides) = table.get("overrides").and_then(|v| v.as_array()) {
-        for ov in overrides {
-            if let Some(s) = ov.as_str() {
-                shape.overrides.push(s.to_string());
-            }
-        }
+    if let Some(overrides) = table.get("define_override").and_then(|v| v.as_str()) {
+        shape
+            .overrides
+            .extend(split_defines(overrides).map(|s| s.to_string()));
     }
 
     Ok(shape)
@@ -647,24 +1116,514 @@ fn parse_xconfig(path: &Path) -> Result<Vec<XConfigEntry>> {
     let mut entries = Vec::new();
 
     for line in reader.lines() {
-        let line = line?;
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') {
-            continue;
-        }
-
-        // Format: size xhot yhot filename delay
-        let parts: Vec<&str> = line.split('\t').collect();
-        if parts.len() >= 5 {
+        if let Some(config) = parse_config_line(&line?) {
             entries.push(XConfigEntry {
-                size: parts[0].parse().unwrap_or(0),
-                hotspot_x: parts[1].parse().unwrap_or(0),
-                hotspot_y: parts[2].parse().unwrap_or(0),
-                image: parts[3].to_string(),
-                delay: parts[4].parse().unwrap_or(0),
+                size: config.size,
+                hotspot_x: config.xhot,
+                hotspot_y: config.yhot,
+                image: config.filename,
+                delay: config.delay,
             });
         }
     }
 
     Ok(entries)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_meta_hl_matches_hyprcursor_util_layout() {
+        let entries = vec![
+            XConfigEntry {
+                size: 32,
+                hotspot_x: 8,
+                hotspot_y: 4,
+                image: "left_ptr_000.png".to_string(),
+                delay: 50,
+            },
+            XConfigEntry {
+                size: 48,
+                hotspot_x: 12,
+                hotspot_y: 6,
+                image: "left_ptr_001.png".to_string(),
+                delay: 50,
+            },
+        ];
+        let overrides = vec!["arrow".to_string(), "default".to_string()];
+
+        let meta = format_meta_hl(&entries, "none", &overrides, None).unwrap();
+        assert_eq!(
+            meta,
+            "resize_algorithm = none\n\
+             hotspot_x = 0.25\n\
+             hotspot_y = 0.12\n\
+             \n\
+             define_size = 32, left_ptr_000.png, 50\n\
+             define_size = 48, left_ptr_001.png, 50\n\
+             \n\
+             define_override = arrow\n\
+             define_override = default\n"
+        );
+
+        assert_eq!(
+            format_manifest_hl("Test", DEFAULT_DESCRIPTION),
+            "name = Test\n\
+             description = Automatically extracted with ani2hyprtui\n\
+             version = 0.1\n\
+             cursors_directory = hyprcursors\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_meta_hl_hyprlang_variants() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("meta.hl");
+        fs::write(
+            &path,
+            "resize_algorithm = bilinear\n\
+             hotspot_x = 0.5\n\
+             hotspot_y = 0.5\n\
+             define_size = 0, shape.svg\n\
+             define_size = 32, shape_000.png, 40\n\
+             define_override = hand1;hand2\n",
+        )
+        .unwrap();
+
+        let shape = parse_meta_hl(&path, "shape").unwrap();
+        assert_eq!(shape.images.len(), 2);
+        assert_eq!(shape.images[0].delay, 0);
+        assert_eq!(shape.images[1].delay, 40);
+        assert_eq!(shape.overrides, vec!["hand1", "hand2"]);
+    }
+
+    #[test]
+    fn test_extract_stops_when_cancelled() {
+        let dir = tempdir().unwrap();
+        let theme = dir.path().join("Bibata");
+        fs::create_dir_all(theme.join("cursors")).unwrap();
+        fs::write(theme.join("cursors").join("left_ptr"), b"not parsed").unwrap();
+
+        let err = extract_xcursor_theme(
+            &theme,
+            Some(&dir.path().join("out")),
+            &ThemeExtractOptions {
+                hyprcursor: &HyprcursorOptions::default(),
+                description: None,
+                exact_output: true,
+                allow_clean: false,
+                max_dimension: DEFAULT_MAX_DIMENSION,
+            },
+            &AtomicBool::new(true),
+            |_, _| {},
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Cancelled");
+        assert!(
+            !dir.path()
+                .join("out")
+                .join("hyprcursors")
+                .join("left_ptr")
+                .exists()
+        );
+    }
+
+    #[test]
+    fn test_extract_keeps_existing_output_without_allow_clean() {
+        let dir = tempdir().unwrap();
+        let theme = dir.path().join("Bibata");
+        fs::create_dir_all(theme.join("cursors")).unwrap();
+        let out = dir.path().join("out");
+        let existing = out.join("extracted_Bibata");
+        fs::create_dir_all(&existing).unwrap();
+        fs::write(existing.join("notes.txt"), "keep me").unwrap();
+
+        let err = extract_xcursor_theme(
+            &theme,
+            Some(&out),
+            &ThemeExtractOptions {
+                hyprcursor: &HyprcursorOptions::default(),
+                description: None,
+                exact_output: false,
+                allow_clean: false,
+                max_dimension: DEFAULT_MAX_DIMENSION,
+            },
+            &AtomicBool::new(false),
+            |_, _| {},
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("refusing to clean"), "{err}");
+        assert!(existing.join("notes.txt").exists());
+
+        extract_xcursor_theme(
+            &theme,
+            Some(&out),
+            &ThemeExtractOptions {
+                hyprcursor: &HyprcursorOptions::default(),
+                description: None,
+                exact_output: false,
+                allow_clean: true,
+                max_dimension: DEFAULT_MAX_DIMENSION,
+            },
+            &AtomicBool::new(false),
+            |_, _| {},
+        )
+        .unwrap();
+        assert!(!existing.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn test_exact_output_is_only_emptied_with_allow_clean() {
+        let dir = tempdir().unwrap();
+        let theme = dir.path().join("Bibata");
+        fs::create_dir_all(theme.join("cursors")).unwrap();
+        let out = dir.path().join("out");
+        fs::create_dir_all(&out).unwrap();
+        fs::write(out.join("notes.txt"), "keep me").unwrap();
+
+        let extract = |allow_clean| {
+            extract_xcursor_theme(
+                &theme,
+                Some(&out),
+                &ThemeExtractOptions {
+                    exact_output: true,
+                    allow_clean,
+                    ..ThemeExtractOptions::new(&HyprcursorOptions::default())
+                },
+                &AtomicBool::new(false),
+                |_, _| {},
+            )
+        };
+
+        extract(false).unwrap();
+        assert!(out.join("notes.txt").exists());
+        assert!(out.join("manifest.hl").exists());
+
+        extract(true).unwrap();
+        assert!(!out.join("notes.txt").exists());
+        assert!(out.join("manifest.hl").exists());
+    }
+
+    #[test]
+    fn test_available_compression_matches_zip_features() {
+        let available = HlcCompression::available();
+        assert!(available.contains(&HlcCompression::Stored));
+        assert_eq!(
+            available.contains(&HlcCompression::Zstd),
+            SUPPORTED_COMPRESSION_METHODS.contains(&CompressionMethod::ZSTD)
+        );
+        let order: Vec<_> = HlcCompression::all()
+            .into_iter()
+            .filter(|c| available.contains(c))
+            .collect();
+        assert_eq!(available, order);
+    }
+
+    #[test]
+    fn test_process_shape_round_trips_every_compression() {
+        let dir = tempdir().unwrap();
+        let shape_dir = dir.path().join("left_ptr");
+        fs::create_dir_all(&shape_dir).unwrap();
+        let meta = "resize_algorithm = none\n\
+                    hotspot_x = 0.25\n\
+                    hotspot_y = 0.12\n\
+                    define_size = 32, left_ptr_000.png, 50\n";
+        let image: Vec<u8> = (0..4096).map(|i| (i % 251) as u8).collect();
+        fs::write(shape_dir.join("meta.hl"), meta).unwrap();
+        fs::write(shape_dir.join("left_ptr_000.png"), &image).unwrap();
+
+        for compression in HlcCompression::available() {
+            let out_dir = dir.path().join(compression.name());
+            fs::create_dir_all(&out_dir).unwrap();
+            process_shape(&shape_dir, &out_dir, "left_ptr", compression, |_, _| {}).unwrap();
+
+            let file = File::open(out_dir.join("left_ptr.hlc")).unwrap();
+            let mut archive = ZipArchive::new(file).unwrap();
+
+            let mut entry = archive.by_name("left_ptr_000.png").unwrap();
+            assert_eq!(entry.compression(), compression.method());
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data).unwrap();
+            assert_eq!(data, image);
+            drop(entry);
+
+            let mut entry = archive.by_name("meta.hl").unwrap();
+            let mut text = String::new();
+            entry.read_to_string(&mut text).unwrap();
+            assert_eq!(text, meta);
+        }
+    }
+
+    #[test]
+    fn test_meta_hl_keeps_comments_and_unknown_keys() {
+        use crate::pipeline::win2xcur::cur::{CursorFrame, CursorImage};
+        use crate::pipeline::win2xcur::xcursor_writer::to_x11;
+        use image::RgbaImage;
+
+        let dir = tempdir().unwrap();
+        let xcursor = dir.path().join("wait");
+        let frame = CursorFrame {
+            images: vec![CursorImage {
+                image: RgbaImage::new(32, 32),
+                hotspot: (16, 16),
+                nominal_size: 32,
+            }],
+            delay: 40,
+        };
+        fs::write(&xcursor, to_x11(&[frame]).unwrap()).unwrap();
+
+        let source = dir.path().join("hyprcursors");
+        let shape_dir = source.join("wait");
+        fs::create_dir_all(&shape_dir).unwrap();
+        fs::write(
+            shape_dir.join("meta.hl"),
+            "# bilinear looks better for this one\n\
+             resize_algorithm = bilinear\n\
+             hotspot_x = 0.5\n\
+             hotspot_y = 0.5\n\
+             my_note = keep me\n\
+             define_size = 32, old_000.png, 40\n",
+        )
+        .unwrap();
+
+        // The incremental update regenerates the meta with the configured algorithm
+        let hyprcursor = HyprcursorOptions {
+            resize_algorithm: ResizeAlgorithm::Nearest,
+            ..HyprcursorOptions::default()
+        };
+        extract_xcursor_to_hypr_source(
+            &xcursor,
+            &source,
+            &hyprcursor,
+            Vec::new(),
+            None,
+            DEFAULT_MAX_DIMENSION,
+        )
+        .unwrap();
+
+        let meta = fs::read_to_string(shape_dir.join("meta.hl")).unwrap();
+        assert!(meta.starts_with("# bilinear looks better for this one\nmy_note = keep me\n\n"));
+        assert!(meta.contains("resize_algorithm = nearest\n"));
+        assert!(meta.contains("define_size = 32, wait_000.png, 40\n"));
+        assert!(!meta.contains("old_000.png"));
+
+        let reparsed = parse_meta_hl_str(&meta, "wait");
+        assert_eq!(
+            reparsed.extra_lines,
+            vec!["# bilinear looks better for this one", "my_note = keep me"]
+        );
+    }
+
+    #[test]
+    fn test_manifest_hl_escapes_comment_marks() {
+        let dir = tempdir().unwrap();
+        let manifest = format_manifest_hl("Theme", "By me # v2 ## draft");
+        assert!(manifest.contains("description = By me ## v2 #### draft\n"));
+
+        let path = dir.path().join("manifest.hl");
+        fs::write(&path, manifest).unwrap();
+        let parsed = parse_manifest_hl(&path).unwrap();
+        assert_eq!(parsed.description, "By me # v2 ## draft");
+    }
+
+    #[test]
+    fn test_parse_meta_toml_schemas() {
+        // As hyprcursor documents it
+        let shape = parse_meta_toml_str(
+            "[General]\n\
+             hotspot_x = 0.5\n\
+             hotspot_y = 0\n\
+             resize_algorithm = \"bilinear\"\n\
+             define_override = \"default;arrow\"\n\
+             define_size = \"64,left_ptr_64.png;32, left_ptr_32.png, 50\"\n",
+            "left_ptr",
+        )
+        .unwrap();
+        assert_eq!((shape.hotspot_x, shape.hotspot_y), (0.5, 0.0));
+        assert_eq!(shape.resize_algorithm, "bilinear");
+        assert_eq!(shape.overrides, vec!["default", "arrow"]);
+        let images: Vec<(u32, &str, u32)> = shape
+            .images
+            .iter()
+            .map(|i| (i.size, i.file.as_str(), i.delay))
+            .collect();
+        assert_eq!(
+            images,
+            vec![(64, "left_ptr_64.png", 0), (32, "left_ptr_32.png", 50)]
+        );
+
+        // Keys outside `[General]` are not hyprcursor's schema
+        let err = parse_meta_toml_str("hotspot_x = 0.25\n", "left_ptr").unwrap_err();
+        assert!(err.to_string().contains("[General]"), "{err}");
+    }
+
+    #[test]
+    fn test_extract_toml_round_trips() {
+        use crate::pipeline::win2xcur::cur::{CursorFrame, CursorImage};
+        use crate::pipeline::win2xcur::xcursor_writer::to_x11;
+        use image::RgbaImage;
+
+        let dir = tempdir().unwrap();
+        let theme = dir.path().join("My \"Theme\"");
+        let cursors = theme.join("cursors");
+        fs::create_dir_all(&cursors).unwrap();
+        let frame = CursorFrame {
+            images: vec![CursorImage {
+                image: RgbaImage::new(32, 32),
+                hotspot: (8, 16),
+                nominal_size: 32,
+            }],
+            delay: 40,
+        };
+        fs::write(cursors.join("left_ptr"), to_x11(&[frame]).unwrap()).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("left_ptr", cursors.join("default")).unwrap();
+
+        let out = dir.path().join("out");
+        extract_xcursor_theme(
+            &theme,
+            Some(&out),
+            &ThemeExtractOptions {
+                hyprcursor: &HyprcursorOptions {
+                    resize_algorithm: ResizeAlgorithm::Nearest,
+                    manifest_format: ManifestFormat::Toml,
+                    ..HyprcursorOptions::default()
+                },
+                description: Some("By \"me\" # v2"),
+                exact_output: true,
+                allow_clean: false,
+                max_dimension: DEFAULT_MAX_DIMENSION,
+            },
+            &AtomicBool::new(false),
+            |_, _| {},
+        )
+        .unwrap();
+
+        assert!(!out.join("manifest.hl").exists());
+        let manifest = parse_manifest_toml(&out.join("manifest.toml")).unwrap();
+        assert_eq!(manifest.name, "My \"Theme\"");
+        assert_eq!(manifest.description, "By \"me\" # v2");
+        assert_eq!(manifest.version, "0.1");
+        assert_eq!(manifest.cursors_directory, "hyprcursors");
+
+        let shape_dir = out.join("hyprcursors").join("left_ptr");
+        assert!(!shape_dir.join("meta.hl").exists());
+        let shape = parse_meta_toml(&shape_dir.join("meta.toml"), "left_ptr").unwrap();
+        assert_eq!(shape.resize_algorithm, "nearest");
+        assert_eq!((shape.hotspot_x, shape.hotspot_y), (0.25, 0.5));
+        assert_eq!(shape.images.len(), 1);
+        assert_eq!(shape.images[0].size, 32);
+        assert_eq!(shape.images[0].delay, 40);
+        assert!(shape_dir.join(&shape.images[0].file).exists());
+        #[cfg(unix)]
+        assert_eq!(shape.overrides, vec!["default"]);
+
+        // Laid out the way hyprcursor reads TOML themes
+        let manifest_raw = fs::read_to_string(out.join("manifest.toml")).unwrap();
+        let manifest_raw = manifest_raw.parse::<toml::Table>().unwrap();
+        assert_eq!(
+            manifest_raw["General"]["cursors_directory"].as_str(),
+            Some("hyprcursors")
+        );
+        let meta_raw = fs::read_to_string(shape_dir.join("meta.toml")).unwrap();
+        let meta_raw = meta_raw.parse::<toml::Table>().unwrap();
+        let general = meta_raw["General"].as_table().unwrap();
+        assert_eq!(
+            general["define_size"].as_str(),
+            Some(format!("32, {}, 40", shape.images[0].file).as_str())
+        );
+        #[cfg(unix)]
+        assert_eq!(general["define_override"].as_str(), Some("default"));
+
+        // The written sources compile like hyprlang ones
+        let compiled = dir.path().join("compiled");
+        create_cursor_theme(
+            &out,
+            Some(&compiled),
+            true,
+            false,
+            HlcCompression::default(),
+            &AtomicBool::new(false),
+            |_, _| {},
+        )
+        .unwrap();
+        assert!(compiled.join("manifest.toml").exists());
+        let hlc = read_hlc(&compiled.join("hyprcursors").join("left_ptr.hlc")).unwrap();
+        assert_eq!(hlc.images.len(), 1);
+    }
+
+    #[test]
+    fn test_resize_override_applies_to_its_shape_only() {
+        use crate::pipeline::win2xcur::cur::{CursorFrame, CursorImage};
+        use crate::pipeline::win2xcur::xcursor_writer::to_x11;
+        use image::RgbaImage;
+
+        let dir = tempdir().unwrap();
+        let theme = dir.path().join("Theme");
+        let cursors = theme.join("cursors");
+        fs::create_dir_all(&cursors).unwrap();
+        let frame = CursorFrame {
+            images: vec![CursorImage {
+                image: RgbaImage::new(32, 32),
+                hotspot: (0, 0),
+                nominal_size: 32,
+            }],
+            delay: 0,
+        };
+        let data = to_x11(&[frame]).unwrap();
+        fs::write(cursors.join("left_ptr"), &data).unwrap();
+        fs::write(cursors.join("hand2"), &data).unwrap();
+
+        let out = dir.path().join("out");
+        let overrides = BTreeMap::from([("left_ptr".to_string(), ResizeAlgorithm::Nearest)]);
+        extract_xcursor_theme(
+            &theme,
+            Some(&out),
+            &ThemeExtractOptions {
+                hyprcursor: &HyprcursorOptions {
+                    resize_algorithm: ResizeAlgorithm::Bilinear,
+                    resize_overrides: overrides.clone(),
+                    ..HyprcursorOptions::default()
+                },
+                description: None,
+                exact_output: true,
+                allow_clean: false,
+                max_dimension: DEFAULT_MAX_DIMENSION,
+            },
+            &AtomicBool::new(false),
+            |_, _| {},
+        )
+        .unwrap();
+
+        let shapes = out.join("hyprcursors");
+        let left_ptr = parse_meta_hl(&shapes.join("left_ptr").join("meta.hl"), "left_ptr");
+        let hand2 = parse_meta_hl(&shapes.join("hand2").join("meta.hl"), "hand2");
+        assert_eq!(left_ptr.unwrap().resize_algorithm, "nearest");
+        assert_eq!(hand2.unwrap().resize_algorithm, "bilinear");
+
+        // `none` is written like any other choice, replacing the earlier bilinear
+        extract_xcursor_theme(
+            &theme,
+            Some(&out),
+            &ThemeExtractOptions {
+                hyprcursor: &HyprcursorOptions {
+                    resize_overrides: overrides,
+                    ..HyprcursorOptions::default()
+                },
+                exact_output: true,
+                ..ThemeExtractOptions::new(&HyprcursorOptions::default())
+            },
+            &AtomicBool::new(false),
+            |_, _| {},
+        )
+        .unwrap();
+        let left_ptr = parse_meta_hl(&shapes.join("left_ptr").join("meta.hl"), "left_ptr");
+        let hand2 = parse_meta_hl(&shapes.join("hand2").join("meta.hl"), "hand2");
+        assert_eq!(left_ptr.unwrap().resize_algorithm, "nearest");
+        assert_eq!(hand2.unwrap().resize_algorithm, "none");
+    }
+}
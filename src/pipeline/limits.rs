@@ -0,0 +1,24 @@
+// Upper bound on image dimensions read from cursor files, checked before any pixel buffer
+// is allocated so a corrupt or hostile header can't exhaust memory
+
+use anyhow::{Result, bail};
+
+pub const DEFAULT_MAX_DIMENSION: u32 = 1024;
+
+/// Resolves a configured cap, where 0 means the default
+pub fn effective_max_dimension(max: u32) -> u32 {
+    if max == 0 { DEFAULT_MAX_DIMENSION } else { max }
+}
+
+pub fn check_dimensions(width: u32, height: u32, max: u32, what: &str) -> Result<()> {
+    if width > max || height > max {
+        bail!(
+            "{} declares {}x{} pixels, above the {}px limit",
+            what,
+            width,
+            height,
+            max
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+/// Level attached to pipeline messages, ordered from least to most important.
+/// The quietest three also serve as the verbosity setting: a message is shown
+/// when its level is at or above the configured one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LogLevel {
+    // Every message from the converters and the Hyprcursor compiler
+    Detail,
+    // One line per processed file
+    Progress,
+    // Stage transitions
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    /// Levels offered as the verbosity setting, quietest first
+    pub fn verbosities() -> [LogLevel; 3] {
+        [LogLevel::Info, LogLevel::Progress, LogLevel::Detail]
+    }
+
+    pub fn default_verbosity() -> LogLevel {
+        LogLevel::Progress
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogLevel::Detail => "Detail",
+            LogLevel::Progress => "Progress",
+            LogLevel::Info => "Info",
+            LogLevel::Warn => "Warn",
+            LogLevel::Error => "Error",
+        }
+    }
+}
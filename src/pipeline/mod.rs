@@ -1,10 +1,20 @@
 pub mod cursor_io;
 pub mod cursor_types;
 pub mod fs_ops;
+pub mod gallery;
+pub mod gif_export;
 pub mod hyprcursor;
+pub mod limits;
+pub mod log;
+pub mod theme_actions;
+pub mod theme_build;
+pub mod tip;
 pub mod win2xcur;
 pub mod xcur2png;
 pub mod xcursor_gen;
 
+// Synchronous entry point for using the conversion pipeline as a library
+pub use theme_build::build_theme;
+
 #[cfg(test)]
 mod pipeline_test;
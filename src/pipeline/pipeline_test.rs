@@ -2,6 +2,7 @@
 
 #[cfg(test)]
 mod tests {
+    use crate::pipeline::limits::DEFAULT_MAX_DIMENSION;
     use crate::pipeline::{win2xcur, xcur2png};
     use std::path::Path;
     use tempfile::tempdir;
@@ -26,7 +27,7 @@ mod tests {
                 win_cursor_path,
                 &xcur_output,
                 &options,
-                |msg| {
+                |_, msg| {
                     eprintln!("{}", msg);
                 },
             );
@@ -121,7 +122,9 @@ mod tests {
         std::fs::write(&xcur_path, &x11_data).unwrap();
 
         // Read back and extract
-        let xcursor = xcur2png::xcursor_reader::XcursorFile::from_file(&xcur_path).unwrap();
+        let xcursor =
+            xcur2png::xcursor_reader::XcursorFile::from_file(&xcur_path, DEFAULT_MAX_DIMENSION)
+                .unwrap();
         assert_eq!(xcursor.images.len(), 1);
         assert_eq!(xcursor.images[0].width, 32);
         assert_eq!(xcursor.images[0].height, 32);
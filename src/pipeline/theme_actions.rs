@@ -0,0 +1,355 @@
+// Follow-up actions offered on the results screen once a theme has been built
+
+use anyhow::{Context, Result, anyhow, bail};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use walkdir::WalkDir;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+use super::fs_ops::copy_dir_except;
+use super::theme_build::INTERMEDIATE_DIR_NAMES;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeAction {
+    Install,
+    Test,
+    OpenFolder,
+    Archive,
+}
+
+impl ThemeAction {
+    pub fn all() -> [ThemeAction; 4] {
+        [
+            ThemeAction::Install,
+            ThemeAction::Test,
+            ThemeAction::OpenFolder,
+            ThemeAction::Archive,
+        ]
+    }
+
+    pub fn key(&self) -> char {
+        match self {
+            ThemeAction::Install => 'i',
+            ThemeAction::Test => 't',
+            ThemeAction::OpenFolder => 'o',
+            ThemeAction::Archive => 'a',
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ThemeAction::Install => "Install to ~/.icons",
+            ThemeAction::Test => "Try with hyprctl",
+            ThemeAction::OpenFolder => "Open folder",
+            ThemeAction::Archive => "Archive as .zip",
+        }
+    }
+
+    pub fn from_key(key: char) -> Option<Self> {
+        Self::all().into_iter().find(|action| action.key() == key)
+    }
+
+    /// Runs the action against a built theme directory and describes the outcome
+    pub fn run(&self, theme_dir: &Path, cursor_size: u32) -> Result<String> {
+        match self {
+            ThemeAction::Install => install_theme(theme_dir)
+                .map(|dest| format!("Installed theme to {}", dest.display())),
+            ThemeAction::Test => {
+                let name = theme_name(theme_dir)?;
+                test_with_hyprctl(&name, cursor_size)
+                    .map(|_| format!("Applied {} at size {} via hyprctl", name, cursor_size))
+            }
+            ThemeAction::OpenFolder => {
+                open_folder(theme_dir).map(|_| format!("Opened {}", theme_dir.display()))
+            }
+            ThemeAction::Archive => archive_theme(theme_dir)
+                .map(|archive| format!("Archived theme to {}", archive.display())),
+        }
+    }
+}
+
+fn theme_name(theme_dir: &Path) -> Result<String> {
+    theme_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| anyhow!("Invalid theme path: {}", theme_dir.display()))
+}
+
+/// Copies the complete theme, Hyprcursor files included, to `~/.icons/<theme>`
+pub fn install_theme(theme_dir: &Path) -> Result<PathBuf> {
+    let home_dir = dirs::home_dir().ok_or_else(|| anyhow!("Could not determine home directory"))?;
+    install_theme_into(theme_dir, &home_dir.join(".icons"))
+}
+
+// Intermediates a `Nested` build left inside the theme are not copied
+fn install_theme_into(theme_dir: &Path, icons_dir: &Path) -> Result<PathBuf> {
+    let dest = icons_dir.join(theme_name(theme_dir)?);
+
+    if dest == theme_dir {
+        return Ok(dest);
+    }
+
+    if dest.exists() {
+        fs::remove_dir_all(&dest)?;
+    }
+    copy_dir_except(theme_dir, &dest, &INTERMEDIATE_DIR_NAMES)?;
+
+    Ok(dest)
+}
+
+/// Switches the running Hyprland session to the theme
+pub fn test_with_hyprctl(theme_name: &str, cursor_size: u32) -> Result<()> {
+    let output = Command::new("hyprctl")
+        .arg("setcursor")
+        .arg(theme_name)
+        .arg(cursor_size.to_string())
+        .output()
+        .context("Failed to run hyprctl")?;
+
+    if !output.status.success() {
+        bail!(
+            "hyprctl setcursor failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+pub fn open_folder(path: &Path) -> Result<()> {
+    Command::new("xdg-open")
+        .arg(path)
+        .spawn()
+        .context("Failed to run xdg-open")?;
+    Ok(())
+}
+
+/// Packs the theme into `<theme>.zip` next to it, keeping cursor symlinks as symlinks
+pub fn archive_theme(theme_dir: &Path) -> Result<PathBuf> {
+    let name = theme_name(theme_dir)?;
+    let archive_path = theme_dir.with_file_name(format!("{}.zip", name));
+    let base = theme_dir.parent().unwrap_or(theme_dir);
+
+    let mut zip = ZipWriter::new(File::create(&archive_path)?);
+    let options = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .unix_permissions(0o644);
+
+    let entries = WalkDir::new(theme_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| entry.depth() != 1 || !is_intermediate(entry.file_name()));
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        let relative = path
+            .strip_prefix(base)?
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        if entry.path_is_symlink() {
+            let target = fs::read_link(path)?;
+            zip.add_symlink(relative, target.to_string_lossy(), options)?;
+        } else if entry.file_type().is_dir() {
+            zip.add_directory(relative, options.unix_permissions(0o755))?;
+        } else {
+            zip.start_file(relative, options)?;
+            zip.write_all(&fs::read(path)?)?;
+        }
+    }
+
+    zip.finish()?;
+    Ok(archive_path)
+}
+
+/// Packs the theme into `<theme>.tar.gz` next to it. Symlinks are stored as symlinks and
+/// every other file, `.hlc` archives included, is stored byte for byte.
+pub fn archive_theme_tar_gz(theme_dir: &Path) -> Result<PathBuf> {
+    let name = theme_name(theme_dir)?;
+    if !theme_dir.is_dir() {
+        bail!("Theme not built yet: {}", theme_dir.display());
+    }
+    let archive_path = theme_dir.with_file_name(format!("{}.tar.gz", name));
+
+    let encoder = GzEncoder::new(File::create(&archive_path)?, Compression::default());
+    let mut tar = tar::Builder::new(encoder);
+    tar.follow_symlinks(false);
+    tar.append_dir(&name, theme_dir)?;
+    let mut entries = fs::read_dir(theme_dir)?.collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        if is_intermediate(&entry.file_name()) {
+            continue;
+        }
+        let archived = Path::new(&name).join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            tar.append_dir_all(&archived, entry.path())?;
+        } else {
+            tar.append_path_with_name(entry.path(), &archived)?;
+        }
+    }
+    tar.into_inner()?.finish()?;
+
+    Ok(archive_path)
+}
+
+fn is_intermediate(name: &std::ffi::OsStr) -> bool {
+    INTERMEDIATE_DIR_NAMES
+        .iter()
+        .any(|intermediate| name == *intermediate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    use zip::ZipArchive;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_archive_theme_keeps_symlinks() {
+        use std::os::unix::fs as unix_fs;
+
+        let temp = tempdir().unwrap();
+        let theme_dir = temp.path().join("Test");
+        let cursors = theme_dir.join("cursors");
+        fs::create_dir_all(&cursors).unwrap();
+        fs::write(cursors.join("left_ptr"), b"xcur").unwrap();
+        unix_fs::symlink("left_ptr", cursors.join("default")).unwrap();
+        fs::write(theme_dir.join("index.theme"), b"[Icon Theme]").unwrap();
+
+        let archive_path = archive_theme(&theme_dir).unwrap();
+        assert_eq!(archive_path, temp.path().join("Test.zip"));
+
+        let mut archive = ZipArchive::new(File::open(&archive_path).unwrap()).unwrap();
+        assert!(archive.by_name("Test/index.theme").is_ok());
+        assert!(archive.by_name("Test/cursors/left_ptr").is_ok());
+        assert!(
+            archive
+                .by_name("Test/cursors/default")
+                .unwrap()
+                .is_symlink()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_tar_gz_keeps_symlinks_and_hlc_bytes() {
+        use flate2::read::GzDecoder;
+        use std::io::Read;
+        use std::os::unix::fs as unix_fs;
+
+        let temp = tempdir().unwrap();
+        let theme_dir = temp.path().join("Test");
+        let cursors = theme_dir.join("cursors");
+        let hypr = theme_dir.join("hyprcursors");
+        fs::create_dir_all(&cursors).unwrap();
+        fs::create_dir_all(&hypr).unwrap();
+        fs::write(cursors.join("left_ptr"), b"xcur").unwrap();
+        unix_fs::symlink("left_ptr", cursors.join("default")).unwrap();
+        let hlc = [b'P', b'K', 3, 4, 0, 0xff, 0x80];
+        fs::write(hypr.join("left_ptr.hlc"), hlc).unwrap();
+
+        let archive_path = archive_theme_tar_gz(&theme_dir).unwrap();
+        assert_eq!(archive_path, temp.path().join("Test.tar.gz"));
+
+        let mut archive = tar::Archive::new(GzDecoder::new(File::open(&archive_path).unwrap()));
+        let mut saw_link = false;
+        let mut saw_hlc = false;
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let path = entry.path().unwrap().into_owned();
+            if path == Path::new("Test/cursors/default") {
+                assert!(entry.header().entry_type().is_symlink());
+                assert_eq!(entry.link_name().unwrap().unwrap(), Path::new("left_ptr"));
+                saw_link = true;
+            } else if path == Path::new("Test/hyprcursors/left_ptr.hlc") {
+                let mut bytes = Vec::new();
+                entry.read_to_end(&mut bytes).unwrap();
+                assert_eq!(bytes, hlc);
+                saw_hlc = true;
+            }
+        }
+        assert!(saw_link && saw_hlc);
+    }
+
+    #[test]
+    fn test_nested_intermediates_are_not_installed_or_archived() {
+        use crate::model::mapping::CursorMapping;
+        use crate::pipeline::log::LogLevel;
+        use crate::pipeline::theme_build::tests::png_cur_bytes;
+        use crate::pipeline::theme_build::{
+            BuildReporter, OutputLayout, ThemeBuildOptions, run_full_theme_pipeline,
+        };
+        use flate2::read::GzDecoder;
+        use std::sync::atomic::AtomicBool;
+
+        struct Quiet;
+        impl BuildReporter for Quiet {
+            fn log(&self, _level: LogLevel, _msg: String) {}
+        }
+
+        let temp = tempdir().unwrap();
+        let input = temp.path().join("src");
+        fs::create_dir_all(&input).unwrap();
+        fs::write(input.join("Normal.cur"), png_cur_bytes(32, (0, 0))).unwrap();
+        let output = temp.path().join("out");
+        let options = ThemeBuildOptions {
+            keep_intermediate: true,
+            layout: OutputLayout::Nested,
+            thread_count: 1,
+            ..ThemeBuildOptions::default()
+        };
+        run_full_theme_pipeline(
+            &input,
+            &output,
+            "Test",
+            &CursorMapping::default(),
+            &options,
+            &Quiet,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+        let theme_dir = output.join("Test");
+        assert!(theme_dir.join("png_intermediate").is_dir());
+        assert!(theme_dir.join("xcur_intermediate").is_dir());
+
+        let installed = install_theme_into(&theme_dir, &temp.path().join("icons")).unwrap();
+        assert!(installed.join("cursors").join("left_ptr").exists());
+        for name in INTERMEDIATE_DIR_NAMES {
+            assert!(!installed.join(name).exists(), "{name}");
+        }
+
+        let mut zip =
+            ZipArchive::new(File::open(archive_theme(&theme_dir).unwrap()).unwrap()).unwrap();
+        assert!(zip.by_name("Test/cursors/left_ptr").is_ok());
+        assert!(zip.file_names().all(|name| !name.contains("_intermediate")));
+
+        let archive_path = archive_theme_tar_gz(&theme_dir).unwrap();
+        let mut tar = tar::Archive::new(GzDecoder::new(File::open(&archive_path).unwrap()));
+        let paths: Vec<PathBuf> = tar
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().into_owned())
+            .collect();
+        assert!(paths.contains(&PathBuf::from("Test/cursors/left_ptr")));
+        assert!(
+            paths
+                .iter()
+                .all(|path| !path.to_string_lossy().contains("_intermediate"))
+        );
+    }
+
+    #[test]
+    fn test_theme_action_keys() {
+        for action in ThemeAction::all() {
+            assert_eq!(ThemeAction::from_key(action.key()), Some(action));
+        }
+        assert_eq!(ThemeAction::from_key('z'), None);
+    }
+}
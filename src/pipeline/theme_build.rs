@@ -0,0 +1,940 @@
+// Full theme builds: Windows cursors to a named XCursor theme plus its Hyprcursor files
+
+use anyhow::{Result, bail};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use walkdir::WalkDir;
+
+use crate::model::mapping::CursorMapping;
+use crate::pipeline::hyprcursor::{self, HyprcursorOptions, ThemeExtractOptions};
+use crate::pipeline::log::LogLevel;
+use crate::pipeline::win2xcur::converter::{ConversionOptions, convert_windows_cursor};
+use crate::pipeline::win2xcur::xcursor_writer::AlphaMode;
+use crate::pipeline::xcur2png::{ExtractOptions, extract_to_pngs};
+use crate::pipeline::xcursor_gen::{DEFAULT_INHERITS, XCursorThemeBuilder};
+
+/// A themed output produced from the same source set in a single build
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeVariant {
+    Base,
+    Dark,
+    // Xcursor files with straight alpha, for testing which encoding a desktop expects
+    StraightAlpha,
+}
+
+impl ThemeVariant {
+    pub fn theme_name(&self, base_name: &str) -> String {
+        match self {
+            ThemeVariant::Base => base_name.to_string(),
+            ThemeVariant::Dark => format!("{}-Dark", base_name),
+            ThemeVariant::StraightAlpha => format!("{}-Straight", base_name),
+        }
+    }
+
+    fn apply(&self, options: ConversionOptions) -> ConversionOptions {
+        match self {
+            ThemeVariant::Base => options,
+            ThemeVariant::Dark => options.with_inverted_colors(true),
+            ThemeVariant::StraightAlpha => options.with_alpha_mode(AlphaMode::Straight),
+        }
+    }
+
+    // Hyprcursor extraction assumes premultiplied Xcursor input, so the straight-alpha
+    // test variant only ships X11 cursors
+    fn builds_hyprcursor(&self) -> bool {
+        *self != ThemeVariant::StraightAlpha
+    }
+
+    // Each variant converts into its own directory so kept intermediates don't mix
+    fn intermediate_dir_name(&self) -> &'static str {
+        match self {
+            ThemeVariant::Base => "xcur_intermediate",
+            ThemeVariant::Dark => "xcur_intermediate_dark",
+            ThemeVariant::StraightAlpha => "xcur_intermediate_straight",
+        }
+    }
+}
+
+/// What a finished theme build produced, for the results screen
+#[derive(Debug, Clone)]
+pub struct BuildSummary {
+    pub theme_name: String,
+    pub theme_dir: PathBuf,
+    pub cursor_count: usize,
+    pub symlink_count: usize,
+    pub warnings: Vec<String>,
+}
+
+/// Names of the intermediate directories a build can create. `Nested` puts them inside the
+/// theme, so installing and archiving leave them out.
+pub const INTERMEDIATE_DIR_NAMES: [&str; 4] = [
+    "png_intermediate",
+    "xcur_intermediate",
+    "xcur_intermediate_dark",
+    "xcur_intermediate_straight",
+];
+
+/// Where full builds put their intermediate directories. For theme `T` in `out/`:
+///
+/// * `Flat`: `out/T/` holds the theme, with `out/png_intermediate/` and a kept
+///   `out/xcur_intermediate/` beside it
+/// * `Nested`: both intermediates live inside the theme, as `out/T/png_intermediate/` and
+///   `out/T/xcur_intermediate/`, so `out/` only ever gets the theme directory
+/// * `ThemeOnly`: like `Flat`, but no `png_intermediate` is written, which leaves the editor
+///   nothing to preview after a build
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutputLayout {
+    #[default]
+    Flat,
+    Nested,
+    ThemeOnly,
+}
+
+impl OutputLayout {
+    pub fn all() -> [OutputLayout; 3] {
+        [
+            OutputLayout::Flat,
+            OutputLayout::Nested,
+            OutputLayout::ThemeOnly,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutputLayout::Flat => "Flat",
+            OutputLayout::Nested => "Nested",
+            OutputLayout::ThemeOnly => "Theme only",
+        }
+    }
+
+    /// Directory the intermediates of `theme_name` are created in
+    pub fn intermediate_root(&self, output_dir: &Path, theme_name: &str) -> PathBuf {
+        match self {
+            OutputLayout::Nested => output_dir.join(theme_name),
+            OutputLayout::Flat | OutputLayout::ThemeOnly => output_dir.to_path_buf(),
+        }
+    }
+
+    /// Frame PNGs the editor previews, for the base theme `theme_name`
+    pub fn png_dir(&self, output_dir: &Path, theme_name: &str) -> PathBuf {
+        self.intermediate_root(output_dir, theme_name)
+            .join("png_intermediate")
+    }
+
+    pub fn keeps_png(&self) -> bool {
+        *self != OutputLayout::ThemeOnly
+    }
+}
+
+/// Receives what a build reports while it runs. Called from the conversion threads,
+/// so it has to be `Sync`.
+pub trait BuildReporter: Sync {
+    fn log(&self, level: LogLevel, msg: String);
+
+    /// `done` of `total` source files have been converted
+    fn progress(&self, _done: usize, _total: usize) {}
+}
+
+/// Settings of a full theme build
+#[derive(Debug, Clone)]
+pub struct ThemeBuildOptions {
+    pub conversion: ConversionOptions,
+    pub variants: Vec<ThemeVariant>,
+    // X11 cursors to warn about when missing or falling back to Normal
+    pub essential_cursors: Vec<String>,
+    pub hyprcursor: HyprcursorOptions,
+    // Also replace ~/.icons/<theme>
+    pub install: bool,
+    pub inherits: String,
+    pub description: String,
+    pub keep_intermediate: bool,
+    pub layout: OutputLayout,
+    pub link_duplicates: bool,
+    // 0 lets rayon pick
+    pub thread_count: usize,
+}
+
+impl Default for ThemeBuildOptions {
+    fn default() -> Self {
+        Self {
+            conversion: ConversionOptions::new(),
+            variants: vec![ThemeVariant::Base],
+            essential_cursors: Vec::new(),
+            hyprcursor: HyprcursorOptions::default(),
+            install: false,
+            inherits: DEFAULT_INHERITS.to_string(),
+            description: String::new(),
+            keep_intermediate: false,
+            layout: OutputLayout::default(),
+            link_duplicates: false,
+            thread_count: 0,
+        }
+    }
+}
+
+/// Result of a full theme build
+#[derive(Debug, Clone)]
+pub struct ThemeBuild {
+    // Source files the base variant converted
+    pub converted: usize,
+    pub summaries: Vec<BuildSummary>,
+}
+
+pub fn check_cancelled(cancel: &AtomicBool) -> Result<()> {
+    if cancel.load(Ordering::Relaxed) {
+        bail!("Cancelled");
+    }
+    Ok(())
+}
+
+pub fn log_target_sizes(reporter: &dyn BuildReporter, options: &ConversionOptions) {
+    let mut sizes = options.target_sizes.clone();
+    sizes.sort_unstable();
+    reporter.log(
+        LogLevel::Info,
+        if sizes.is_empty() {
+            "Generating the sizes found in the source files".to_string()
+        } else {
+            let sizes: Vec<String> = sizes.iter().map(|s| s.to_string()).collect();
+            format!("Generating sizes: {}", sizes.join(", "))
+        },
+    );
+}
+
+/// Every `.ani` and `.cur` file below `input_dir`
+pub fn find_cursor_files(input_dir: &Path) -> Vec<PathBuf> {
+    WalkDir::new(input_dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path().to_path_buf())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .map(|s| {
+                    let s = s.to_lowercase();
+                    s == "ani" || s == "cur"
+                })
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+/// Converts `cursor_files` into `xcur_dir` in parallel, and into frame PNGs under `png_dir`
+/// if given. Returns (processed, failed).
+pub fn convert_batch(
+    cursor_files: &[PathBuf],
+    xcur_dir: &Path,
+    png_dir: Option<&Path>,
+    conversion_options: &ConversionOptions,
+    reporter: &dyn BuildReporter,
+    thread_count: usize,
+    cancel: &AtomicBool,
+) -> Result<(usize, usize)> {
+    let total_files = cursor_files.len();
+
+    let processed = AtomicUsize::new(0);
+    let failed = AtomicUsize::new(0);
+    // Held while reporting so progress messages leave in increasing order
+    let finished = Mutex::new(0usize);
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(thread_count)
+        .build()?;
+
+    pool.install(|| {
+        cursor_files
+            .par_iter()
+            .enumerate()
+            .for_each(|(idx, cursor_file)| {
+                if cancel.load(Ordering::Relaxed) {
+                    return;
+                }
+
+                let file_name = cursor_file
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("cursor");
+
+                reporter.log(
+                    LogLevel::Progress,
+                    format!("Processing {}/{}: {}", idx + 1, total_files, file_name),
+                );
+
+                let xcur_output = xcur_dir.join(file_name);
+                let options = conversion_options.clone().with_comment(file_name);
+                match convert_windows_cursor(cursor_file, &xcur_output, &options, |level, msg| {
+                    reporter.log(level, msg)
+                }) {
+                    Ok(_) => {
+                        if let Some(png_out) = png_dir {
+                            let png_output_dir = png_out.join(file_name);
+                            if let Err(e) = fs::create_dir_all(&png_output_dir) {
+                                reporter
+                                    .log(LogLevel::Error, format!("Failed to create dir: {}", e));
+                                failed.fetch_add(1, Ordering::Relaxed);
+                                return;
+                            }
+
+                            let extract_options = ExtractOptions::new()
+                                .with_prefix(file_name)
+                                .with_config(true)
+                                .with_max_dimension(conversion_options.max_dimension);
+
+                            match extract_to_pngs(&xcur_output, &png_output_dir, &extract_options) {
+                                Ok(_) => {
+                                    processed.fetch_add(1, Ordering::Relaxed);
+                                }
+                                Err(e) => {
+                                    reporter.log(
+                                        LogLevel::Error,
+                                        format!("Failed to extract PNGs: {}", e),
+                                    );
+                                    failed.fetch_add(1, Ordering::Relaxed);
+                                }
+                            }
+                        } else {
+                            processed.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                    Err(e) => {
+                        reporter.log(LogLevel::Error, format!("Failed to convert: {}", e));
+                        failed.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+
+                let mut finished = finished.lock().unwrap_or_else(|e| e.into_inner());
+                *finished += 1;
+                reporter.progress(*finished, total_files);
+            });
+    });
+
+    check_cancelled(cancel)?;
+
+    Ok((
+        processed.load(Ordering::Relaxed),
+        failed.load(Ordering::Relaxed),
+    ))
+}
+
+/// Every directory a full build of `theme_name` writes into: for each variant its theme,
+/// its Xcursor intermediate and, when installing, its copy in `~/.icons`, plus the base
+/// theme's PNGs when the layout keeps them
+pub fn written_dirs(
+    output_dir: &Path,
+    theme_name: &str,
+    layout: OutputLayout,
+    variants: &[ThemeVariant],
+    install: bool,
+) -> Vec<PathBuf> {
+    let mut written = Vec::new();
+    if layout.keeps_png() {
+        written.push(layout.png_dir(output_dir, theme_name));
+    }
+    for variant in variants {
+        let variant_name = variant.theme_name(theme_name);
+        written.push(output_dir.join(&variant_name));
+        written.push(
+            layout
+                .intermediate_root(output_dir, &variant_name)
+                .join(variant.intermediate_dir_name()),
+        );
+        if install && let Some(home) = dirs::home_dir() {
+            written.push(home.join(".icons").join(&variant_name));
+        }
+    }
+    written
+}
+
+/// Converts every cursor file in `input_dir` and builds `output_dir/theme_name`, plus a
+/// theme for each extra variant, on the calling thread. The conversions themselves run
+/// on `options.thread_count` threads.
+pub fn run_full_theme_pipeline(
+    input_dir: &Path,
+    output_dir: &Path,
+    theme_name: &str,
+    mapping: &CursorMapping,
+    options: &ThemeBuildOptions,
+    reporter: &dyn BuildReporter,
+    cancel: &AtomicBool,
+) -> Result<ThemeBuild> {
+    let cursor_files = find_cursor_files(input_dir);
+    let total_files = cursor_files.len();
+
+    if total_files == 0 {
+        bail!("No .ani or .cur files found");
+    }
+
+    log_target_sizes(reporter, &options.conversion);
+
+    let layout = options.layout;
+    let mut converted = 0;
+    let mut summaries = Vec::new();
+
+    for &variant in &options.variants {
+        check_cancelled(cancel)?;
+        let variant_name = variant.theme_name(theme_name);
+
+        // ANI to XCursor binaries
+        reporter.log(
+            LogLevel::Info,
+            format!(
+                "Converting ANI files to X11 cursor format for {}...",
+                variant_name
+            ),
+        );
+
+        // A kept directory from an earlier build could hold cursors that no longer convert
+        let xcur_dir = layout
+            .intermediate_root(output_dir, &variant_name)
+            .join(variant.intermediate_dir_name());
+        let _ = fs::remove_dir_all(&xcur_dir);
+        fs::create_dir_all(&xcur_dir)?;
+
+        // Run as one step so the intermediate directory is cleaned up when any part fails
+        let built = (|| -> Result<(usize, usize, BuildSummary)> {
+            // Only the base variant feeds the editor preview
+            let png_dir = layout.png_dir(output_dir, theme_name);
+            let png_dir = if variant == ThemeVariant::Base && layout.keeps_png() {
+                fs::create_dir_all(&png_dir)?;
+                Some(png_dir.as_path())
+            } else {
+                None
+            };
+
+            let conversion = variant.apply(options.conversion.clone());
+            let (processed, failed) = convert_batch(
+                &cursor_files,
+                &xcur_dir,
+                png_dir,
+                &conversion,
+                reporter,
+                options.thread_count,
+                cancel,
+            )?;
+
+            if processed == 0 {
+                bail!("Failed to convert any cursor files");
+            }
+
+            reporter.log(
+                LogLevel::Info,
+                format!("Converted {}/{} cursor files", processed, total_files),
+            );
+
+            let summary = build_theme_from_xcur(
+                &xcur_dir,
+                output_dir,
+                &variant_name,
+                mapping,
+                options,
+                reporter,
+                cancel,
+            )?;
+            if variant.builds_hyprcursor() {
+                build_hyprcursor(&summary.theme_dir, options, reporter, cancel)?;
+            } else {
+                reporter.log(
+                    LogLevel::Info,
+                    format!(
+                        "Skipping Hyprcursor output for {} (straight alpha, X11 only)",
+                        variant_name
+                    ),
+                );
+            }
+            Ok((processed, failed, summary))
+        })();
+        clean_intermediate(&xcur_dir, options.keep_intermediate, reporter);
+        let (processed, failed, mut summary) = built?;
+
+        if variant == ThemeVariant::Base {
+            converted = processed;
+        }
+        if failed > 0 {
+            summary.warnings.push(format!(
+                "{} of {} source files failed to convert",
+                failed, total_files
+            ));
+        }
+        summaries.push(summary);
+    }
+
+    Ok(ThemeBuild {
+        converted,
+        summaries,
+    })
+}
+
+pub fn clean_intermediate(xcur_dir: &Path, keep: bool, reporter: &dyn BuildReporter) {
+    if keep {
+        reporter.log(
+            LogLevel::Info,
+            format!("Kept intermediate Xcursor files in {}", xcur_dir.display()),
+        );
+    } else {
+        let _ = fs::remove_dir_all(xcur_dir);
+    }
+}
+
+// Organizes converted XCursor binaries into a named theme
+fn build_theme_from_xcur(
+    xcur_dir: &Path,
+    output_dir: &Path,
+    theme_name: &str,
+    mapping: &CursorMapping,
+    options: &ThemeBuildOptions,
+    reporter: &dyn BuildReporter,
+    cancel: &AtomicBool,
+) -> Result<BuildSummary> {
+    // Organize into theme with mapping
+    reporter.log(
+        LogLevel::Info,
+        "Building XCursor theme with mapping...".to_string(),
+    );
+
+    let theme_output = output_dir.join(theme_name);
+    let builder = XCursorThemeBuilder::new(
+        theme_output.clone(),
+        theme_name.to_string(),
+        mapping.clone(),
+    )
+    .with_install(options.install)
+    .with_inherits(&options.inherits)
+    .with_description(&options.description)
+    .with_link_duplicates(options.link_duplicates)
+    .with_max_dimension(options.conversion.max_dimension);
+
+    let (theme_count, linked) = builder.build_from_xcur_files(xcur_dir, cancel)?;
+    if options.link_duplicates {
+        reporter.log(
+            LogLevel::Info,
+            format!("Linked {} duplicate cursor files to identical ones", linked),
+        );
+    }
+
+    reporter.log(
+        LogLevel::Info,
+        format!("Created theme with {} cursors and symlinks", theme_count),
+    );
+    reporter.log(
+        LogLevel::Info,
+        if options.install {
+            format!("Installed XCursor theme to ~/.icons/{}", theme_name)
+        } else {
+            format!("Skipped installing {} into ~/.icons", theme_name)
+        },
+    );
+
+    let warnings = builder.check_essential_cursors(xcur_dir, &options.essential_cursors);
+    for warning in &warnings {
+        reporter.log(LogLevel::Warn, format!("Warning: {}", warning));
+    }
+
+    Ok(BuildSummary {
+        theme_name: theme_name.to_string(),
+        theme_dir: theme_output.clone(),
+        cursor_count: theme_count,
+        symlink_count: count_symlinks(&theme_output.join("cursors")),
+        warnings,
+    })
+}
+
+// Compiles the Hyprcursor files of a built XCursor theme into the theme directory
+fn build_hyprcursor(
+    theme_output: &Path,
+    options: &ThemeBuildOptions,
+    reporter: &dyn BuildReporter,
+    cancel: &AtomicBool,
+) -> Result<()> {
+    let hyprcursor_options = &options.hyprcursor;
+
+    // Generate Hyprcursor theme
+    reporter.log(LogLevel::Info, "Generating Hyprcursor theme...".to_string());
+
+    let temp_dir = tempfile::tempdir()?;
+    let working_state_dir = temp_dir.path();
+
+    // Extract XCursor theme to working state
+    reporter.log(
+        LogLevel::Info,
+        "Extracting XCursor theme to working state...".to_string(),
+    );
+
+    hyprcursor::extract_xcursor_theme(
+        theme_output,
+        Some(working_state_dir),
+        &ThemeExtractOptions {
+            description: Some(&options.description),
+            exact_output: true,
+            max_dimension: options.conversion.max_dimension,
+            ..ThemeExtractOptions::new(hyprcursor_options)
+        },
+        cancel,
+        |level, msg| reporter.log(level, msg),
+    )?;
+
+    // Compile Hyprcursor theme back into the theme directory
+    reporter.log(LogLevel::Info, "Compiling Hyprcursor theme...".to_string());
+
+    hyprcursor::create_cursor_theme(
+        working_state_dir,
+        Some(theme_output),
+        true,
+        false,
+        hyprcursor_options.compression,
+        cancel,
+        |level, msg| reporter.log(level, msg),
+    )?;
+
+    reporter.log(
+        LogLevel::Info,
+        format!("Generated Hyprcursor files in {}", theme_output.display()),
+    );
+
+    Ok(())
+}
+
+fn count_symlinks(dir: &Path) -> usize {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.file_type().map(|t| t.is_symlink()).unwrap_or(false))
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+// Forwards progress lines and above to a plain callback
+struct LogFnReporter<F>(Mutex<F>);
+
+impl<F> BuildReporter for LogFnReporter<F>
+where
+    F: FnMut(String) + Send,
+{
+    fn log(&self, level: LogLevel, msg: String) {
+        if level >= LogLevel::Progress {
+            (self.0.lock().unwrap_or_else(|e| e.into_inner()))(msg);
+        }
+    }
+}
+
+/// Runs a full theme build on the calling thread, the same way the Runner's full conversion
+/// does, and returns how many source cursors were converted. Log lines go to `log_fn`,
+/// which may be called from the conversion threads. Only the base variant is built with
+/// default conversion settings, and nothing is installed into `~/.icons`; empty `sizes`
+/// keeps the source sizes.
+pub fn build_theme<F>(
+    input_dir: &Path,
+    output_dir: &Path,
+    theme_name: &str,
+    mapping: CursorMapping,
+    sizes: &[u32],
+    log_fn: &mut F,
+) -> Result<usize>
+where
+    F: FnMut(String) + Send,
+{
+    let options = ThemeBuildOptions {
+        conversion: ConversionOptions::new().with_target_sizes(sizes.to_vec()),
+        ..ThemeBuildOptions::default()
+    };
+    let build = run_full_theme_pipeline(
+        input_dir,
+        output_dir,
+        theme_name,
+        &mapping,
+        &options,
+        &LogFnReporter(Mutex::new(log_fn)),
+        &AtomicBool::new(false),
+    )?;
+    Ok(build.converted)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::fs::File;
+    use tempfile::tempdir;
+
+    // Single-image .cur with an embedded PNG
+    pub(crate) fn png_cur_bytes(size: u8, hotspot: (u16, u16)) -> Vec<u8> {
+        let mut png = Vec::new();
+        image::RgbaImage::from_pixel(size as u32, size as u32, image::Rgba([0, 0, 0, 255]))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let mut data = vec![0, 0, 2, 0, 1, 0];
+        data.extend_from_slice(&[size, size, 0, 0]);
+        data.extend_from_slice(&hotspot.0.to_le_bytes());
+        data.extend_from_slice(&hotspot.1.to_le_bytes());
+        data.extend_from_slice(&(png.len() as u32).to_le_bytes());
+        data.extend_from_slice(&22u32.to_le_bytes());
+        data.extend_from_slice(&png);
+        data
+    }
+
+    // Discards log lines and records progress reports
+    struct ProgressLog(Mutex<Vec<(usize, usize)>>);
+
+    impl BuildReporter for ProgressLog {
+        fn log(&self, _level: LogLevel, _msg: String) {}
+
+        fn progress(&self, done: usize, total: usize) {
+            self.0.lock().unwrap().push((done, total));
+        }
+    }
+
+    #[test]
+    fn test_convert_batch_threading() {
+        let temp_dir = tempdir().unwrap();
+        let input_dir = temp_dir.path().join("input");
+        let xcur_dir = temp_dir.path().join("xcur");
+        let png_dir = temp_dir.path().join("png");
+
+        fs::create_dir_all(&input_dir).unwrap();
+        fs::create_dir_all(&xcur_dir).unwrap();
+        fs::create_dir_all(&png_dir).unwrap();
+
+        let mut files = Vec::new();
+        for i in 0..10 {
+            let path = input_dir.join(format!("cursor_{}.cur", i));
+            File::create(&path).unwrap();
+            files.push(path);
+        }
+
+        let reporter = ProgressLog(Mutex::new(Vec::new()));
+        let result = convert_batch(
+            &files,
+            &xcur_dir,
+            Some(&png_dir),
+            &ConversionOptions::new(),
+            &reporter,
+            4,
+            &AtomicBool::new(false),
+        );
+
+        assert!(result.is_ok());
+        let (processed, failed) = result.unwrap();
+        assert_eq!(processed + failed, 10);
+
+        // Every file reports once, in order, even with four threads racing
+        let progress = reporter.0.into_inner().unwrap();
+        assert_eq!(
+            progress,
+            (1..=10).map(|done| (done, 10)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_build_theme_runs_synchronously() {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join("src");
+        fs::create_dir_all(&input).unwrap();
+        fs::write(input.join("Normal.cur"), png_cur_bytes(32, (0, 0))).unwrap();
+        let output = dir.path().join("out");
+
+        let caller = std::thread::current().id();
+        let mut lines = Vec::new();
+        let mut threads = Vec::new();
+        let converted = build_theme(
+            &input,
+            &output,
+            "Test",
+            CursorMapping::default(),
+            &[24],
+            &mut |line| {
+                threads.push(std::thread::current().id());
+                lines.push(line);
+            },
+        )
+        .unwrap();
+
+        assert_eq!(converted, 1);
+        let theme = output.join("Test");
+        assert!(theme.join("index.theme").exists());
+        assert!(theme.join("cursors").join("left_ptr").exists());
+        assert!(theme.join("hyprcursors").join("left_ptr.hlc").exists());
+        assert!(lines.iter().any(|l| l == "Generating sizes: 24"));
+        assert!(lines.iter().any(|l| l.starts_with("Processing 1/1")));
+        // Stage lines come from the caller; only conversions fan out
+        assert_eq!(threads[0], caller);
+        assert_eq!(*threads.last().unwrap(), caller);
+    }
+
+    #[test]
+    fn test_build_theme_reports_missing_sources() {
+        let dir = tempdir().unwrap();
+        let err = build_theme(
+            dir.path(),
+            &dir.path().join("out"),
+            "Test",
+            CursorMapping::default(),
+            &[],
+            &mut |_| {},
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("No .ani or .cur files"), "{err}");
+    }
+
+    #[test]
+    fn test_nested_layout_keeps_intermediates_in_theme() {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join("src");
+        fs::create_dir_all(&input).unwrap();
+        fs::write(input.join("Normal.cur"), png_cur_bytes(32, (0, 0))).unwrap();
+        let output = dir.path().join("out");
+
+        let options = ThemeBuildOptions {
+            keep_intermediate: true,
+            layout: OutputLayout::Nested,
+            thread_count: 1,
+            ..ThemeBuildOptions::default()
+        };
+        run_full_theme_pipeline(
+            &input,
+            &output,
+            "Test",
+            &CursorMapping::default(),
+            &options,
+            &ProgressLog(Mutex::new(Vec::new())),
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        let top: Vec<_> = fs::read_dir(&output)
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(top, vec!["Test"]);
+        let theme = output.join("Test");
+        assert!(theme.join("xcur_intermediate").join("Normal").exists());
+        assert_eq!(
+            OutputLayout::Nested.png_dir(&output, "Test"),
+            theme.join("png_intermediate")
+        );
+        assert!(theme.join("png_intermediate").join("Normal").is_dir());
+        assert!(theme.join("cursors").join("left_ptr").exists());
+    }
+
+    #[test]
+    fn test_intermediate_dir_names_are_listed() {
+        for variant in [
+            ThemeVariant::Base,
+            ThemeVariant::Dark,
+            ThemeVariant::StraightAlpha,
+        ] {
+            assert!(INTERMEDIATE_DIR_NAMES.contains(&variant.intermediate_dir_name()));
+        }
+        let png_dir = OutputLayout::Nested.png_dir(Path::new("out"), "Test");
+        assert!(
+            INTERMEDIATE_DIR_NAMES
+                .iter()
+                .any(|name| png_dir.ends_with(name))
+        );
+    }
+
+    #[test]
+    fn test_written_dirs_cover_every_variant() {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join("src");
+        fs::create_dir_all(&input).unwrap();
+        fs::write(input.join("Normal.cur"), png_cur_bytes(32, (0, 0))).unwrap();
+        let output = dir.path().join("out");
+
+        let variants = vec![ThemeVariant::Base, ThemeVariant::Dark];
+        let options = ThemeBuildOptions {
+            variants: variants.clone(),
+            keep_intermediate: true,
+            thread_count: 1,
+            ..ThemeBuildOptions::default()
+        };
+        run_full_theme_pipeline(
+            &input,
+            &output,
+            "Test",
+            &CursorMapping::default(),
+            &options,
+            &ProgressLog(Mutex::new(Vec::new())),
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        let written = written_dirs(&output, "Test", OutputLayout::Flat, &variants, false);
+        assert!(written.contains(&output.join("Test-Dark")));
+        assert!(written.contains(&output.join("xcur_intermediate_dark")));
+        for entry in fs::read_dir(&output).unwrap() {
+            let path = entry.unwrap().path();
+            assert!(written.contains(&path), "{} not listed", path.display());
+        }
+    }
+
+    #[test]
+    fn test_failed_build_removes_intermediate() {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join("src");
+        fs::create_dir_all(&input).unwrap();
+        fs::write(input.join("Normal.cur"), b"not a cursor").unwrap();
+        let output = dir.path().join("out");
+
+        let options = ThemeBuildOptions {
+            thread_count: 1,
+            ..ThemeBuildOptions::default()
+        };
+        let err = run_full_theme_pipeline(
+            &input,
+            &output,
+            "Test",
+            &CursorMapping::default(),
+            &options,
+            &ProgressLog(Mutex::new(Vec::new())),
+            &AtomicBool::new(false),
+        )
+        .unwrap_err();
+
+        assert_eq!(err.to_string(), "Failed to convert any cursor files");
+        assert!(!output.join("xcur_intermediate").exists());
+    }
+
+    #[test]
+    fn test_theme_only_layout_writes_only_the_theme() {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join("src");
+        fs::create_dir_all(&input).unwrap();
+        fs::write(input.join("Normal.cur"), png_cur_bytes(32, (0, 0))).unwrap();
+        let output = dir.path().join("out");
+
+        let options = ThemeBuildOptions {
+            layout: OutputLayout::ThemeOnly,
+            thread_count: 1,
+            ..ThemeBuildOptions::default()
+        };
+        run_full_theme_pipeline(
+            &input,
+            &output,
+            "Test",
+            &CursorMapping::default(),
+            &options,
+            &ProgressLog(Mutex::new(Vec::new())),
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        let top: Vec<_> = fs::read_dir(&output)
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(top, vec!["Test"]);
+        assert!(!output.join("Test").join("png_intermediate").exists());
+        assert!(
+            output
+                .join("Test")
+                .join("cursors")
+                .join("left_ptr")
+                .exists()
+        );
+    }
+}
@@ -0,0 +1,135 @@
+// Hotspot guesses from the visible pixels of a cursor image
+
+use image::RgbaImage;
+
+use super::win2xcur::cur::CursorFrame;
+
+// Alpha above which a pixel counts as part of the visible cursor shape
+const TIP_ALPHA_THRESHOLD: u8 = 128;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipHeuristic {
+    // Topmost, then leftmost opaque pixel, which is the tip of arrow-style cursors
+    TopLeft,
+    // Center of the opaque bounding box, for crosshairs and move cursors
+    Center,
+}
+
+impl TipHeuristic {
+    pub fn next(&self) -> Self {
+        match self {
+            TipHeuristic::TopLeft => TipHeuristic::Center,
+            TipHeuristic::Center => TipHeuristic::TopLeft,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            TipHeuristic::TopLeft => "top-left tip",
+            TipHeuristic::Center => "center",
+        }
+    }
+}
+
+// Guesses a hotspot from the visible pixels of an image.
+pub fn detect_tip(image: &RgbaImage, heuristic: TipHeuristic) -> Option<(u32, u32)> {
+    let opaque = |x: u32, y: u32| image.get_pixel(x, y)[3] >= TIP_ALPHA_THRESHOLD;
+
+    match heuristic {
+        TipHeuristic::TopLeft => {
+            for y in 0..image.height() {
+                for x in 0..image.width() {
+                    if opaque(x, y) {
+                        return Some((x, y));
+                    }
+                }
+            }
+            None
+        }
+        TipHeuristic::Center => {
+            let mut bounds: Option<(u32, u32, u32, u32)> = None;
+            for y in 0..image.height() {
+                for x in 0..image.width() {
+                    if opaque(x, y) {
+                        let (min_x, min_y, max_x, max_y) = bounds.unwrap_or((x, y, x, y));
+                        bounds = Some((min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)));
+                    }
+                }
+            }
+            bounds.map(|(min_x, min_y, max_x, max_y)| ((min_x + max_x) / 2, (min_y + max_y) / 2))
+        }
+    }
+}
+
+// Arrow-style cursors whose hotspot is expected to sit on the visual tip
+pub fn is_arrow_like(x11_name: &str) -> bool {
+    let name = x11_name.to_lowercase();
+    name == "arrow"
+        || name == "default"
+        || name == "normal"
+        || name.contains("left_ptr")
+        || name.contains("top_left_arrow")
+}
+
+/// Places the hotspot of every image on its tip when the cursor came without one, i.e. all
+/// hotspots are (0, 0). Returns whether any hotspot moved.
+pub fn place_missing_tips(frames: &mut [CursorFrame]) -> bool {
+    let images = || frames.iter().flat_map(|frame| &frame.images);
+    if images().any(|img| img.hotspot != (0, 0)) {
+        return false;
+    }
+
+    let mut moved = false;
+    for img in frames.iter_mut().flat_map(|frame| &mut frame.images) {
+        if let Some((x, y)) = detect_tip(&img.image, TipHeuristic::TopLeft)
+            && (x, y) != (0, 0)
+        {
+            img.hotspot = (x as u16, y as u16);
+            moved = true;
+        }
+    }
+    moved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::win2xcur::cur::CursorImage;
+
+    #[test]
+    fn test_detect_tip_heuristics() {
+        let mut image = RgbaImage::new(16, 16);
+        assert_eq!(detect_tip(&image, TipHeuristic::TopLeft), None);
+
+        // Faint pixels stay below the alpha threshold
+        image.put_pixel(0, 0, image::Rgba([0, 0, 0, 40]));
+        for (x, y) in [(5, 3), (2, 4), (9, 11)] {
+            image.put_pixel(x, y, image::Rgba([0, 0, 0, 255]));
+        }
+        assert_eq!(detect_tip(&image, TipHeuristic::TopLeft), Some((5, 3)));
+        assert_eq!(detect_tip(&image, TipHeuristic::Center), Some((5, 7)));
+    }
+
+    #[test]
+    fn test_missing_tips_are_placed_only_without_a_hotspot() {
+        let mut image = RgbaImage::new(8, 8);
+        image.put_pixel(3, 1, image::Rgba([0, 0, 0, 255]));
+        let frame = |hotspot| CursorFrame {
+            images: vec![CursorImage {
+                image: image.clone(),
+                hotspot,
+                nominal_size: 8,
+            }],
+            delay: 0,
+        };
+
+        let mut frames = vec![frame((0, 0)), frame((0, 0))];
+        assert!(place_missing_tips(&mut frames));
+        assert!(frames.iter().all(|f| f.images[0].hotspot == (3, 1)));
+
+        // One real hotspot means the source set them, (0, 0) included
+        let mut frames = vec![frame((0, 0)), frame((2, 2))];
+        assert!(!place_missing_tips(&mut frames));
+        assert_eq!(frames[0].images[0].hotspot, (0, 0));
+    }
+}
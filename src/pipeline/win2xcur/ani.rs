@@ -3,6 +3,7 @@ use byteorder::{LittleEndian, ReadBytesExt};
 use std::io::{Cursor, Read, Seek, SeekFrom};
 
 use super::cur::{CurParser, CursorFrame};
+use crate::pipeline::log::LogLevel;
 
 const SIGNATURE: &[u8] = b"RIFF";
 const ANI_TYPE: &[u8] = b"ACON";
@@ -12,11 +13,67 @@ const SEQ_CHUNK: &[u8] = b"seq ";
 const RATE_CHUNK: &[u8] = b"rate";
 const FRAME_TYPE: &[u8] = b"fram";
 const ICON_CHUNK: &[u8] = b"icon";
+const INFO_TYPE: &[u8] = b"INFO";
+const NAME_CHUNK: &[u8] = b"INAM";
+const ARTIST_CHUNK: &[u8] = b"IART";
 
 const ICON_FLAG: u32 = 0x1;
 
 pub struct AniParser;
 
+/// Strings from an ANI file's `LIST`/`INFO` chunk
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AniMetadata {
+    pub name: Option<String>,
+    pub artist: Option<String>,
+}
+
+/// Playback data of an ANI file as stored, before it is expanded into one frame per step
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AniSequence {
+    pub frame_count: usize,
+    pub step_count: usize,
+    // Header rate in jiffies, used for every step when there is no `rate` chunk
+    pub display_rate: u32,
+    // Contents of the `seq ` chunk, if present
+    pub order: Option<Vec<u32>>,
+    // Contents of the `rate` chunk in jiffies, if present
+    pub rates: Option<Vec<u32>>,
+}
+
+impl AniSequence {
+    /// Frame index, delay in jiffies and delay in ms of every step, in playback order
+    pub fn steps(&self) -> Vec<(u32, u32, u32)> {
+        (0..self.step_count)
+            .map(|i| {
+                let frame = match &self.order {
+                    Some(order) => order
+                        .get(i)
+                        .copied()
+                        .unwrap_or((i % self.frame_count.max(1)) as u32),
+                    None => (i % self.frame_count.max(1)) as u32,
+                };
+                let jiffies = match &self.rates {
+                    Some(rates) => rates.get(i).copied().unwrap_or(self.display_rate),
+                    None => self.display_rate,
+                };
+                (frame, jiffies, jiffies_to_ms(jiffies))
+            })
+            .collect()
+    }
+}
+
+// Rates are in jiffies (1/60 s)
+fn jiffies_to_ms(jiffies: u32) -> u32 {
+    ((jiffies as f64 / 60.0) * 1000.0) as u32
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SequenceStep {
+    frame_index: usize,
+    delay_ms: u32,
+}
+
 #[derive(Debug)]
 struct AnihHeader {
     size: u32,
@@ -33,7 +90,7 @@ struct AnihHeader {
 impl AnihHeader {
     fn validate<F>(&self, _log_fn: F) -> Result<()>
     where
-        F: FnMut(String),
+        F: FnMut(LogLevel, String),
     {
         if self.size != 36 {
             bail!("Invalid ANI header size: {}", self.size);
@@ -48,9 +105,21 @@ impl AniParser {
         data.len() >= 12 && &data[0..4] == SIGNATURE && &data[8..12] == ANI_TYPE
     }
 
-    pub fn parse<F>(data: &[u8], mut log_fn: F) -> Result<Vec<CursorFrame>>
+    pub fn parse<F>(data: &[u8], max_dimension: u32, log_fn: F) -> Result<Vec<CursorFrame>>
     where
-        F: FnMut(String),
+        F: FnMut(LogLevel, String),
+    {
+        Self::parse_detailed(data, max_dimension, log_fn).map(|(frames, _)| frames)
+    }
+
+    /// `parse` plus the raw `seq `/`rate` data, for inspecting the timing of a cursor
+    pub fn parse_detailed<F>(
+        data: &[u8],
+        max_dimension: u32,
+        mut log_fn: F,
+    ) -> Result<(Vec<CursorFrame>, AniSequence)>
+    where
+        F: FnMut(LogLevel, String),
     {
         if !Self::can_parse(data) {
             bail!("Not a valid .ANI file");
@@ -100,21 +169,16 @@ impl AniParser {
                             &mut cursor,
                             data,
                             header.frame_count as usize,
+                            max_dimension,
                             &mut log_fn,
                         )?;
                     }
                 }
                 SEQ_CHUNK => {
-                    order = Some(Self::read_seq_chunk(
-                        &mut cursor,
-                        header.step_count as usize,
-                    )?);
+                    order = Some(Self::read_u32_chunk(&mut cursor, chunk_size)?);
                 }
                 RATE_CHUNK => {
-                    delays = Some(Self::read_rate_chunk(
-                        &mut cursor,
-                        header.step_count as usize,
-                    )?);
+                    delays = Some(Self::read_u32_chunk(&mut cursor, chunk_size)?);
                 }
                 _ => {
                     // Skip unknown chunk
@@ -128,30 +192,157 @@ impl AniParser {
             }
         }
 
-        // Build final sequence
-        let order = order.unwrap_or_else(|| (0..header.frame_count).collect());
-        let delays =
-            delays.unwrap_or_else(|| vec![header.display_rate; header.step_count as usize]);
+        let sequence = AniSequence {
+            frame_count: frames.len(),
+            step_count: header.step_count as usize,
+            display_rate: header.display_rate,
+            order: order.clone(),
+            rates: delays.clone(),
+        };
+        let steps = Self::resolve_sequence(&header, order, delays, frames.len(), &mut log_fn)?;
+        let sequence = AniSequence {
+            step_count: steps.len(),
+            ..sequence
+        };
+
+        // Expand into one frame per step so every consumer (X11 writer, PNG extraction,
+        // Hyprcursor meta and the editor) sees the exact same order and timing
+        let frames = steps
+            .into_iter()
+            .map(|step| {
+                let mut frame = frames[step.frame_index].clone();
+                frame.delay = step.delay_ms;
+                frame
+            })
+            .collect();
+        Ok((frames, sequence))
+    }
+
+    /// `parse` plus the name and artist from the INFO list, if the file has one
+    pub fn parse_with_metadata<F>(
+        data: &[u8],
+        max_dimension: u32,
+        log_fn: F,
+    ) -> Result<(Vec<CursorFrame>, AniMetadata)>
+    where
+        F: FnMut(LogLevel, String),
+    {
+        let frames = Self::parse(data, max_dimension, log_fn)?;
+        Ok((frames, Self::read_metadata(data)))
+    }
+
+    // Walks the top-level chunks on its own, since INFO may sit before `anih` or after the
+    // frame list. Damaged or missing INFO just yields empty metadata.
+    fn read_metadata(data: &[u8]) -> AniMetadata {
+        let mut metadata = AniMetadata::default();
+        let mut cursor = Cursor::new(data);
+        cursor.set_position(12);
+
+        while let Ok((name, size, data_start)) = Self::read_chunk(&mut cursor) {
+            let end = (data_start + size as u64).min(data.len() as u64);
+
+            if name == LIST_CHUNK
+                && data.get(data_start as usize..data_start as usize + 4) == Some(INFO_TYPE)
+            {
+                cursor.set_position(data_start + 4);
+                while cursor.position() + 8 <= end {
+                    let Ok((sub_name, sub_size, sub_start)) = Self::read_chunk(&mut cursor) else {
+                        break;
+                    };
+                    let sub_end = (sub_start + sub_size as u64).min(end);
+                    let text = String::from_utf8_lossy(&data[sub_start as usize..sub_end as usize])
+                        .trim_end_matches('\0')
+                        .trim()
+                        .to_string();
+
+                    if !text.is_empty() {
+                        match &sub_name[..] {
+                            NAME_CHUNK => metadata.name = Some(text),
+                            ARTIST_CHUNK => metadata.artist = Some(text),
+                            _ => {}
+                        }
+                    }
+                    cursor.set_position(sub_end + (sub_end & 1));
+                }
+            }
+
+            cursor.set_position(end + (end & 1));
+        }
+
+        metadata
+    }
 
-        if order.len() != header.step_count as usize {
-            bail!("Sequence length mismatch");
+    /// Resolve the playback order: the 'seq ' chunk when present, otherwise steps map to
+    /// frames in file order. Delays come from 'rate' or fall back to the header display rate.
+    /// A zero step count means one step per frame; a `seq ` or `rate` chunk of the wrong
+    /// length is truncated, or padded with sequential frames and the display rate.
+    fn resolve_sequence<F>(
+        header: &AnihHeader,
+        order: Option<Vec<u32>>,
+        delays: Option<Vec<u32>>,
+        frame_count: usize,
+        mut log_fn: F,
+    ) -> Result<Vec<SequenceStep>>
+    where
+        F: FnMut(LogLevel, String),
+    {
+        if frame_count == 0 {
+            bail!("ANI file contains no frames");
         }
-        if delays.len() != header.step_count as usize {
-            bail!("Rate length mismatch");
+
+        let mut step_count = header.step_count as usize;
+        if step_count == 0 {
+            log_fn(
+                LogLevel::Warn,
+                format!(
+                    "Warning: ANI step count is 0, using frame count {}",
+                    frame_count
+                ),
+            );
+            step_count = frame_count;
         }
 
-        // Create sequence of frames
-        let mut sequence = Vec::new();
-        for (idx, delay) in order.iter().zip(delays.iter()) {
-            if *idx >= frames.len() as u32 {
-                bail!("Invalid frame index in sequence");
-            }
-            let mut frame = frames[*idx as usize].clone();
-            frame.delay = ((*delay as f64 / 60.0) * 1000.0) as u32;
-            sequence.push(frame);
+        let mut order =
+            order.unwrap_or_else(|| (0..step_count).map(|i| (i % frame_count) as u32).collect());
+        if order.len() != step_count {
+            log_fn(
+                LogLevel::Warn,
+                format!(
+                    "Warning: ANI sequence has {} entries for {} steps, repairing",
+                    order.len(),
+                    step_count
+                ),
+            );
+            order.truncate(step_count);
+            order.extend((order.len()..step_count).map(|i| (i % frame_count) as u32));
         }
 
-        Ok(sequence)
+        let mut delays = delays.unwrap_or_else(|| vec![header.display_rate; step_count]);
+        if delays.len() != step_count {
+            log_fn(
+                LogLevel::Warn,
+                format!(
+                    "Warning: ANI rate table has {} entries for {} steps, repairing",
+                    delays.len(),
+                    step_count
+                ),
+            );
+            delays.resize(step_count, header.display_rate);
+        }
+
+        order
+            .into_iter()
+            .zip(delays)
+            .map(|(idx, jiffies)| {
+                if idx as usize >= frame_count {
+                    bail!("Invalid frame index in sequence");
+                }
+                Ok(SequenceStep {
+                    frame_index: idx as usize,
+                    delay_ms: jiffies_to_ms(jiffies),
+                })
+            })
+            .collect()
     }
 
     fn read_chunk(cursor: &mut Cursor<&[u8]>) -> Result<([u8; 4], u32, u64)> {
@@ -209,10 +400,11 @@ impl AniParser {
         cursor: &mut Cursor<&[u8]>,
         full_data: &[u8],
         count: usize,
+        max_dimension: u32,
         mut log_fn: F,
     ) -> Result<Vec<CursorFrame>>
     where
-        F: FnMut(String),
+        F: FnMut(LogLevel, String),
     {
         let mut frames = Vec::new();
 
@@ -229,7 +421,7 @@ impl AniParser {
             }
 
             let icon_data = &full_data[start..end];
-            let cur_frames = CurParser::parse(icon_data, &mut log_fn)?;
+            let cur_frames = CurParser::parse(icon_data, max_dimension, &mut log_fn)?;
 
             if let Some(frame) = cur_frames.first() {
                 frames.push(frame.clone());
@@ -244,26 +436,21 @@ impl AniParser {
         Ok(frames)
     }
 
-    fn read_seq_chunk(cursor: &mut Cursor<&[u8]>, count: usize) -> Result<Vec<u32>> {
-        let mut seq = Vec::new();
-        for _ in 0..count {
-            seq.push(cursor.read_u32::<LittleEndian>()?);
-        }
-        Ok(seq)
-    }
-
-    fn read_rate_chunk(cursor: &mut Cursor<&[u8]>, count: usize) -> Result<Vec<u32>> {
-        let mut rates = Vec::new();
-        for _ in 0..count {
-            rates.push(cursor.read_u32::<LittleEndian>()?);
+    // Reads a `seq ` or `rate` chunk by its own size, since it may disagree with the header
+    fn read_u32_chunk(cursor: &mut Cursor<&[u8]>, chunk_size: u32) -> Result<Vec<u32>> {
+        let mut values = Vec::new();
+        for _ in 0..chunk_size / 4 {
+            values.push(cursor.read_u32::<LittleEndian>()?);
         }
-        Ok(rates)
+        cursor.seek(SeekFrom::Current((chunk_size % 4) as i64))?;
+        Ok(values)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pipeline::limits::DEFAULT_MAX_DIMENSION;
 
     #[test]
     fn test_ani_detection() {
@@ -273,4 +460,241 @@ mod tests {
         let invalid = b"RIFF\x00\x00\x00\x00WAVE";
         assert!(!AniParser::can_parse(invalid));
     }
+
+    fn header(frame_count: u32, step_count: u32) -> AnihHeader {
+        AnihHeader {
+            size: 36,
+            frame_count,
+            step_count,
+            _width: 0,
+            _height: 0,
+            _bit_count: 0,
+            _planes: 0,
+            display_rate: 6,
+            flags: ICON_FLAG,
+        }
+    }
+
+    fn chunk(name: &[u8], body: &[u8]) -> Vec<u8> {
+        let mut data = name.to_vec();
+        data.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        data.extend_from_slice(body);
+        if body.len() % 2 == 1 {
+            data.push(0);
+        }
+        data
+    }
+
+    #[test]
+    fn test_read_metadata_from_info_list() {
+        let mut info = INFO_TYPE.to_vec();
+        info.extend(chunk(NAME_CHUNK, b"Busy Ring\0"));
+        info.extend(chunk(ARTIST_CHUNK, b"Someone\0"));
+
+        let mut anih = Vec::new();
+        for value in [36u32, 1, 1, 0, 0, 0, 0, 6, ICON_FLAG] {
+            anih.extend_from_slice(&value.to_le_bytes());
+        }
+
+        // INFO ahead of `anih`, the way several cursor editors write it
+        let mut body = ANI_TYPE.to_vec();
+        body.extend(chunk(LIST_CHUNK, &info));
+        body.extend(chunk(HEADER_CHUNK, &anih));
+        let mut data = SIGNATURE.to_vec();
+        data.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        data.extend(body);
+
+        assert_eq!(
+            AniParser::read_metadata(&data),
+            AniMetadata {
+                name: Some("Busy Ring".to_string()),
+                artist: Some("Someone".to_string()),
+            }
+        );
+
+        let mut without_info = SIGNATURE.to_vec();
+        without_info.extend_from_slice(&4u32.to_le_bytes());
+        without_info.extend_from_slice(ANI_TYPE);
+        assert_eq!(
+            AniParser::read_metadata(&without_info),
+            AniMetadata::default()
+        );
+    }
+
+    // Minimal icon-flagged ANI; every frame is a 1x1 PNG-embedded .cur
+    fn ani_bytes(frame_count: u32, seq: &[u32], rate: &[u32]) -> Vec<u8> {
+        let mut png = Vec::new();
+        image::RgbaImage::new(1, 1)
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let mut icon = vec![0, 0, 2, 0, 1, 0, 1, 1, 0, 0, 0, 0, 0, 0];
+        icon.extend_from_slice(&(png.len() as u32).to_le_bytes());
+        icon.extend_from_slice(&22u32.to_le_bytes());
+        icon.extend_from_slice(&png);
+
+        let mut anih = Vec::new();
+        for value in [36, frame_count, seq.len() as u32, 0, 0, 0, 0, 6, ICON_FLAG] {
+            anih.extend_from_slice(&value.to_le_bytes());
+        }
+        let mut frames = FRAME_TYPE.to_vec();
+        for _ in 0..frame_count {
+            frames.extend(chunk(ICON_CHUNK, &icon));
+        }
+        let words =
+            |values: &[u32]| -> Vec<u8> { values.iter().flat_map(|v| v.to_le_bytes()).collect() };
+
+        let mut body = ANI_TYPE.to_vec();
+        body.extend(chunk(HEADER_CHUNK, &anih));
+        body.extend(chunk(SEQ_CHUNK, &words(seq)));
+        body.extend(chunk(RATE_CHUNK, &words(rate)));
+        body.extend(chunk(LIST_CHUNK, &frames));
+        let mut data = SIGNATURE.to_vec();
+        data.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        data.extend(body);
+        data
+    }
+
+    #[test]
+    fn test_repeated_sequence_steps_keep_their_delays() {
+        let data = ani_bytes(2, &[0, 1, 0], &[3, 6, 12]);
+        let frames = AniParser::parse(&data, DEFAULT_MAX_DIMENSION, |_, _| {}).unwrap();
+
+        let delays: Vec<u32> = frames.iter().map(|f| f.delay).collect();
+        assert_eq!(delays, vec![50, 100, 200]);
+
+        // The X11 writer emits one image per step, so repeats survive with their own timing
+        let x11 = super::super::xcursor_writer::to_x11(&frames).unwrap();
+        let images = xcursor::parser::parse_xcursor(&x11).unwrap();
+        let delays: Vec<u32> = images.iter().map(|img| img.delay).collect();
+        assert_eq!(delays, vec![50, 100, 200]);
+    }
+
+    #[test]
+    fn test_repeated_sequence_steps_reach_the_cursor_meta() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("wait.ani"),
+            ani_bytes(2, &[0, 1, 0], &[3, 6, 12]),
+        )
+        .unwrap();
+
+        // Loading goes through convert_windows_cursor_to_meta, which the editor preview uses
+        let cursors = crate::pipeline::cursor_io::load_cursor_folder(
+            dir.path(),
+            DEFAULT_MAX_DIMENSION,
+            |_, msg| panic!("{msg}"),
+        )
+        .unwrap();
+        assert_eq!(cursors.len(), 1);
+        assert_eq!(cursors[0].variants.len(), 1);
+        let delays: Vec<u32> = cursors[0].variants[0]
+            .frames
+            .iter()
+            .map(|f| f.delay_ms)
+            .collect();
+        assert_eq!(delays, vec![50, 100, 200]);
+    }
+
+    #[test]
+    fn test_parse_detailed_keeps_raw_sequence() {
+        let data = ani_bytes(2, &[1, 0, 1], &[3, 6, 12]);
+        let (frames, sequence) =
+            AniParser::parse_detailed(&data, DEFAULT_MAX_DIMENSION, |_, _| {}).unwrap();
+
+        assert_eq!(frames.len(), 3);
+        assert_eq!(sequence.frame_count, 2);
+        assert_eq!(sequence.order, Some(vec![1, 0, 1]));
+        assert_eq!(sequence.rates, Some(vec![3, 6, 12]));
+        assert_eq!(
+            sequence.steps(),
+            vec![(1, 3, 50), (0, 6, 100), (1, 12, 200)]
+        );
+
+        let implicit = AniSequence {
+            frame_count: 2,
+            step_count: 3,
+            display_rate: 6,
+            order: None,
+            rates: None,
+        };
+        assert_eq!(
+            implicit.steps(),
+            vec![(0, 6, 100), (1, 6, 100), (0, 6, 100)]
+        );
+    }
+
+    #[test]
+    fn test_resolve_sequence_from_seq_chunk() {
+        let steps = AniParser::resolve_sequence(
+            &header(2, 3),
+            Some(vec![1, 0, 1]),
+            Some(vec![3, 6, 12]),
+            2,
+            |_, _| {},
+        )
+        .unwrap();
+
+        let indices: Vec<usize> = steps.iter().map(|s| s.frame_index).collect();
+        let delays: Vec<u32> = steps.iter().map(|s| s.delay_ms).collect();
+        assert_eq!(indices, vec![1, 0, 1]);
+        assert_eq!(delays, vec![50, 100, 200]);
+    }
+
+    #[test]
+    fn test_resolve_sequence_implicit_order() {
+        let steps = AniParser::resolve_sequence(&header(3, 3), None, None, 3, |_, _| {}).unwrap();
+
+        let indices: Vec<usize> = steps.iter().map(|s| s.frame_index).collect();
+        assert_eq!(indices, vec![0, 1, 2]);
+        assert!(steps.iter().all(|s| s.delay_ms == 100));
+
+        assert!(
+            AniParser::resolve_sequence(&header(2, 2), Some(vec![0, 5]), None, 2, |_, _| {})
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_short_seq_is_padded() {
+        let mut warnings = Vec::new();
+        let steps = AniParser::resolve_sequence(
+            &header(3, 4),
+            Some(vec![2, 2]),
+            Some(vec![3, 6, 12, 12, 24]),
+            3,
+            |_, msg| warnings.push(msg),
+        )
+        .unwrap();
+
+        let indices: Vec<usize> = steps.iter().map(|s| s.frame_index).collect();
+        let delays: Vec<u32> = steps.iter().map(|s| s.delay_ms).collect();
+        assert_eq!(indices, vec![2, 2, 2, 0]);
+        assert_eq!(delays, vec![50, 100, 200, 200]);
+        assert_eq!(warnings.len(), 2);
+
+        // A seq chunk shorter than the header claims is read by its own size
+        let mut data = ani_bytes(2, &[1, 0, 1], &[3, 6, 12]);
+        let anih_step_count = 12 + 8 + 8;
+        data[anih_step_count..anih_step_count + 4].copy_from_slice(&5u32.to_le_bytes());
+        let (frames, sequence) =
+            AniParser::parse_detailed(&data, DEFAULT_MAX_DIMENSION, |_, _| {}).unwrap();
+        assert_eq!(frames.len(), 5);
+        assert_eq!(sequence.step_count, 5);
+        assert_eq!(
+            sequence.steps().iter().map(|s| s.0).collect::<Vec<_>>(),
+            vec![1, 0, 1, 1, 0]
+        );
+    }
+
+    #[test]
+    fn test_zero_step_count_uses_frame_count() {
+        let mut warnings = Vec::new();
+        let steps =
+            AniParser::resolve_sequence(&header(3, 0), None, None, 3, |_, msg| warnings.push(msg))
+                .unwrap();
+
+        let indices: Vec<usize> = steps.iter().map(|s| s.frame_index).collect();
+        assert_eq!(indices, vec![0, 1, 2]);
+        assert_eq!(warnings.len(), 1);
+    }
 }
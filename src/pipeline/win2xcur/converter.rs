@@ -1,21 +1,76 @@
 // High-level conversion API for Windows to X11 cursor conversion
 
-use anyhow::Result;
-use std::collections::HashMap;
+use anyhow::{Context, Result, bail};
+use image::imageops::FilterType;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
+use crate::pipeline::xcur2png::XcursorFile;
+
 use super::{
-    cur::CursorFrame,
-    utils::{ShadowConfig, apply_shadows, scale_frames},
-    xcursor_writer,
+    cur::{CursorFrame, CursorImage},
+    utils::{ShadowConfig, apply_shadows, invert_colors, scale_frames, trim_transparent},
+    xcursor_writer::{self, AlphaMode},
 };
+use crate::pipeline::cursor_types::clamp_hotspot;
+use crate::pipeline::limits::{DEFAULT_MAX_DIMENSION, effective_max_dimension};
+use crate::pipeline::log::LogLevel;
+use crate::pipeline::tip::{is_arrow_like, place_missing_tips};
+
+/// Filters offered for resizing, in the order settings cycle through them
+pub const RESIZE_FILTERS: [FilterType; 5] = [
+    FilterType::Lanczos3,
+    FilterType::CatmullRom,
+    FilterType::Gaussian,
+    FilterType::Triangle,
+    FilterType::Nearest,
+];
+
+pub fn resize_filter_name(filter: FilterType) -> &'static str {
+    match filter {
+        FilterType::Nearest => "Nearest",
+        FilterType::Triangle => "Triangle",
+        FilterType::CatmullRom => "CatmullRom",
+        FilterType::Gaussian => "Gaussian",
+        FilterType::Lanczos3 => "Lanczos3",
+    }
+}
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct ConversionOptions {
     pub scale: Option<f32>,
     pub shadow: Option<ShadowConfig>,
     pub hotspot_overrides: HashMap<u32, (u32, u32)>,
     pub target_sizes: Vec<u32>,
+    pub guaranteed_sizes: Vec<u32>,
+    pub invert_colors: bool,
+    pub trim_transparent: bool,
+    pub replace_sizes: bool,
+    pub resize_filter: FilterType,
+    pub alpha_mode: AlphaMode,
+    pub comment: Option<String>,
+    pub verify: bool,
+    pub max_dimension: u32,
+}
+
+impl Default for ConversionOptions {
+    fn default() -> Self {
+        Self {
+            scale: None,
+            shadow: None,
+            hotspot_overrides: HashMap::new(),
+            target_sizes: Vec::new(),
+            guaranteed_sizes: Vec::new(),
+            invert_colors: false,
+            trim_transparent: false,
+            replace_sizes: false,
+            resize_filter: FilterType::Lanczos3,
+            alpha_mode: AlphaMode::Premultiplied,
+            comment: None,
+            verify: false,
+            max_dimension: DEFAULT_MAX_DIMENSION,
+        }
+    }
 }
 
 impl ConversionOptions {
@@ -47,89 +102,245 @@ impl ConversionOptions {
         self.target_sizes = sizes;
         self
     }
+
+    /// Sizes every output contains regardless of the source or `replace_sizes`, resized from
+    /// the closest image when missing; for consumers that only look for common sizes
+    pub fn with_guaranteed_sizes(mut self, sizes: Vec<u32>) -> Self {
+        self.guaranteed_sizes = sizes;
+        self
+    }
+
+    pub fn with_inverted_colors(mut self, invert: bool) -> Self {
+        self.invert_colors = invert;
+        self
+    }
+
+    /// Crop transparent margins off every image just before writing, hotspots moved along
+    pub fn with_trim_transparent(mut self, trim: bool) -> Self {
+        self.trim_transparent = trim;
+        self
+    }
+
+    /// Keep only `target_sizes` in the output instead of adding them next to the source sizes
+    pub fn with_replace_sizes(mut self, replace: bool) -> Self {
+        self.replace_sizes = replace;
+        self
+    }
+
+    /// Filter used for scaling and target-size resizing; `Nearest` keeps pixel-art edges crisp
+    pub fn with_filter(mut self, filter: FilterType) -> Self {
+        self.resize_filter = filter;
+        self
+    }
+
+    /// Largest width or height accepted from the source, checked before decoding; 0 means
+    /// the default
+    pub fn with_max_dimension(mut self, max: u32) -> Self {
+        self.max_dimension = effective_max_dimension(max);
+        self
+    }
+
+    /// Write straight instead of premultiplied alpha, for diagnosing misbehaving consumers
+    pub fn with_alpha_mode(mut self, alpha_mode: AlphaMode) -> Self {
+        self.alpha_mode = alpha_mode;
+        self
+    }
+
+    /// Embed an Xcursor comment chunk, e.g. the cursor's name, in the output
+    pub fn with_comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    /// Read every written file back and fail the conversion if it doesn't parse
+    pub fn with_verify(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
 }
 
-pub fn convert_to_x11(
+pub fn convert_to_x11<F>(
     mut frames: Vec<CursorFrame>,
     options: &ConversionOptions,
-) -> Result<Vec<u8>> {
-    // Apply hotspot overrides
-    if !options.hotspot_overrides.is_empty() {
-        for frame in &mut frames {
-            for image in &mut frame.images {
-                if let Some(&hotspot) = options.hotspot_overrides.get(&image.nominal_size) {
-                    image.hotspot = (hotspot.0 as u16, hotspot.1 as u16);
-                }
-            }
-        }
-    }
+    mut log_fn: F,
+) -> Result<Vec<u8>>
+where
+    F: FnMut(LogLevel, String),
+{
+    clamp_hotspots(&mut frames, &mut log_fn);
+
+    // Overrides are in the pixels of the size they name: source images take theirs
+    // before scaling, images resized to a new size once they exist
+    apply_hotspot_overrides(&mut frames, &options.hotspot_overrides, None);
 
     if let Some(scale) = options.scale {
-        scale_frames(&mut frames, scale);
+        scale_frames(&mut frames, scale, options.resize_filter);
     }
 
+    let source_sizes: Vec<HashSet<u32>> = frames
+        .iter()
+        .map(|f| f.images.iter().map(|i| i.nominal_size).collect())
+        .collect();
+
     // Handle target sizes resizing
     if !options.target_sizes.is_empty() {
-        for frame in &mut frames {
-            let mut new_images = Vec::new();
-
-            // We assume the first image in the frame is the "source" to resize from
-            // usually ANI/CUR frames have one image per frame index, but can have multiple sizes.
-            // We'll take the largest one as source if multiple exist.
-            if let Some(source_image) = frame.images.iter().max_by_key(|i| i.nominal_size) {
-                for &size in &options.target_sizes {
-                    // Check if we already have this size
-                    if frame.images.iter().any(|i| i.nominal_size == size) {
-                        continue;
-                    }
-
-                    let _width = source_image.image.width();
-                    let _height = source_image.image.height();
-
-                    // Calculate scale factor
-                    let scale = size as f32 / source_image.nominal_size as f32;
-
-                    let new_width = size;
-                    let new_height = size; // Force square for cursor sizes usually
-
-                    let scaled_img = image::imageops::resize(
-                        &source_image.image,
-                        new_width,
-                        new_height,
-                        image::imageops::FilterType::Lanczos3,
-                    );
-
-                    let (new_hotspot_x, new_hotspot_y) =
-                        if let Some(&override_hotspot) = options.hotspot_overrides.get(&size) {
-                            (
-                                override_hotspot.0.min(u16::MAX as u32) as u16,
-                                override_hotspot.1.min(u16::MAX as u32) as u16,
-                            )
-                        } else {
-                            (
-                                (source_image.hotspot.0 as f32 * scale).round() as u16,
-                                (source_image.hotspot.1 as f32 * scale).round() as u16,
-                            )
-                        };
-
-                    use super::cur::CursorImage;
-                    new_images.push(CursorImage {
-                        image: scaled_img,
-                        hotspot: (new_hotspot_x, new_hotspot_y),
-                        nominal_size: size,
-                    });
+        resize_to_target_sizes(&mut frames, options);
+    }
+
+    if !options.guaranteed_sizes.is_empty() {
+        add_guaranteed_sizes(&mut frames, options);
+    }
+
+    apply_hotspot_overrides(&mut frames, &options.hotspot_overrides, Some(&source_sizes));
+
+    if options.invert_colors {
+        invert_colors(&mut frames);
+    }
+
+    if let Some(ref shadow_config) = options.shadow {
+        apply_shadows(&mut frames, shadow_config)?;
+    }
+
+    if options.trim_transparent {
+        trim_transparent(&mut frames);
+    }
+
+    xcursor_writer::to_x11_with_comment(&frames, options.alpha_mode, options.comment.as_deref())
+}
+
+fn resize_to_target_sizes(frames: &mut [CursorFrame], options: &ConversionOptions) {
+    for frame in frames {
+        let mut new_images = Vec::new();
+
+        for &size in &options.target_sizes {
+            // Check if we already have this size
+            if let Some(existing) = frame.images.iter().find(|i| i.nominal_size == size) {
+                if options.replace_sizes {
+                    new_images.push(existing.clone());
                 }
+                continue;
             }
 
+            if let Some(image) = resized_from_closest(&frame.images, size, options.resize_filter) {
+                new_images.push(image);
+            }
+        }
+
+        if options.replace_sizes {
+            frame.images = new_images;
+        } else {
             frame.images.extend(new_images);
         }
     }
+}
 
-    if let Some(ref shadow_config) = options.shadow {
-        apply_shadows(&mut frames, shadow_config)?;
+fn add_guaranteed_sizes(frames: &mut [CursorFrame], options: &ConversionOptions) {
+    for frame in frames {
+        for &size in &options.guaranteed_sizes {
+            if frame.images.iter().any(|i| i.nominal_size == size) {
+                continue;
+            }
+            if let Some(image) = resized_from_closest(&frame.images, size, options.resize_filter) {
+                frame.images.push(image);
+            }
+        }
+    }
+}
+
+// Downscaling from the nearest larger image keeps the most detail; only upscale from the
+// largest when nothing bigger exists
+fn resized_from_closest(
+    images: &[CursorImage],
+    size: u32,
+    filter: FilterType,
+) -> Option<CursorImage> {
+    let source_image = images
+        .iter()
+        .filter(|i| i.nominal_size > size)
+        .min_by_key(|i| i.nominal_size)
+        .or_else(|| images.iter().max_by_key(|i| i.nominal_size))?;
+
+    let scale = size as f32 / source_image.nominal_size as f32;
+    // Force square for cursor sizes usually
+    let scaled_img = image::imageops::resize(&source_image.image, size, size, filter);
+
+    Some(CursorImage {
+        image: scaled_img,
+        hotspot: (
+            (source_image.hotspot.0 as f32 * scale).round() as u16,
+            (source_image.hotspot.1 as f32 * scale).round() as u16,
+        ),
+        nominal_size: size,
+    })
+}
+
+// Some cursors in the wild put the hotspot outside the image, which yields Xcursor files
+// that compositors reject; pull those back onto the nearest edge pixel.
+fn clamp_hotspots<F>(frames: &mut [CursorFrame], log_fn: &mut F)
+where
+    F: FnMut(LogLevel, String),
+{
+    let mut reported = HashSet::new();
+
+    for image in frames.iter_mut().flat_map(|f| f.images.iter_mut()) {
+        let clamped = clamp_to_image((image.hotspot.0 as u32, image.hotspot.1 as u32), image);
+
+        if clamped != image.hotspot {
+            if reported.insert((image.nominal_size, image.hotspot)) {
+                log_fn(
+                    LogLevel::Warn,
+                    format!(
+                        "Warning: hotspot {:?} is outside the {}x{} image at size {}, clamped to {:?}",
+                        image.hotspot,
+                        image.image.width(),
+                        image.image.height(),
+                        image.nominal_size,
+                        clamped
+                    ),
+                );
+            }
+            image.hotspot = clamped;
+        }
     }
+}
+
+// `clamp_hotspot` for a cursor image, whose hotspot is stored as u16
+fn clamp_to_image(hotspot: (u32, u32), image: &CursorImage) -> (u16, u16) {
+    let (x, y) = clamp_hotspot(hotspot, image.image.dimensions());
+    (x.min(u16::MAX as u32) as u16, y.min(u16::MAX as u32) as u16)
+}
 
-    xcursor_writer::to_x11(&frames)
+// Moves the first image of each overridden size onto the override and shifts the
+// remaining images of that size by the same offset, so per-image differences survive.
+// Sizes a frame lists in `skip_sizes` are left as they are.
+fn apply_hotspot_overrides(
+    frames: &mut [CursorFrame],
+    overrides: &HashMap<u32, (u32, u32)>,
+    skip_sizes: Option<&[HashSet<u32>]>,
+) {
+    let mut offsets: HashMap<u32, (i64, i64)> = HashMap::new();
+
+    for (index, frame) in frames.iter_mut().enumerate() {
+        for image in &mut frame.images {
+            if skip_sizes.is_some_and(|skip| skip[index].contains(&image.nominal_size)) {
+                continue;
+            }
+            let Some(&(ox, oy)) = overrides.get(&image.nominal_size) else {
+                continue;
+            };
+
+            let (dx, dy) = *offsets.entry(image.nominal_size).or_insert((
+                ox as i64 - image.hotspot.0 as i64,
+                oy as i64 - image.hotspot.1 as i64,
+            ));
+
+            let moved = (
+                (image.hotspot.0 as i64 + dx).max(0) as u32,
+                (image.hotspot.1 as i64 + dy).max(0) as u32,
+            );
+            image.hotspot = clamp_to_image(moved, image);
+        }
+    }
 }
 
 pub fn convert_windows_cursor<F>(
@@ -139,7 +350,7 @@ pub fn convert_windows_cursor<F>(
     mut log_fn: F,
 ) -> Result<()>
 where
-    F: FnMut(String),
+    F: FnMut(LogLevel, String),
 {
     use super::{AniParser, CurParser, CursorFormat};
 
@@ -148,15 +359,73 @@ where
     let format = CursorFormat::detect(&data)
         .ok_or_else(|| anyhow::anyhow!("Unsupported cursor format: {}", input_path.display()))?;
 
-    let frames = match format {
-        CursorFormat::Cur => CurParser::parse(&data, &mut log_fn)?,
-        CursorFormat::Ani => AniParser::parse(&data, &mut log_fn)?,
+    let mut frames = match format {
+        CursorFormat::Cur => CurParser::parse(&data, options.max_dimension, &mut log_fn)?,
+        CursorFormat::Ani => {
+            let (frames, metadata) =
+                AniParser::parse_with_metadata(&data, options.max_dimension, &mut log_fn)?;
+            if let Some(name) = &metadata.name {
+                let artist = metadata.artist.as_deref().unwrap_or("unknown artist");
+                log_fn(
+                    LogLevel::Detail,
+                    format!(
+                        "{}: embedded name \"{}\" by {}",
+                        input_path.display(),
+                        name,
+                        artist
+                    ),
+                );
+            }
+            frames
+        }
     };
 
-    let x11_data = convert_to_x11(frames, options)?;
+    let name = input_path.file_stem().unwrap_or_default().to_string_lossy();
+    if is_arrow_like(&name) && place_missing_tips(&mut frames) {
+        log_fn(
+            LogLevel::Detail,
+            format!(
+                "{}: no hotspot set, placed it on the arrow tip",
+                input_path.display()
+            ),
+        );
+    }
+
+    let x11_data = convert_to_x11(frames, options, &mut log_fn)?;
 
-    std::fs::write(output_path, x11_data)?;
+    // Verified before the rename, so a bad write never replaces the previous output
+    let written = write_beside(output_path, &x11_data)?;
+    if options.verify {
+        verify_written_cursor(input_path, written.path(), options.max_dimension)?;
+    }
+    written.persist(output_path)?;
+
+    Ok(())
+}
+
+// Writes to a temporary file beside `path`. Persisting renames it over `path`, so a symlink
+// there (e.g. a cursor linked to an identical one) is replaced rather than followed into
+// the file it shares.
+fn write_beside(path: &Path, data: &[u8]) -> Result<tempfile::NamedTempFile> {
+    use std::io::Write;
 
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    file.write_all(data)?;
+    file.flush()?;
+    Ok(file)
+}
+
+// Parses the file as written, so writer bugs surface here with the source file named
+fn verify_written_cursor(input_path: &Path, written_path: &Path, max_dimension: u32) -> Result<()> {
+    let written = XcursorFile::from_file(written_path, max_dimension)
+        .with_context(|| format!("Xcursor written for {} is unreadable", input_path.display()))?;
+    if written.images.is_empty() {
+        bail!("Xcursor written for {} has no images", input_path.display());
+    }
     Ok(())
 }
 
@@ -166,7 +435,7 @@ pub fn batch_convert<F>(
     mut log_fn: F,
 ) -> Result<Vec<Result<()>>>
 where
-    F: FnMut(String),
+    F: FnMut(LogLevel, String),
 {
     let results: Vec<Result<()>> = files
         .iter()
@@ -176,10 +445,24 @@ where
     Ok(results)
 }
 
+/// `batch_convert_parallel_with_log` for callers without a log sink; messages go to stderr
 pub fn batch_convert_parallel(
     files: Vec<(std::path::PathBuf, std::path::PathBuf)>,
     options: ConversionOptions,
 ) -> Vec<Result<()>> {
+    batch_convert_parallel_with_log(files, options, |_, msg| eprintln!("{}", msg))
+}
+
+/// Converts `files` on one thread per CPU, each thread logging through its own clone of
+/// `log_fn` (e.g. a closure around a channel sender)
+pub fn batch_convert_parallel_with_log<F>(
+    files: Vec<(std::path::PathBuf, std::path::PathBuf)>,
+    options: ConversionOptions,
+    log_fn: F,
+) -> Vec<Result<()>>
+where
+    F: Fn(LogLevel, String) + Send + Clone + 'static,
+{
     use std::sync::Arc;
     use std::thread;
 
@@ -191,16 +474,12 @@ pub fn batch_convert_parallel(
     for chunk in files.chunks(chunk_size) {
         let chunk = chunk.to_vec();
         let options = Arc::clone(&options);
+        let log_fn = log_fn.clone();
 
         let handle = thread::spawn(move || {
             chunk
                 .iter()
-                .map(|(input, output)| {
-                    convert_windows_cursor(input, output, &options, |msg| {
-                        // For parallel execution, we fall back to eprintln since cross-thread logging is complex
-                        eprintln!("{}", msg);
-                    })
-                })
+                .map(|(input, output)| convert_windows_cursor(input, output, &options, &log_fn))
                 .collect::<Vec<_>>()
         });
 
@@ -235,6 +514,280 @@ mod tests {
         assert!(opts.shadow.is_some());
     }
 
+    fn frame_with_sizes(sizes: &[u32]) -> CursorFrame {
+        CursorFrame {
+            images: sizes
+                .iter()
+                .map(|&size| CursorImage {
+                    image: image::RgbaImage::new(size, size),
+                    hotspot: (size as u16 / 2, size as u16 / 2),
+                    nominal_size: size,
+                })
+                .collect(),
+            delay: 0,
+        }
+    }
+
+    #[test]
+    fn test_replace_sizes_keeps_only_targets() {
+        let mut frames = vec![frame_with_sizes(&[32, 64, 128])];
+        let mut marked = image::RgbaImage::new(64, 64);
+        marked.put_pixel(3, 3, image::Rgba([1, 2, 3, 4]));
+        frames[0].images[1].image = marked.clone();
+
+        let options = ConversionOptions::new()
+            .with_target_sizes(vec![24, 48, 64])
+            .with_replace_sizes(true);
+        resize_to_target_sizes(&mut frames, &options);
+
+        let sizes: Vec<u32> = frames[0].images.iter().map(|i| i.nominal_size).collect();
+        assert_eq!(sizes, vec![24, 48, 64]);
+
+        // An exact match passes through untouched
+        assert_eq!(frames[0].images[2].image, marked);
+        assert_eq!(frames[0].images[1].hotspot, (24, 24));
+    }
+
+    #[test]
+    fn test_target_sizes_without_replace_keeps_sources() {
+        let mut frames = vec![frame_with_sizes(&[32])];
+
+        let options = ConversionOptions::new().with_target_sizes(vec![24, 32]);
+        resize_to_target_sizes(&mut frames, &options);
+
+        let sizes: Vec<u32> = frames[0].images.iter().map(|i| i.nominal_size).collect();
+        assert_eq!(sizes, vec![32, 24]);
+    }
+
+    #[test]
+    fn test_target_sizes_resize_from_nearest_larger_image() {
+        let mut frames = vec![frame_with_sizes(&[16, 32, 64])];
+        for (image, shade) in frames[0].images.iter_mut().zip([10u8, 20, 30]) {
+            let size = image.nominal_size;
+            image.image = image::RgbaImage::from_pixel(size, size, image::Rgba([shade; 4]));
+        }
+        frames[0].images[1].hotspot = (8, 4);
+        frames[0].images[2].hotspot = (40, 20);
+
+        let options = ConversionOptions::new().with_target_sizes(vec![24, 48]);
+        resize_to_target_sizes(&mut frames, &options);
+
+        let sizes: Vec<u32> = frames[0].images.iter().map(|i| i.nominal_size).collect();
+        assert_eq!(sizes, vec![16, 32, 64, 24, 48]);
+
+        let from_32 = &frames[0].images[3];
+        assert_eq!(from_32.image.get_pixel(12, 12), &image::Rgba([20; 4]));
+        assert_eq!(from_32.hotspot, (6, 3));
+
+        let from_64 = &frames[0].images[4];
+        assert_eq!(from_64.image.get_pixel(24, 24), &image::Rgba([30; 4]));
+        assert_eq!(from_64.hotspot, (30, 15));
+    }
+
+    #[test]
+    fn test_guaranteed_sizes_survive_replace_sizes() {
+        let frames = vec![frame_with_sizes(&[64])];
+        let options = ConversionOptions::new()
+            .with_target_sizes(vec![48])
+            .with_replace_sizes(true)
+            .with_guaranteed_sizes(vec![24, 32, 48]);
+
+        let data = convert_to_x11(frames, &options, |_, _| {}).unwrap();
+        let xcursor =
+            crate::pipeline::xcur2png::XcursorFile::from_bytes(&data, DEFAULT_MAX_DIMENSION)
+                .unwrap();
+
+        assert_eq!(xcursor.get_sizes(), vec![24, 32, 48]);
+        let image_24 = xcursor.get_images_for_size(24)[0];
+        assert_eq!((image_24.width, image_24.height), (24, 24));
+        assert_eq!((image_24.xhot, image_24.yhot), (12, 12));
+    }
+
+    #[test]
+    fn test_hotspot_overrides_use_the_pixels_of_their_size() {
+        let frames = vec![frame_with_sizes(&[32])];
+        let mut options = ConversionOptions::new()
+            .with_scale(2.0)
+            .with_target_sizes(vec![24])
+            .with_hotspot_override(32, 10, 6)
+            .with_hotspot_override(24, 40, 3);
+        options.resize_filter = FilterType::Nearest;
+
+        let data = convert_to_x11(frames, &options, |_, _| {}).unwrap();
+        let xcursor =
+            crate::pipeline::xcur2png::XcursorFile::from_bytes(&data, DEFAULT_MAX_DIMENSION)
+                .unwrap();
+
+        // Set on the 32px source, then scaled along with it
+        let scaled = xcursor.get_images_for_size(64)[0];
+        assert_eq!((scaled.xhot, scaled.yhot), (20, 12));
+        // Set on the resized image itself, clamped like the editor does
+        let resized = xcursor.get_images_for_size(24)[0];
+        assert_eq!((resized.xhot, resized.yhot), (23, 3));
+    }
+
+    #[test]
+    fn test_trim_transparent_crops_to_content() {
+        let mut frames = vec![frame_with_sizes(&[32, 16])];
+        for y in 10..18 {
+            for x in 4..12 {
+                frames[0].images[0]
+                    .image
+                    .put_pixel(x, y, image::Rgba([0, 0, 0, 255]));
+            }
+        }
+        frames[0].images[0].hotspot = (6, 12);
+
+        let options = ConversionOptions::new().with_trim_transparent(true);
+        let data = convert_to_x11(frames, &options, |_, _| {}).unwrap();
+        let xcursor =
+            crate::pipeline::xcur2png::XcursorFile::from_bytes(&data, DEFAULT_MAX_DIMENSION)
+                .unwrap();
+
+        let trimmed = xcursor.get_images_for_size(32)[0];
+        assert_eq!((trimmed.width, trimmed.height), (8, 8));
+        assert_eq!((trimmed.xhot, trimmed.yhot), (2, 2));
+        assert_eq!(trimmed.pixels.get_pixel(0, 0)[3], 255);
+
+        // Nothing to crop to, so the empty image keeps its size and hotspot
+        let empty = xcursor.get_images_for_size(16)[0];
+        assert_eq!((empty.width, empty.height), (16, 16));
+        assert_eq!((empty.xhot, empty.yhot), (8, 8));
+    }
+
+    #[test]
+    fn test_out_of_range_hotspot_is_clamped() {
+        let mut frames = vec![frame_with_sizes(&[32]), frame_with_sizes(&[32])];
+        frames[0].images[0].hotspot = (40, 40);
+        frames[1].images[0].hotspot = (40, 40);
+
+        let mut warnings = Vec::new();
+        clamp_hotspots(&mut frames, &mut |level, msg| warnings.push((level, msg)));
+
+        assert_eq!(frames[0].images[0].hotspot, (31, 31));
+        assert_eq!(frames[1].images[0].hotspot, (31, 31));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].0, LogLevel::Warn);
+        assert!(warnings[0].1.starts_with("Warning: hotspot (40, 40)"));
+    }
+
+    #[test]
+    fn test_hotspot_override_keeps_per_image_offsets() {
+        let mut frames = vec![frame_with_sizes(&[32]), frame_with_sizes(&[32])];
+        frames[1].images[0].hotspot = (18, 14);
+
+        let mut overrides = HashMap::new();
+        overrides.insert(32, (10, 10));
+        apply_hotspot_overrides(&mut frames, &overrides, None);
+
+        assert_eq!(frames[0].images[0].hotspot, (10, 10));
+        assert_eq!(frames[1].images[0].hotspot, (12, 8));
+    }
+
+    // Single-image .cur with an embedded PNG
+    fn png_cur_bytes(size: u8, hotspot: (u16, u16)) -> Vec<u8> {
+        let mut png = Vec::new();
+        image::RgbaImage::new(size as u32, size as u32)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let mut data = vec![0, 0, 2, 0, 1, 0];
+        data.extend_from_slice(&[size, size, 0, 0]);
+        data.extend_from_slice(&hotspot.0.to_le_bytes());
+        data.extend_from_slice(&hotspot.1.to_le_bytes());
+        data.extend_from_slice(&(png.len() as u32).to_le_bytes());
+        data.extend_from_slice(&22u32.to_le_bytes());
+        data.extend_from_slice(&png);
+        data
+    }
+
+    #[test]
+    fn test_batch_convert_parallel_forwards_logs() {
+        let temp = tempfile::tempdir().unwrap();
+        let files: Vec<_> = (0..3)
+            .map(|i| {
+                let input = temp.path().join(format!("bad_{}.cur", i));
+                std::fs::write(&input, png_cur_bytes(8, (40, 40))).unwrap();
+                (input, temp.path().join(format!("bad_{}", i)))
+            })
+            .collect();
+
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let results =
+            batch_convert_parallel_with_log(files, ConversionOptions::new(), move |level, msg| {
+                let _ = tx.send((level, msg));
+            });
+
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|r| r.is_ok()));
+        let clamp_warnings = rx
+            .try_iter()
+            .filter(|(level, _)| *level == LogLevel::Warn)
+            .count();
+        assert_eq!(clamp_warnings, 3);
+    }
+
+    #[test]
+    fn test_verify_rejects_unreadable_output() {
+        let temp = tempfile::tempdir().unwrap();
+        let input = temp.path().join("arrow.cur");
+        let output = temp.path().join("arrow");
+        std::fs::write(&input, png_cur_bytes(16, (2, 2))).unwrap();
+        std::fs::write(&output, b"previous").unwrap();
+
+        let options = ConversionOptions::new().with_verify(true);
+        convert_windows_cursor(&input, &output, &options, |_, _| {}).unwrap();
+
+        // Scaling past the size limit writes a cursor the reader refuses
+        let options = options.with_max_dimension(32).with_scale(4.0);
+        std::fs::write(&output, b"previous").unwrap();
+        let err = convert_windows_cursor(&input, &output, &options, |_, _| {}).unwrap_err();
+        assert!(format!("{:#}", err).contains("arrow.cur"), "{err:#}");
+        assert_eq!(std::fs::read(&output).unwrap(), b"previous");
+
+        // Without verify the same conversion goes through
+        let options = options.with_verify(false);
+        convert_windows_cursor(&input, &output, &options, |_, _| {}).unwrap();
+        assert_ne!(std::fs::read(&output).unwrap(), b"previous");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_verify_reads_the_written_file_not_a_symlink_target() {
+        let temp = tempfile::tempdir().unwrap();
+        let input = temp.path().join("arrow.cur");
+        let shared = temp.path().join("left_ptr");
+        let output = temp.path().join("arrow");
+        std::fs::write(&input, png_cur_bytes(16, (2, 2))).unwrap();
+        // A linked cursor whose target is not a readable Xcursor
+        std::fs::write(&shared, b"not an xcursor").unwrap();
+        std::os::unix::fs::symlink("left_ptr", &output).unwrap();
+
+        let options = ConversionOptions::new().with_verify(true);
+        convert_windows_cursor(&input, &output, &options, |_, _| {}).unwrap();
+
+        assert!(!output.symlink_metadata().unwrap().is_symlink());
+        assert_eq!(std::fs::read(&shared).unwrap(), b"not an xcursor");
+    }
+
+    #[test]
+    fn test_max_dimension_comes_from_the_options() {
+        let temp = tempfile::tempdir().unwrap();
+        let input = temp.path().join("big.cur");
+        let output = temp.path().join("big");
+        std::fs::write(&input, png_cur_bytes(48, (2, 2))).unwrap();
+
+        let strict = ConversionOptions::new().with_max_dimension(32);
+        let err = convert_windows_cursor(&input, &output, &strict, |_, _| {}).unwrap_err();
+        assert!(err.to_string().contains("32px limit"), "{err}");
+        assert!(!output.exists());
+
+        // Another conversion in the same process keeps its own limit
+        let options = ConversionOptions::new().with_max_dimension(0);
+        assert_eq!(options.max_dimension, DEFAULT_MAX_DIMENSION);
+        convert_windows_cursor(&input, &output, &options, |_, _| {}).unwrap();
+    }
+
     #[test]
     fn test_num_cpus() {
         let cpus = num_cpus();
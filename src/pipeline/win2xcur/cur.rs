@@ -3,8 +3,14 @@ use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use image::RgbaImage;
 use std::io::{Cursor, Write};
 
+use crate::pipeline::limits::check_dimensions;
+use crate::pipeline::log::LogLevel;
+
 const ICO_TYPE_CUR: u16 = 2;
 const MAGIC: &[u8] = &[0x00, 0x00, 0x02, 0x00];
+const BITMAPINFOHEADER_SIZE: u32 = 40;
+const BI_BITFIELDS: u32 = 3;
+const BI_ALPHABITFIELDS: u32 = 6;
 
 #[derive(Debug, Clone)]
 pub struct CursorImage {
@@ -36,7 +42,7 @@ struct IconDirEntry {
 impl IconDirEntry {
     fn validate<F>(&self, mut log_fn: F) -> Result<()>
     where
-        F: FnMut(String),
+        F: FnMut(LogLevel, String),
     {
         if self.reserved != 0 {
             bail!(
@@ -47,10 +53,13 @@ impl IconDirEntry {
 
         // Log a warning if color count seems suspicious
         if self.color_count != 0 && self.color_count < 2 {
-            log_fn(format!(
-                "Warning: Suspicious color count {} in cursor entry",
-                self.color_count
-            ));
+            log_fn(
+                LogLevel::Warn,
+                format!(
+                    "Warning: Suspicious color count {} in cursor entry",
+                    self.color_count
+                ),
+            );
         }
         Ok(())
     }
@@ -61,9 +70,9 @@ impl CurParser {
         data.len() >= 4 && &data[0..4] == MAGIC
     }
 
-    pub fn parse<F>(data: &[u8], mut log_fn: F) -> Result<Vec<CursorFrame>>
+    pub fn parse<F>(data: &[u8], max_dimension: u32, mut log_fn: F) -> Result<Vec<CursorFrame>>
     where
-        F: FnMut(String),
+        F: FnMut(LogLevel, String),
     {
         if !Self::can_parse(data) {
             bail!("Not a valid .CUR file");
@@ -93,7 +102,7 @@ impl CurParser {
 
         let mut cursor_images = Vec::new();
         for entry in entries {
-            let image = Self::parse_image(data, &entry)?;
+            let image = Self::parse_image(data, &entry, max_dimension)?;
             cursor_images.push(image);
         }
 
@@ -116,7 +125,7 @@ impl CurParser {
         })
     }
 
-    fn parse_image(data: &[u8], entry: &IconDirEntry) -> Result<CursorImage> {
+    fn parse_image(data: &[u8], entry: &IconDirEntry, max_dimension: u32) -> Result<CursorImage> {
         let offset = entry.offset as usize;
         let size = entry.size_bytes as usize;
 
@@ -127,12 +136,22 @@ impl CurParser {
         let image_data = &data[offset..offset + size];
 
         let (img, is_bmp) = if image_data.len() >= 8 && &image_data[0..8] == b"\x89PNG\r\n\x1a\n" {
+            // IHDR always comes first: width and height sit right after its type
+            if image_data.len() >= 24 {
+                let width = u32::from_be_bytes(image_data[16..20].try_into()?);
+                let height = u32::from_be_bytes(image_data[20..24].try_into()?);
+                check_dimensions(width, height, max_dimension, "PNG cursor image")?;
+            }
             (
                 image::load_from_memory_with_format(image_data, image::ImageFormat::Png)
                     .context("Failed to decode PNG cursor image")?,
                 false,
             )
         } else {
+            if image_data.len() >= 12 {
+                let (width, height) = dib_dimensions(image_data);
+                check_dimensions(width, height, max_dimension, "DIB cursor image")?;
+            }
             let bmp_data = create_bmp_from_dib(image_data)?;
             (
                 image::load_from_memory_with_format(&bmp_data, image::ImageFormat::Bmp)
@@ -144,7 +163,7 @@ impl CurParser {
         let mut rgba = img.to_rgba8();
 
         if is_bmp {
-            apply_and_mask(&mut rgba, image_data)?;
+            apply_and_mask(&mut rgba, image_data, max_dimension)?;
         }
 
         let _width = rgba.width();
@@ -171,21 +190,18 @@ impl CurParser {
     }
 }
 
-fn apply_and_mask(image: &mut RgbaImage, dib_data: &[u8]) -> Result<()> {
+fn apply_and_mask(image: &mut RgbaImage, dib_data: &[u8], max_dimension: u32) -> Result<()> {
     if dib_data.len() < 40 {
         return Ok(());
     }
 
     let header_size =
         u32::from_le_bytes([dib_data[0], dib_data[1], dib_data[2], dib_data[3]]) as usize;
-    let width =
-        i32::from_le_bytes([dib_data[4], dib_data[5], dib_data[6], dib_data[7]]).unsigned_abs();
-    let height = i32::from_le_bytes([dib_data[8], dib_data[9], dib_data[10], dib_data[11]])
-        .unsigned_abs()
-        / 2;
+    let (width, height) = dib_dimensions(dib_data);
+    check_dimensions(width, height, max_dimension, "DIB AND mask")?;
     let bits_per_pixel = u16::from_le_bytes([dib_data[14], dib_data[15]]);
 
-    let palette_size = calculate_palette_size(dib_data)? as usize;
+    let palette_size = (calculate_palette_size(dib_data)? + bitfield_masks_size(dib_data)) as usize;
 
     let xor_row_size = (width * bits_per_pixel as u32).div_ceil(32) * 4;
     let xor_size = xor_row_size * height;
@@ -230,6 +246,16 @@ fn apply_and_mask(image: &mut RgbaImage, dib_data: &[u8]) -> Result<()> {
     Ok(())
 }
 
+// Width and real height of a cursor DIB, whose header height covers both XOR and AND masks
+fn dib_dimensions(dib_data: &[u8]) -> (u32, u32) {
+    let width =
+        i32::from_le_bytes([dib_data[4], dib_data[5], dib_data[6], dib_data[7]]).unsigned_abs();
+    let height = i32::from_le_bytes([dib_data[8], dib_data[9], dib_data[10], dib_data[11]])
+        .unsigned_abs()
+        / 2;
+    (width, height)
+}
+
 /// Create a complete BMP file from DIB data
 fn create_bmp_from_dib(dib_data: &[u8]) -> Result<Vec<u8>> {
     if dib_data.len() < 40 {
@@ -249,8 +275,9 @@ fn create_bmp_from_dib(dib_data: &[u8]) -> Result<Vec<u8>> {
     modified_dib[10] = actual_height_bytes[2];
     modified_dib[11] = actual_height_bytes[3];
 
-    // Calculate how much data we need (only the XOR mask)
-    let palette_size = calculate_palette_size(&modified_dib)?;
+    // Calculate how much data we need (only the XOR mask). BITFIELDS masks sit between the
+    // header and the pixels, so they count like palette bytes.
+    let palette_size = calculate_palette_size(&modified_dib)? + bitfield_masks_size(&modified_dib);
     let bits_per_pixel = u16::from_le_bytes([dib_data[14], dib_data[15]]);
 
     let row_size = (width.unsigned_abs() * bits_per_pixel as u32).div_ceil(32) * 4;
@@ -279,6 +306,22 @@ fn create_bmp_from_dib(dib_data: &[u8]) -> Result<Vec<u8>> {
     Ok(bmp_data)
 }
 
+// Size of the color masks that follow a plain BITMAPINFOHEADER in BITFIELDS DIBs; larger
+// headers (V4/V5) carry the masks inside the header itself
+fn bitfield_masks_size(dib_data: &[u8]) -> u32 {
+    if dib_data.len() < 20 {
+        return 0;
+    }
+    let header_size = u32::from_le_bytes([dib_data[0], dib_data[1], dib_data[2], dib_data[3]]);
+    let compression = u32::from_le_bytes([dib_data[16], dib_data[17], dib_data[18], dib_data[19]]);
+
+    match (header_size, compression) {
+        (BITMAPINFOHEADER_SIZE, BI_BITFIELDS) => 12,
+        (BITMAPINFOHEADER_SIZE, BI_ALPHABITFIELDS) => 16,
+        _ => 0,
+    }
+}
+
 fn calculate_palette_size(dib_data: &[u8]) -> Result<u32> {
     if dib_data.len() < 40 {
         return Ok(0);
@@ -305,6 +348,7 @@ fn calculate_palette_size(dib_data: &[u8]) -> Result<u32> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pipeline::limits::DEFAULT_MAX_DIMENSION;
 
     #[test]
     fn test_magic_detection() {
@@ -314,4 +358,57 @@ mod tests {
         let invalid = vec![0x00, 0x00, 0x01, 0x00];
         assert!(!CurParser::can_parse(&invalid));
     }
+
+    #[test]
+    fn test_bitfields_dib_uses_its_masks() {
+        // 2x2, 32bpp, BI_BITFIELDS with the channels in R, G, B byte order
+        let mut dib = vec![0u8; 40];
+        dib[0..4].copy_from_slice(&40u32.to_le_bytes());
+        dib[4..8].copy_from_slice(&2i32.to_le_bytes());
+        dib[8..12].copy_from_slice(&4i32.to_le_bytes()); // XOR + AND height
+        dib[12..14].copy_from_slice(&1u16.to_le_bytes());
+        dib[14..16].copy_from_slice(&32u16.to_le_bytes());
+        dib[16..20].copy_from_slice(&BI_BITFIELDS.to_le_bytes());
+        for mask in [0x0000_00FFu32, 0x0000_FF00, 0x00FF_0000] {
+            dib.extend_from_slice(&mask.to_le_bytes());
+        }
+        for _ in 0..4 {
+            dib.extend_from_slice(&[10, 20, 30, 0]);
+        }
+        // AND mask: top-left pixel transparent (bottom-up rows, 4 bytes each)
+        dib.extend_from_slice(&[0, 0, 0, 0]);
+        dib.extend_from_slice(&[0x80, 0, 0, 0]);
+
+        let mut data = vec![0, 0, 2, 0, 1, 0, 2, 2, 0, 0, 1, 0, 1, 0];
+        data.extend_from_slice(&(dib.len() as u32).to_le_bytes());
+        data.extend_from_slice(&22u32.to_le_bytes());
+        data.extend_from_slice(&dib);
+
+        let frames = CurParser::parse(&data, DEFAULT_MAX_DIMENSION, |_, _| {}).unwrap();
+        let image = &frames[0].images[0].image;
+        assert_eq!(image.dimensions(), (2, 2));
+        let pixel = image.get_pixel(1, 1);
+        assert_eq!(&pixel.0[..3], &[10, 20, 30]);
+        assert_eq!(pixel[3], 255);
+        assert_eq!(image.get_pixel(0, 0)[3], 0);
+    }
+
+    #[test]
+    fn test_oversized_dib_is_rejected() {
+        let mut dib = vec![0u8; 40];
+        dib[0..4].copy_from_slice(&40u32.to_le_bytes());
+        dib[4..8].copy_from_slice(&100_000i32.to_le_bytes());
+        dib[8..12].copy_from_slice(&64i32.to_le_bytes());
+        dib[14..16].copy_from_slice(&32u16.to_le_bytes());
+
+        let mut data = vec![0, 0, 2, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        data.extend_from_slice(&(dib.len() as u32).to_le_bytes());
+        data.extend_from_slice(&22u32.to_le_bytes());
+        data.extend_from_slice(&dib);
+
+        let err = CurParser::parse(&data, DEFAULT_MAX_DIMENSION, |_, _| {})
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("100000x32"), "{err}");
+    }
 }
@@ -4,7 +4,7 @@ pub mod cur;
 pub mod utils;
 pub mod xcursor_writer;
 
-pub use ani::AniParser;
+pub use ani::{AniParser, AniSequence};
 pub use converter::ConversionOptions;
 pub use cur::CurParser;
 
@@ -41,16 +41,16 @@ pub fn parse_and_convert(path: &Path, options: &ConversionOptions) -> Result<Vec
 
     match format {
         CursorFormat::Cur => {
-            let cursor = CurParser::parse(&data, |msg| {
+            let cursor = CurParser::parse(&data, options.max_dimension, |_, msg| {
                 eprintln!("{}", msg);
             })?;
-            converter::convert_to_x11(cursor, options)
+            converter::convert_to_x11(cursor, options, |_, msg| eprintln!("{}", msg))
         }
         CursorFormat::Ani => {
-            let cursor = AniParser::parse(&data, |msg| {
+            let cursor = AniParser::parse(&data, options.max_dimension, |_, msg| {
                 eprintln!("{}", msg);
             })?;
-            converter::convert_to_x11(cursor, options)
+            converter::convert_to_x11(cursor, options, |_, msg| eprintln!("{}", msg))
         }
     }
 }
@@ -1,10 +1,12 @@
 use anyhow::Result;
+use image::imageops::FilterType;
 use image::{ImageBuffer, Rgba, RgbaImage};
 use imageproc::filter::gaussian_blur_f32;
+use serde::{Deserialize, Serialize};
 
 use super::cur::CursorFrame;
 
-pub fn scale_frames(frames: &mut [CursorFrame], scale: f32) {
+pub fn scale_frames(frames: &mut [CursorFrame], scale: f32, filter: FilterType) {
     for frame in frames {
         for cursor in &mut frame.images {
             let width = cursor.image.width();
@@ -13,12 +15,7 @@ pub fn scale_frames(frames: &mut [CursorFrame], scale: f32) {
             let new_width = (width as f32 * scale).round() as u32;
             let new_height = (height as f32 * scale).round() as u32;
 
-            let scaled = image::imageops::resize(
-                &cursor.image,
-                new_width,
-                new_height,
-                image::imageops::FilterType::Lanczos3,
-            );
+            let scaled = image::imageops::resize(&cursor.image, new_width, new_height, filter);
 
             cursor.image = scaled;
             cursor.nominal_size = new_width.max(new_height);
@@ -29,7 +26,21 @@ pub fn scale_frames(frames: &mut [CursorFrame], scale: f32) {
     }
 }
 
-#[derive(Debug, Clone)]
+/// Invert RGB channels while keeping alpha, turning a light cursor into a dark one
+pub fn invert_colors(frames: &mut [CursorFrame]) {
+    for frame in frames {
+        for cursor in &mut frame.images {
+            for pixel in cursor.image.pixels_mut() {
+                pixel[0] = 255 - pixel[0];
+                pixel[1] = 255 - pixel[1];
+                pixel[2] = 255 - pixel[2];
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ShadowConfig {
     pub color: [u8; 3],
     pub radius: f32,
@@ -52,6 +63,24 @@ impl Default for ShadowConfig {
     }
 }
 
+impl ShadowConfig {
+    // Offsets and sigma are fractions of the image size; past these the canvas balloons and
+    // the blur gets very slow on large cursors. The shadow only grows the canvas right and
+    // down, so offsets can't go negative.
+    pub const OFFSET_RANGE: (f32, f32) = (0.0, 0.2);
+    pub const SIGMA_RANGE: (f32, f32) = (0.01, 0.25);
+
+    /// Pulls offsets and sigma back into their supported ranges
+    pub fn clamped(mut self) -> Self {
+        let (min_offset, max_offset) = Self::OFFSET_RANGE;
+        let (min_sigma, max_sigma) = Self::SIGMA_RANGE;
+        self.x_offset = self.x_offset.clamp(min_offset, max_offset);
+        self.y_offset = self.y_offset.clamp(min_offset, max_offset);
+        self.sigma = self.sigma.clamp(min_sigma, max_sigma);
+        self
+    }
+}
+
 pub fn apply_shadows(frames: &mut [CursorFrame], config: &ShadowConfig) -> Result<()> {
     for frame in frames {
         for cursor in &mut frame.images {
@@ -62,12 +91,14 @@ pub fn apply_shadows(frames: &mut [CursorFrame], config: &ShadowConfig) -> Resul
     Ok(())
 }
 
-fn apply_shadow_to_image(image: &RgbaImage, config: &ShadowConfig) -> Result<RgbaImage> {
+/// Draws `image` over its blurred shadow, cropped to the visible result
+pub fn apply_shadow_to_image(image: &RgbaImage, config: &ShadowConfig) -> Result<RgbaImage> {
     let width = image.width();
     let height = image.height();
 
-    let x_offset = (config.x_offset * width as f32).round() as i32;
-    let y_offset = (config.y_offset * height as f32).round() as i32;
+    // A negative offset would place the mask outside the canvas, whatever the config says
+    let x_offset = (config.x_offset.max(0.0) * width as f32).round() as i32;
+    let y_offset = (config.y_offset.max(0.0) * height as f32).round() as i32;
 
     let new_width = width + (3 * x_offset.unsigned_abs());
     let new_height = height + (3 * y_offset.unsigned_abs());
@@ -158,29 +189,53 @@ fn blend_over(src: Rgba<u8>, dst: Rgba<u8>) -> Rgba<u8> {
     Rgba([r, g, b, a])
 }
 
-fn trim_to_content(image: &RgbaImage, min_width: u32, min_height: u32) -> RgbaImage {
-    let (width, height) = (image.width(), image.height());
-
-    let mut min_x = width;
-    let mut max_x = 0;
-    let mut min_y = height;
-    let mut max_y = 0;
+/// Crops every image to its non-transparent pixels, moving the hotspot by the crop offset.
+/// The crop always keeps the hotspot pixel, and fully transparent images are left as they are.
+pub fn trim_transparent(frames: &mut [CursorFrame]) {
+    for cursor in frames.iter_mut().flat_map(|f| f.images.iter_mut()) {
+        let Some((min_x, min_y, max_x, max_y)) = content_bounds(&cursor.image) else {
+            continue;
+        };
+        let (hx, hy) = (cursor.hotspot.0 as u32, cursor.hotspot.1 as u32);
+        let (min_x, min_y) = (min_x.min(hx), min_y.min(hy));
+        let max_x = max_x.max(hx).min(cursor.image.width() - 1);
+        let max_y = max_y.max(hy).min(cursor.image.height() - 1);
+
+        cursor.image = image::imageops::crop_imm(
+            &cursor.image,
+            min_x,
+            min_y,
+            max_x - min_x + 1,
+            max_y - min_y + 1,
+        )
+        .to_image();
+        cursor.hotspot = (
+            cursor.hotspot.0 - min_x as u16,
+            cursor.hotspot.1 - min_y as u16,
+        );
+    }
+}
 
-    for y in 0..height {
-        for x in 0..width {
-            let pixel = image.get_pixel(x, y);
-            if pixel[3] > 0 {
-                min_x = min_x.min(x);
-                max_x = max_x.max(x);
-                min_y = min_y.min(y);
-                max_y = max_y.max(y);
-            }
+// (min_x, min_y, max_x, max_y) of the pixels with any alpha, or None for an empty image
+fn content_bounds(image: &RgbaImage) -> Option<(u32, u32, u32, u32)> {
+    let mut bounds: Option<(u32, u32, u32, u32)> = None;
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if pixel[3] > 0 {
+            bounds = Some(match bounds {
+                Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+                None => (x, y, x, y),
+            });
         }
     }
+    bounds
+}
+
+fn trim_to_content(image: &RgbaImage, min_width: u32, min_height: u32) -> RgbaImage {
+    let (width, height) = (image.width(), image.height());
 
-    if min_x > max_x {
+    let Some((min_x, min_y, max_x, max_y)) = content_bounds(image) else {
         return RgbaImage::new(min_width, min_height);
-    }
+    };
 
     let content_width = (max_x - min_x + 1).max(min_width);
     let content_height = (max_y - min_y + 1).max(min_height);
@@ -204,6 +259,34 @@ fn trim_to_content(image: &RgbaImage, min_width: u32, min_height: u32) -> RgbaIm
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_negative_shadow_offset_is_ignored() {
+        let image = RgbaImage::from_pixel(8, 8, Rgba([255, 255, 255, 255]));
+        let config = ShadowConfig {
+            x_offset: -0.2,
+            y_offset: -0.2,
+            ..ShadowConfig::default()
+        };
+        let shadowed = apply_shadow_to_image(&image, &config).unwrap();
+        assert_eq!(shadowed.dimensions(), (8, 8));
+    }
+
+    #[test]
+    fn test_shadow_config_clamped() {
+        let config = ShadowConfig {
+            sigma: 40.0,
+            x_offset: -3.0,
+            y_offset: 0.1,
+            ..ShadowConfig::default()
+        }
+        .clamped();
+
+        assert_eq!(config.sigma, ShadowConfig::SIGMA_RANGE.1);
+        assert_eq!(config.x_offset, ShadowConfig::OFFSET_RANGE.0);
+        assert_eq!(config.y_offset, 0.1);
+        assert_eq!(ShadowConfig::default().clamped(), ShadowConfig::default());
+    }
+
     #[test]
     fn test_scale_frames() {
         let img = RgbaImage::new(32, 32);
@@ -217,13 +300,63 @@ mod tests {
             delay: 0,
         }];
 
-        scale_frames(&mut frames, 2.0);
+        scale_frames(&mut frames, 2.0, FilterType::Lanczos3);
 
         assert_eq!(frames[0].images[0].image.width(), 64);
         assert_eq!(frames[0].images[0].image.height(), 64);
         assert_eq!(frames[0].images[0].hotspot, (32, 32));
     }
 
+    #[test]
+    fn test_scale_frames_nearest_keeps_hard_edges() {
+        let img = RgbaImage::from_fn(16, 16, |x, y| {
+            if (x + y) % 2 == 0 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+        let mut frames = vec![super::super::cur::CursorFrame {
+            images: vec![super::super::cur::CursorImage {
+                image: img,
+                hotspot: (0, 0),
+                nominal_size: 16,
+            }],
+            delay: 0,
+        }];
+
+        scale_frames(&mut frames, 2.0, FilterType::Nearest);
+
+        let image = &frames[0].images[0].image;
+        assert_eq!(image.width(), 32);
+        assert!(
+            image
+                .pixels()
+                .all(|p| *p == Rgba([0, 0, 0, 255]) || *p == Rgba([255, 255, 255, 255]))
+        );
+    }
+
+    #[test]
+    fn test_invert_colors() {
+        let mut img = RgbaImage::new(2, 1);
+        img.put_pixel(0, 0, Rgba([255, 255, 255, 255]));
+        img.put_pixel(1, 0, Rgba([10, 20, 30, 0]));
+        let mut frames = vec![super::super::cur::CursorFrame {
+            images: vec![super::super::cur::CursorImage {
+                image: img,
+                hotspot: (0, 0),
+                nominal_size: 2,
+            }],
+            delay: 0,
+        }];
+
+        invert_colors(&mut frames);
+
+        let image = &frames[0].images[0].image;
+        assert_eq!(*image.get_pixel(0, 0), Rgba([0, 0, 0, 255]));
+        assert_eq!(*image.get_pixel(1, 0), Rgba([245, 235, 225, 0]));
+    }
+
     #[test]
     fn test_blend_over() {
         let src = Rgba([255, 0, 0, 128]);
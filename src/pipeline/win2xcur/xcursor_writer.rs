@@ -7,8 +7,37 @@ use super::cur::CursorFrame;
 const MAGIC: &[u8] = b"Xcur";
 const VERSION: u32 = 0x0001_0000;
 const CHUNK_IMAGE: u32 = 0xFFFD_0002;
+const CHUNK_COMMENT: u32 = 0xFFFE_0001;
+// Comment subtypes are copyright (1), license (2) and other (3)
+const COMMENT_OTHER: u32 = 3;
+const COMMENT_HEADER_SIZE: usize = 20;
+
+/// How pixel data is stored in written Xcursor files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AlphaMode {
+    /// Colors multiplied by alpha, as the Xcursor format specifies. Fully opaque pixels are
+    /// stored unchanged; translucent ones lose precision the lower their alpha.
+    #[default]
+    Premultiplied,
+    /// Colors stored as authored. Non-standard, only for checking consumers that misread
+    /// premultiplied data; spec-following readers will show translucent edges too bright.
+    Straight,
+}
 
 pub fn to_x11(frames: &[CursorFrame]) -> Result<Vec<u8>> {
+    to_x11_with_alpha(frames, AlphaMode::Premultiplied)
+}
+
+pub fn to_x11_with_alpha(frames: &[CursorFrame], alpha_mode: AlphaMode) -> Result<Vec<u8>> {
+    to_x11_with_comment(frames, alpha_mode, None)
+}
+
+/// Like `to_x11_with_alpha`, with an optional comment chunk written ahead of the images
+pub fn to_x11_with_comment(
+    frames: &[CursorFrame],
+    alpha_mode: AlphaMode,
+    comment: Option<&str>,
+) -> Result<Vec<u8>> {
     let mut output = Vec::new();
     let mut chunks = Vec::new();
 
@@ -20,7 +49,10 @@ pub fn to_x11(frames: &[CursorFrame]) -> Result<Vec<u8>> {
             let nominal = cursor.nominal_size;
             let delay = frame.delay;
 
-            let pixels = premultiply_alpha(&cursor.image);
+            let pixels = match alpha_mode {
+                AlphaMode::Premultiplied => premultiply_alpha(&cursor.image),
+                AlphaMode::Straight => straight_alpha(&cursor.image),
+            };
 
             chunks.push(ChunkData {
                 chunk_type: CHUNK_IMAGE,
@@ -38,11 +70,19 @@ pub fn to_x11(frames: &[CursorFrame]) -> Result<Vec<u8>> {
     output.write_all(MAGIC)?;
     output.write_u32::<LittleEndian>(16)?; // header size
     output.write_u32::<LittleEndian>(VERSION)?;
-    output.write_u32::<LittleEndian>(chunks.len() as u32)?;
+    let toc_count = chunks.len() + usize::from(comment.is_some());
+    output.write_u32::<LittleEndian>(toc_count as u32)?;
 
-    let toc_size = chunks.len() * 12; // Each TOC entry is 12 bytes
+    let toc_size = toc_count * 12; // Each TOC entry is 12 bytes
     let mut offset = 16 + toc_size; // After header and TOC
 
+    if let Some(comment) = comment {
+        output.write_u32::<LittleEndian>(CHUNK_COMMENT)?;
+        output.write_u32::<LittleEndian>(COMMENT_OTHER)?;
+        output.write_u32::<LittleEndian>(offset as u32)?;
+        offset += COMMENT_HEADER_SIZE + comment.len();
+    }
+
     for chunk in &chunks {
         output.write_u32::<LittleEndian>(chunk.chunk_type)?;
         output.write_u32::<LittleEndian>(chunk.nominal)?;
@@ -52,6 +92,15 @@ pub fn to_x11(frames: &[CursorFrame]) -> Result<Vec<u8>> {
         offset += 36 + image_size; // 36 byte header + image data
     }
 
+    if let Some(comment) = comment {
+        output.write_u32::<LittleEndian>(COMMENT_HEADER_SIZE as u32)?;
+        output.write_u32::<LittleEndian>(CHUNK_COMMENT)?;
+        output.write_u32::<LittleEndian>(COMMENT_OTHER)?;
+        output.write_u32::<LittleEndian>(1)?; // version
+        output.write_u32::<LittleEndian>(comment.len() as u32)?;
+        output.write_all(comment.as_bytes())?;
+    }
+
     for chunk in &chunks {
         output.write_u32::<LittleEndian>(36)?; // header size
         output.write_u32::<LittleEndian>(chunk.chunk_type)?;
@@ -92,9 +141,9 @@ fn premultiply_alpha(image: &image::RgbaImage) -> Vec<u8> {
 
         let alpha_factor = a / 255.0;
 
-        let b_pre = (b * alpha_factor) as u8;
-        let g_pre = (g * alpha_factor) as u8;
-        let r_pre = (r * alpha_factor) as u8;
+        let b_pre = (b * alpha_factor).round() as u8;
+        let g_pre = (g * alpha_factor).round() as u8;
+        let r_pre = (r * alpha_factor).round() as u8;
         let a_byte = a as u8;
 
         result.push(b_pre);
@@ -106,9 +155,17 @@ fn premultiply_alpha(image: &image::RgbaImage) -> Vec<u8> {
     result
 }
 
+fn straight_alpha(image: &image::RgbaImage) -> Vec<u8> {
+    image
+        .pixels()
+        .flat_map(|pixel| [pixel[2], pixel[1], pixel[0], pixel[3]])
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pipeline::limits::DEFAULT_MAX_DIMENSION;
     use crate::pipeline::win2xcur::cur::CursorImage;
     use image::{Rgba, RgbaImage};
 
@@ -131,6 +188,14 @@ mod tests {
         assert_eq!(result[7], 255);
     }
 
+    #[test]
+    fn test_straight_alpha_keeps_colors() {
+        let mut img = RgbaImage::new(1, 1);
+        img.put_pixel(0, 0, Rgba([200, 100, 50, 128]));
+
+        assert_eq!(straight_alpha(&img), vec![50, 100, 200, 128]);
+    }
+
     #[test]
     fn test_xcursor_format() {
         let mut img = RgbaImage::new(32, 32);
@@ -158,4 +223,44 @@ mod tests {
         let version = u32::from_le_bytes([result[8], result[9], result[10], result[11]]);
         assert_eq!(version, 0x0001_0000);
     }
+
+    #[test]
+    fn test_comment_chunk_keeps_images_readable() {
+        use crate::pipeline::xcur2png::xcursor_reader::XcursorFile;
+
+        let frames: Vec<CursorFrame> = [24, 32]
+            .into_iter()
+            .map(|size| CursorFrame {
+                images: vec![CursorImage {
+                    image: RgbaImage::from_pixel(size, size, Rgba([10, 20, 30, 255])),
+                    hotspot: (3, 4),
+                    nominal_size: size,
+                }],
+                delay: 50,
+            })
+            .collect();
+
+        let comment = "left_ptr from ani2hyprtui";
+        let data = to_x11_with_comment(&frames, AlphaMode::Premultiplied, Some(comment)).unwrap();
+
+        let ntoc = u32::from_le_bytes(data[12..16].try_into().unwrap());
+        assert_eq!(ntoc, 3);
+        let first_type = u32::from_le_bytes(data[16..20].try_into().unwrap());
+        assert_eq!(first_type, CHUNK_COMMENT);
+        let comment_pos = u32::from_le_bytes(data[24..28].try_into().unwrap()) as usize;
+        let text_start = comment_pos + COMMENT_HEADER_SIZE;
+        assert_eq!(
+            &data[text_start..text_start + comment.len()],
+            comment.as_bytes()
+        );
+
+        let parsed = XcursorFile::from_bytes(&data, DEFAULT_MAX_DIMENSION).unwrap();
+        assert_eq!(parsed.get_sizes(), vec![24, 32]);
+        assert_eq!(parsed.images[1].width, 32);
+        assert_eq!((parsed.images[1].xhot, parsed.images[1].yhot), (3, 4));
+        assert_eq!(
+            parsed.images[1].pixels.get_pixel(0, 0),
+            &Rgba([10, 20, 30, 255])
+        );
+    }
 }
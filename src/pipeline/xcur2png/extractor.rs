@@ -3,6 +3,7 @@ use std::path::{Path, PathBuf};
 
 use super::png_writer::{PngWriteConfig, write_config_file, write_png};
 use super::xcursor_reader::XcursorFile;
+use crate::pipeline::limits::{DEFAULT_MAX_DIMENSION, effective_max_dimension};
 
 #[derive(Debug, Clone)]
 pub struct ExtractOptions {
@@ -11,6 +12,8 @@ pub struct ExtractOptions {
     pub write_config: bool,
     pub config_name: Option<String>,
     pub extract_all_sizes: bool,
+    pub prefix_from_comment: bool,
+    pub max_dimension: u32,
 }
 
 impl ExtractOptions {
@@ -21,6 +24,8 @@ impl ExtractOptions {
             write_config: true,
             config_name: None,
             extract_all_sizes: true,
+            prefix_from_comment: false,
+            max_dimension: DEFAULT_MAX_DIMENSION,
         }
     }
 
@@ -48,6 +53,18 @@ impl ExtractOptions {
         self.extract_all_sizes = extract_all;
         self
     }
+
+    /// Name PNGs after the file's first comment chunk, when it has one, instead of `prefix`
+    pub fn with_prefix_from_comment(mut self, enabled: bool) -> Self {
+        self.prefix_from_comment = enabled;
+        self
+    }
+
+    /// Largest image width or height read from the Xcursor; 0 means the default
+    pub fn with_max_dimension(mut self, max: u32) -> Self {
+        self.max_dimension = effective_max_dimension(max);
+        self
+    }
 }
 
 impl Default for ExtractOptions {
@@ -61,7 +78,7 @@ pub fn extract_to_pngs(
     output_dir: &Path,
     options: &ExtractOptions,
 ) -> Result<Vec<PathBuf>> {
-    let xcursor = XcursorFile::from_file(xcursor_path)?;
+    let xcursor = XcursorFile::from_file(xcursor_path, options.max_dimension)?;
 
     std::fs::create_dir_all(output_dir)?;
 
@@ -69,6 +86,13 @@ pub fn extract_to_pngs(
     let mut config_entries = Vec::new();
     let mut suffix = options.initial_suffix;
 
+    let prefix = options
+        .prefix_from_comment
+        .then(|| xcursor.comments.first().map(|c| comment_to_prefix(c)))
+        .flatten()
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| options.prefix.clone());
+
     let sizes = if options.extract_all_sizes {
         xcursor.get_sizes()
     } else {
@@ -78,7 +102,7 @@ pub fn extract_to_pngs(
     for size in sizes {
         let images = xcursor.get_images_for_size(size);
         for image in images.iter() {
-            let filename = format!("{}_{:03}.png", options.prefix, suffix);
+            let filename = format!("{}_{:03}.png", prefix, suffix);
             let filepath = output_dir.join(&filename);
 
             write_png(&image.pixels, &filepath)?;
@@ -107,7 +131,7 @@ pub fn extract_to_pngs(
         let config_name = options
             .config_name
             .clone()
-            .unwrap_or_else(|| format!("{}.conf", options.prefix));
+            .unwrap_or_else(|| format!("{}.conf", prefix));
 
         let config_path = output_dir.join(config_name);
         write_config_file(&config_path, &config_entries)?;
@@ -116,8 +140,23 @@ pub fn extract_to_pngs(
     Ok(extracted_files)
 }
 
-pub fn extract_metadata(xcursor_path: &Path) -> Result<CursorMetadata> {
-    let xcursor = XcursorFile::from_file(xcursor_path)?;
+// Comments are free text; keep only characters that are safe in a file name
+fn comment_to_prefix(comment: &str) -> String {
+    comment
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+pub fn extract_metadata(xcursor_path: &Path, max_dimension: u32) -> Result<CursorMetadata> {
+    let xcursor = XcursorFile::from_file(xcursor_path, max_dimension)?;
 
     let sizes = xcursor.get_sizes();
     let total_images = xcursor.images.len();
@@ -132,6 +171,7 @@ pub fn extract_metadata(xcursor_path: &Path) -> Result<CursorMetadata> {
         sizes: sizes.clone(),
         total_images,
         frames_per_size,
+        comments: xcursor.comments,
     })
 }
 
@@ -140,6 +180,7 @@ pub struct CursorMetadata {
     pub sizes: Vec<u32>,
     pub total_images: usize,
     pub frames_per_size: Vec<(u32, usize)>,
+    pub comments: Vec<String>,
 }
 
 #[cfg(test)]
@@ -165,4 +206,10 @@ mod tests {
         assert_eq!(opts.initial_suffix, 0);
         assert!(opts.write_config);
     }
+
+    #[test]
+    fn test_comment_to_prefix() {
+        assert_eq!(comment_to_prefix("left_ptr"), "left_ptr");
+        assert_eq!(comment_to_prefix(" Busy / wait "), "Busy___wait");
+    }
 }
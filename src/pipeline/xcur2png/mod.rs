@@ -2,12 +2,14 @@
 
 pub mod extractor;
 pub mod png_writer;
+pub mod sprite_sheet;
 pub mod xcursor_reader;
 
 use anyhow::Result;
 use std::path::{Path, PathBuf};
 
 pub use extractor::{ExtractOptions, extract_to_pngs};
+pub use sprite_sheet::{build_sprite_sheet, export_sprite_sheet};
 pub use xcursor_reader::{XcursorFile, XcursorImage};
 
 pub fn extract_cursor(
@@ -28,6 +28,48 @@ pub fn format_config_line(config: &PngWriteConfig) -> String {
     )
 }
 
+/// Delay given to `.conf` lines that leave out the last column
+pub const DEFAULT_CONFIG_DELAY: u32 = 50;
+
+/// Reads one `size xhot yhot filename [delay]` line of a `.conf` file, with the delay in
+/// milliseconds. Tab-separated lines, as written by `write_config_file`, may have spaces in
+/// the file name; other lines are split on any whitespace, and a trailing number is only
+/// taken as the delay when there are at least five columns. Comments and blank or
+/// malformed lines give `None`.
+pub fn parse_config_line(line: &str) -> Option<PngWriteConfig> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let parts: Vec<&str> = if line.contains('\t') {
+        line.split('\t').map(str::trim).collect()
+    } else {
+        line.split_whitespace().collect()
+    };
+    if parts.len() < 4 {
+        return None;
+    }
+
+    let (filename, delay) = match parts[3..].split_last() {
+        Some((last, name)) if !name.is_empty() && last.parse::<u32>().is_ok() => {
+            (name.join(" "), last.parse().ok())
+        }
+        _ => (parts[3..].join(" "), None),
+    };
+    if filename.is_empty() {
+        return None;
+    }
+
+    Some(PngWriteConfig {
+        filename,
+        size: parts[0].parse().ok()?,
+        xhot: parts[1].parse().ok()?,
+        yhot: parts[2].parse().ok()?,
+        delay: delay.unwrap_or(DEFAULT_CONFIG_DELAY),
+    })
+}
+
 pub fn write_config_file(path: &Path, configs: &[PngWriteConfig]) -> Result<()> {
     use std::io::Write;
 
@@ -84,6 +126,39 @@ mod tests {
         assert_eq!(line, "32\t16\t16\tcursor_001.png\t50");
     }
 
+    #[test]
+    fn test_parse_tab_separated_config_line() {
+        let config = parse_config_line("32\t4\t6\tbusy frame 1.png\t120").unwrap();
+        assert_eq!(config.filename, "busy frame 1.png");
+        assert_eq!((config.size, config.xhot, config.yhot), (32, 4, 6));
+        assert_eq!(config.delay, 120);
+
+        let written = format_config_line(&config);
+        let reread = parse_config_line(&written).unwrap();
+        assert_eq!(reread.filename, config.filename);
+        assert_eq!(reread.delay, 120);
+
+        let no_delay = parse_config_line("24\t0\t0\tleft_ptr_000.png").unwrap();
+        assert_eq!(no_delay.delay, DEFAULT_CONFIG_DELAY);
+    }
+
+    #[test]
+    fn test_parse_whitespace_separated_config_line() {
+        let config = parse_config_line("  48 12  6   wait_003.png 33 ").unwrap();
+        assert_eq!(config.filename, "wait_003.png");
+        assert_eq!((config.size, config.xhot, config.yhot), (48, 12, 6));
+        assert_eq!(config.delay, 33);
+
+        // Four columns: a numeric name is still the file, not a delay
+        let config = parse_config_line("16 1 1 100").unwrap();
+        assert_eq!(config.filename, "100");
+        assert_eq!(config.delay, DEFAULT_CONFIG_DELAY);
+
+        assert!(parse_config_line("#size xhot yhot file delay").is_none());
+        assert!(parse_config_line("32 x 0 a.png 50").is_none());
+        assert!(parse_config_line("32 0 0").is_none());
+    }
+
     #[test]
     fn test_write_config_file() {
         let dir = tempdir().unwrap();
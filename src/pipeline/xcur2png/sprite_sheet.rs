@@ -0,0 +1,95 @@
+// Lays every frame of every size of a cursor out in a single PNG, for documentation
+
+use anyhow::{Result, bail};
+use image::RgbaImage;
+use std::path::Path;
+
+use super::png_writer::write_png;
+use super::xcursor_reader::XcursorFile;
+
+/// One row per entry of `rows`, one column per frame. Every cell is as large as the largest
+/// image; smaller images sit in the top-left corner of their cell and short rows are
+/// left-aligned, with the rest of the sheet transparent.
+pub fn build_sprite_sheet(rows: &[Vec<&RgbaImage>]) -> RgbaImage {
+    let images = rows.iter().flatten();
+    let cell_w = images.clone().map(|img| img.width()).max().unwrap_or(0);
+    let cell_h = images.map(|img| img.height()).max().unwrap_or(0);
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0) as u32;
+
+    let mut sheet = RgbaImage::new(cell_w * columns, cell_h * rows.len() as u32);
+    for (row, frames) in rows.iter().enumerate() {
+        for (column, frame) in frames.iter().enumerate() {
+            image::imageops::replace(
+                &mut sheet,
+                *frame,
+                (column as u32 * cell_w) as i64,
+                (row as u32 * cell_h) as i64,
+            );
+        }
+    }
+    sheet
+}
+
+/// Writes a sprite sheet of `xcursor` to `out`, sizes ascending from top to bottom
+pub fn export_sprite_sheet(xcursor: &XcursorFile, out: &Path) -> Result<()> {
+    let rows: Vec<Vec<&RgbaImage>> = xcursor
+        .get_sizes()
+        .into_iter()
+        .map(|size| {
+            xcursor
+                .get_images_for_size(size)
+                .into_iter()
+                .map(|img| &img.pixels)
+                .collect()
+        })
+        .collect();
+
+    if rows.is_empty() {
+        bail!("Cursor has no images");
+    }
+    write_png(&build_sprite_sheet(&rows), out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::xcur2png::XcursorImage;
+    use image::Rgba;
+    use tempfile::tempdir;
+
+    fn image(size: u32, shade: u8) -> XcursorImage {
+        XcursorImage {
+            size,
+            width: size,
+            height: size,
+            xhot: 0,
+            yhot: 0,
+            delay: 50,
+            pixels: RgbaImage::from_pixel(size, size, Rgba([shade, shade, shade, 255])),
+        }
+    }
+
+    #[test]
+    fn test_ragged_rows_are_left_aligned() {
+        // Size 2 has three frames, size 4 only one
+        let xcursor = XcursorFile {
+            images: vec![image(4, 200), image(2, 10), image(2, 20), image(2, 30)],
+            comments: Vec::new(),
+        };
+        let dir = tempdir().unwrap();
+        let out = dir.path().join("sheet.png");
+
+        export_sprite_sheet(&xcursor, &out).unwrap();
+        let sheet = image::open(&out).unwrap().to_rgba8();
+
+        assert_eq!(sheet.dimensions(), (12, 8));
+        // First row: the 2px frames, each in the top-left of a 4px cell
+        assert_eq!(sheet.get_pixel(0, 0), &Rgba([10, 10, 10, 255]));
+        assert_eq!(sheet.get_pixel(8, 0), &Rgba([30, 30, 30, 255]));
+        assert_eq!(sheet.get_pixel(3, 3)[3], 0);
+        // Second row: one frame, then transparent padding
+        assert_eq!(sheet.get_pixel(3, 7), &Rgba([200, 200, 200, 255]));
+        assert_eq!(sheet.get_pixel(4, 4)[3], 0);
+        assert_eq!(sheet.get_pixel(11, 7)[3], 0);
+    }
+}
@@ -4,9 +4,13 @@ use image::{Rgba, RgbaImage};
 use std::io::{Cursor, Read};
 use std::path::Path;
 
+use crate::pipeline::limits::check_dimensions;
+
 const XCURSOR_MAGIC: &[u8] = b"Xcur";
 const XCURSOR_VERSION: u32 = 0x0001_0000;
 const XCURSOR_IMAGE_TYPE: u32 = 0xfffd0002;
+const XCURSOR_COMMENT_TYPE: u32 = 0xfffe0001;
+const IMAGE_HEADER_SIZE: usize = 36;
 
 #[derive(Debug, Clone)]
 pub struct XcursorImage {
@@ -22,15 +26,18 @@ pub struct XcursorImage {
 #[derive(Debug)]
 pub struct XcursorFile {
     pub images: Vec<XcursorImage>,
+    // Text of the file's comment chunks, in TOC order
+    pub comments: Vec<String>,
 }
 
 impl XcursorFile {
-    pub fn from_file(path: &Path) -> Result<Self> {
+    pub fn from_file(path: &Path, max_dimension: u32) -> Result<Self> {
         let data = std::fs::read(path)?;
-        Self::from_bytes(&data)
+        Self::from_bytes(&data, max_dimension)
     }
 
-    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+    /// Parses an Xcursor, rejecting images wider or taller than `max_dimension`
+    pub fn from_bytes(data: &[u8], max_dimension: u32) -> Result<Self> {
         let mut cursor = Cursor::new(data);
 
         // Read and validate magic
@@ -54,19 +61,38 @@ impl XcursorFile {
 
         // Read TOC
         let mut toc_entries = Vec::new();
+        let mut comment_positions = Vec::new();
         for _ in 0..ntoc {
             let chunk_type = cursor.read_u32::<LittleEndian>()?;
             let chunk_subtype = cursor.read_u32::<LittleEndian>()?;
             let chunk_position = cursor.read_u32::<LittleEndian>()?;
 
-            if chunk_type == XCURSOR_IMAGE_TYPE {
-                toc_entries.push((chunk_subtype, chunk_position));
+            match chunk_type {
+                XCURSOR_IMAGE_TYPE => toc_entries.push((chunk_subtype, chunk_position)),
+                XCURSOR_COMMENT_TYPE => comment_positions.push(chunk_position),
+                _ => {}
             }
         }
 
+        // A damaged comment shouldn't cost the images, so those are skipped
+        let comments = comment_positions
+            .into_iter()
+            .filter_map(|position| read_comment(data, position).ok())
+            .collect();
+
         // Read image chunks
         let mut images = Vec::new();
-        for (size, position) in toc_entries {
+        for (index, (size, position)) in toc_entries.into_iter().enumerate() {
+            // A TOC entry can point anywhere; make sure the whole 36-byte header is there
+            if position as usize + IMAGE_HEADER_SIZE > data.len() {
+                return Err(anyhow!(
+                    "TOC entry {} points to offset {}, but the image header needs {} bytes and the file is only {} bytes",
+                    index,
+                    position,
+                    IMAGE_HEADER_SIZE,
+                    data.len()
+                ));
+            }
             cursor.set_position(position as u64);
 
             // Read chunk header
@@ -78,7 +104,7 @@ impl XcursorFile {
                 continue;
             }
 
-            if chunk_header != 36 {
+            if chunk_header as usize != IMAGE_HEADER_SIZE {
                 return Err(anyhow!("Invalid chunk header size: {}", chunk_header));
             }
 
@@ -93,30 +119,39 @@ impl XcursorFile {
             let xhot = cursor.read_u32::<LittleEndian>()?;
             let yhot = cursor.read_u32::<LittleEndian>()?;
             let delay = cursor.read_u32::<LittleEndian>()?;
+            check_dimensions(
+                width,
+                height,
+                max_dimension,
+                &format!("Xcursor image {}", index),
+            )?;
 
             // Read pixels (BGRA format with premultiplied alpha)
-            let _pixel_count = (width * height) as usize;
+            let pixel_offset = cursor.position();
+            let expected_bytes = width as u64 * height as u64 * 4;
+            let available_bytes = (data.len() as u64).saturating_sub(pixel_offset);
+            if available_bytes < expected_bytes {
+                return Err(anyhow!(
+                    "Truncated pixel data in image {} ({}x{}, nominal size {}) at offset {}: expected {} bytes, only {} available",
+                    index,
+                    width,
+                    height,
+                    size,
+                    pixel_offset,
+                    expected_bytes,
+                    available_bytes
+                ));
+            }
+
             let mut pixels = RgbaImage::new(width, height);
 
             for y in 0..height {
                 for x in 0..width {
-                    let b = cursor.read_u8()?;
-                    let g = cursor.read_u8()?;
-                    let r = cursor.read_u8()?;
-                    let a = cursor.read_u8()?;
-
-                    // Undo premultiplied alpha
-                    let (r_out, g_out, b_out) = if a == 0 {
-                        (255, 255, 255)
-                    } else {
-                        let alpha_factor = 255.0 / a as f64;
-                        let r_unpre = ((r as f64 * alpha_factor).min(255.0)) as u8;
-                        let g_unpre = ((g as f64 * alpha_factor).min(255.0)) as u8;
-                        let b_unpre = ((b as f64 * alpha_factor).min(255.0)) as u8;
-                        (r_unpre, g_unpre, b_unpre)
-                    };
-
-                    pixels.put_pixel(x, y, Rgba([r_out, g_out, b_out, a]));
+                    let mut bgra = [0u8; 4];
+                    cursor.read_exact(&mut bgra)?;
+                    let [b, g, r, a] = bgra;
+
+                    pixels.put_pixel(x, y, unpremultiply(r, g, b, a));
                 }
             }
 
@@ -135,7 +170,7 @@ impl XcursorFile {
             return Err(anyhow!("No valid cursor images found"));
         }
 
-        Ok(XcursorFile { images })
+        Ok(XcursorFile { images, comments })
     }
 
     /// Get the nominal size of cursors in this file
@@ -152,19 +187,54 @@ impl XcursorFile {
     }
 }
 
+// Rounds rather than truncates, since dividing by a small alpha magnifies any error; opaque
+// pixels were stored unchanged and skip the division entirely
+fn unpremultiply(r: u8, g: u8, b: u8, a: u8) -> Rgba<u8> {
+    match a {
+        0 => Rgba([255, 255, 255, 0]),
+        255 => Rgba([r, g, b, 255]),
+        _ => {
+            let undo = |c: u8| (c as f64 * 255.0 / a as f64).round().min(255.0) as u8;
+            Rgba([undo(r), undo(g), undo(b), a])
+        }
+    }
+}
+
+// Comment chunk: header size, type, subtype, version and length, then the UTF-8 text
+fn read_comment(data: &[u8], position: u32) -> Result<String> {
+    let mut cursor = Cursor::new(data);
+    cursor.set_position(position as u64);
+
+    let header_size = cursor.read_u32::<LittleEndian>()?;
+    let chunk_type = cursor.read_u32::<LittleEndian>()?;
+    if chunk_type != XCURSOR_COMMENT_TYPE {
+        return Err(anyhow!("Chunk at {} is not a comment", position));
+    }
+    let _subtype = cursor.read_u32::<LittleEndian>()?;
+    let _version = cursor.read_u32::<LittleEndian>()?;
+    let length = cursor.read_u32::<LittleEndian>()? as usize;
+
+    // The 20-byte header already counts the length field
+    let start = position as usize + header_size as usize;
+    let bytes = data
+        .get(start..start.saturating_add(length))
+        .ok_or_else(|| anyhow!("Truncated comment at {}", position))?;
+    Ok(String::from_utf8_lossy(bytes).into_owned())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pipeline::limits::DEFAULT_MAX_DIMENSION;
 
     #[test]
     fn test_xcursor_magic_validation() {
         let invalid_data = b"INVALID";
-        let result = XcursorFile::from_bytes(invalid_data);
+        let result = XcursorFile::from_bytes(invalid_data, DEFAULT_MAX_DIMENSION);
         assert!(result.is_err());
     }
 
-    #[test]
-    fn test_xcursor_parsing() {
+    fn sample_cursor_bytes() -> Vec<u8> {
         let mut data = Vec::new();
 
         data.extend_from_slice(b"Xcur");
@@ -193,11 +263,143 @@ mod tests {
             data.extend_from_slice(&[255, 128, 64, 255]); // BGRA
         }
 
-        let xcursor = XcursorFile::from_bytes(&data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_xcursor_parsing() {
+        let data = sample_cursor_bytes();
+        let xcursor = XcursorFile::from_bytes(&data, DEFAULT_MAX_DIMENSION).unwrap();
         assert_eq!(xcursor.images.len(), 1);
         assert_eq!(xcursor.images[0].width, 2);
         assert_eq!(xcursor.images[0].height, 2);
         assert_eq!(xcursor.images[0].xhot, 1);
         assert_eq!(xcursor.images[0].yhot, 1);
     }
+
+    #[test]
+    fn test_premultiplied_round_trip() {
+        use crate::pipeline::win2xcur::cur::{CursorFrame, CursorImage};
+        use crate::pipeline::win2xcur::xcursor_writer::to_x11;
+
+        let colors = [
+            Rgba([200, 100, 50, 255]),
+            Rgba([1, 254, 127, 255]),
+            Rgba([200, 100, 50, 128]),
+            Rgba([255, 255, 255, 3]),
+        ];
+        let mut image = RgbaImage::new(colors.len() as u32, 1);
+        for (x, color) in colors.iter().enumerate() {
+            image.put_pixel(x as u32, 0, *color);
+        }
+        let frames = vec![CursorFrame {
+            images: vec![CursorImage {
+                image,
+                hotspot: (0, 0),
+                nominal_size: 4,
+            }],
+            delay: 0,
+        }];
+
+        let xcursor =
+            XcursorFile::from_bytes(&to_x11(&frames).unwrap(), DEFAULT_MAX_DIMENSION).unwrap();
+        let pixels = &xcursor.images[0].pixels;
+        // Opaque colors come back exactly
+        assert_eq!(pixels.get_pixel(0, 0), &colors[0]);
+        assert_eq!(pixels.get_pixel(1, 0), &colors[1]);
+        // Translucent ones within what 8-bit premultiplied storage can hold
+        for (x, color) in colors.iter().enumerate().skip(2) {
+            let read = pixels.get_pixel(x as u32, 0);
+            let tolerance = 255 / color[3] as i32 + 1;
+            for c in 0..3 {
+                assert!((read[c] as i32 - color[c] as i32).abs() <= tolerance);
+            }
+            assert_eq!(read[3], color[3]);
+        }
+    }
+
+    #[test]
+    fn test_xcursor_comment_chunk() {
+        let comment = b"left_ptr";
+        let mut data = Vec::new();
+
+        data.extend_from_slice(b"Xcur");
+        data.extend_from_slice(&16u32.to_le_bytes()); // header size
+        data.extend_from_slice(&0x0001_0000u32.to_le_bytes()); // version
+        data.extend_from_slice(&2u32.to_le_bytes()); // ntoc
+
+        // TOC: comment, then image
+        let comment_pos = 16 + 2 * 12;
+        let image_pos = comment_pos + 20 + comment.len();
+        data.extend_from_slice(&0xfffe0001u32.to_le_bytes());
+        data.extend_from_slice(&3u32.to_le_bytes()); // subtype (other)
+        data.extend_from_slice(&(comment_pos as u32).to_le_bytes());
+        data.extend_from_slice(&0xfffd0002u32.to_le_bytes());
+        data.extend_from_slice(&32u32.to_le_bytes());
+        data.extend_from_slice(&(image_pos as u32).to_le_bytes());
+
+        // Comment chunk
+        data.extend_from_slice(&20u32.to_le_bytes()); // chunk header
+        data.extend_from_slice(&0xfffe0001u32.to_le_bytes()); // type
+        data.extend_from_slice(&3u32.to_le_bytes()); // subtype
+        data.extend_from_slice(&1u32.to_le_bytes()); // version
+        data.extend_from_slice(&(comment.len() as u32).to_le_bytes());
+        data.extend_from_slice(comment);
+
+        // Image chunk, reused from the plain sample
+        let sample = sample_cursor_bytes();
+        data.extend_from_slice(&sample[28..]);
+
+        let xcursor = XcursorFile::from_bytes(&data, DEFAULT_MAX_DIMENSION).unwrap();
+        assert_eq!(xcursor.comments, vec!["left_ptr".to_string()]);
+        assert_eq!(xcursor.images.len(), 1);
+        assert_eq!(xcursor.images[0].size, 32);
+        assert_eq!(xcursor.images[0].width, 2);
+    }
+
+    #[test]
+    fn test_xcursor_oversized_image_is_rejected() {
+        let mut data = sample_cursor_bytes();
+        // width field of the image header
+        data[44..48].copy_from_slice(&70_000u32.to_le_bytes());
+
+        let err = XcursorFile::from_bytes(&data, DEFAULT_MAX_DIMENSION)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("70000x2"), "{err}");
+    }
+
+    #[test]
+    fn test_xcursor_toc_offset_out_of_bounds() {
+        let mut data = sample_cursor_bytes();
+        // position field of the only TOC entry
+        data[24..28].copy_from_slice(&4096u32.to_le_bytes());
+        let err = XcursorFile::from_bytes(&data, DEFAULT_MAX_DIMENSION)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("TOC entry 0 points to offset 4096"), "{err}");
+
+        // Image chunk cut off halfway through its header
+        let mut data = sample_cursor_bytes();
+        data.truncate(28 + 20);
+        let err = XcursorFile::from_bytes(&data, DEFAULT_MAX_DIMENSION)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("TOC entry 0 points to offset 28"), "{err}");
+    }
+
+    #[test]
+    fn test_xcursor_truncated_pixels() {
+        let mut data = sample_cursor_bytes();
+        data.truncate(data.len() - 6);
+
+        let err = XcursorFile::from_bytes(&data, DEFAULT_MAX_DIMENSION)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("image 0"), "{err}");
+        assert!(
+            err.contains("expected 16 bytes, only 10 available"),
+            "{err}"
+        );
+    }
 }
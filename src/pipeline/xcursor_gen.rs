@@ -1,15 +1,30 @@
 // takes X11 cursor binaries from win2xcur into a proper theme structure with mapping and symlinks
 
+use super::fs_ops::{copy_dir_all, relative_link_target, symlink_relative};
+use super::theme_build::check_cancelled;
+use super::xcur2png::XcursorFile;
 use crate::model::mapping::CursorMapping;
+use crate::pipeline::limits::{DEFAULT_MAX_DIMENSION, effective_max_dimension};
 use anyhow::Result;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
 use std::fs;
-use std::os::unix::fs as unix_fs;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+
+/// Theme that `index.theme` falls back to for cursors this one lacks, unless overridden
+pub const DEFAULT_INHERITS: &str = "hicolor";
 
 pub struct XCursorThemeBuilder {
     output_dir: PathBuf,
     theme_name: String,
     mapping: CursorMapping,
+    install: bool,
+    inherits: String,
+    description: String,
+    link_duplicates: bool,
+    max_dimension: u32,
 }
 
 impl XCursorThemeBuilder {
@@ -22,12 +37,52 @@ impl XCursorThemeBuilder {
             output_dir: output_dir.into(),
             theme_name,
             mapping,
+            install: true,
+            inherits: DEFAULT_INHERITS.to_string(),
+            description: String::new(),
+            link_duplicates: false,
+            max_dimension: DEFAULT_MAX_DIMENSION,
         }
     }
 
+    /// Whether a build also replaces `~/.icons/<theme>` with the result
+    pub fn with_install(mut self, install: bool) -> Self {
+        self.install = install;
+        self
+    }
+
+    /// `Comment=` of index.theme and cursor.theme; empty uses "<name> cursor theme"
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Value of `Inherits=` in `index.theme`; empty leaves the line out
+    pub fn with_inherits(mut self, inherits: impl Into<String>) -> Self {
+        self.inherits = inherits.into();
+        self
+    }
+
+    /// Replace byte-identical cursor files with symlinks to one of them
+    pub fn with_link_duplicates(mut self, link: bool) -> Self {
+        self.link_duplicates = link;
+        self
+    }
+
+    /// Largest image read back when comparing cursors for linking; 0 means the default
+    pub fn with_max_dimension(mut self, max: u32) -> Self {
+        self.max_dimension = effective_max_dimension(max);
+        self
+    }
+
     /// Build theme from existing X11 cursor binaries
-    /// xcur_source_dir should contain cursor files with Windows names
-    pub fn build_from_xcur_files(&self, xcur_source_dir: &Path) -> Result<usize> {
+    /// xcur_source_dir should contain cursor files with Windows names.
+    /// Returns (cursor files written, how many of them were turned into duplicate links).
+    pub fn build_from_xcur_files(
+        &self,
+        xcur_source_dir: &Path,
+        cancel: &AtomicBool,
+    ) -> Result<(usize, usize)> {
         let cursors_dir = self.output_dir.join("cursors");
         fs::create_dir_all(&cursors_dir)?;
 
@@ -35,6 +90,7 @@ impl XCursorThemeBuilder {
 
         // Copy and rename cursor files according to mapping
         for (x11_name, win_name) in &self.mapping.x11_to_win {
+            check_cancelled(cancel)?;
             let source_file = xcur_source_dir.join(win_name);
             if !source_file.exists() {
                 if let Some(normal_win_name) = self.mapping.x11_to_win.get("left_ptr") {
@@ -61,20 +117,108 @@ impl XCursorThemeBuilder {
             }
 
             let dest_file = cursors_dir.join(x11_name);
-            fs::copy(&source_file, &dest_file)?;
+            copy_replacing(&source_file, &dest_file)?;
             count += 1;
         }
 
+        // Before the mapping's links, so those land on whichever file stays a real one
+        let linked = if self.link_duplicates {
+            link_identical_cursors(&cursors_dir, self.max_dimension)?
+        } else {
+            0
+        };
+
         self.create_symlinks(&cursors_dir)?;
         self.create_theme_files()?;
-        self.install_to_user_icons()?;
+        if self.install {
+            self.install_to_user_icons()?;
+        }
+
+        Ok((count, linked))
+    }
+
+    /// Check that essential cursors made it into the theme with their own source.
+    /// Must run before `xcur_source_dir` is cleaned up; returns one warning per problem.
+    pub fn check_essential_cursors(
+        &self,
+        xcur_source_dir: &Path,
+        essential_cursors: &[String],
+    ) -> Vec<String> {
+        let cursors_dir = self.output_dir.join("cursors");
+        let mut warnings = Vec::new();
+
+        for name in essential_cursors {
+            let built = cursors_dir.join(name).exists();
+            let root = self.mapping.resolve_root(name);
+
+            match (built, root) {
+                (false, _) | (_, None) => {
+                    warnings.push(format!("essential cursor '{}' is missing", name));
+                }
+                (true, Some(root)) => {
+                    let win_name = &self.mapping.x11_to_win[root];
+                    let has_source = xcur_source_dir.join(win_name).exists();
+                    let is_normal = win_name == "Normal" && root != "left_ptr";
+
+                    if !has_source || is_normal {
+                        warnings.push(format!("essential cursor '{}' fell back to Normal", name));
+                    }
+                }
+            }
+        }
 
-        Ok(count)
+        warnings
     }
 
-    fn create_symlinks(&self, cursors_dir: &Path) -> Result<()> {
+    /// Brings the symlinks of an already built `cursors/` directory in line with the mapping
+    /// without touching the cursor files. Links the mapping no longer lists, or that point at
+    /// a different cursor, are removed; returns (created, removed).
+    pub fn refresh_symlinks(&self) -> Result<(usize, usize)> {
+        let cursors_dir = self.output_dir.join("cursors");
+        if !cursors_dir.is_dir() {
+            anyhow::bail!(
+                "{} does not exist, build the theme first",
+                cursors_dir.display()
+            );
+        }
+
+        let expected: HashMap<&str, &str> = self
+            .mapping
+            .symlinks
+            .iter()
+            .flat_map(|(target, links)| links.iter().map(move |l| (l.as_str(), target.as_str())))
+            .collect();
+
+        let mut removed = 0;
+        for entry in fs::read_dir(&cursors_dir)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_symlink() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().into_owned();
+            // A mapped cursor that is a link was deduplicated, not created from `symlinks`
+            if !expected.contains_key(name.as_str()) && self.mapping.x11_to_win.contains_key(&name)
+            {
+                continue;
+            }
+            let current = fs::read_link(entry.path())?;
+            let wanted = expected
+                .get(name.as_str())
+                .map(|target| relative_link_target(&entry.path(), &cursors_dir.join(target)));
+            if wanted.as_deref() != Some(current.as_path()) {
+                fs::remove_file(entry.path())?;
+                removed += 1;
+            }
+        }
+
+        let created = self.create_symlinks(&cursors_dir)?;
+        Ok((created, removed))
+    }
+
+    // Returns how many links were created; existing files and links are left alone
+    fn create_symlinks(&self, cursors_dir: &Path) -> Result<usize> {
+        let mut created = 0;
         for (x11_name, symlink_names) in &self.mapping.symlinks {
-            let target = x11_name; // Relative symlink
             let target_file = cursors_dir.join(x11_name);
 
             if !target_file.exists() {
@@ -88,20 +232,26 @@ impl XCursorThemeBuilder {
                     continue;
                 }
 
-                unix_fs::symlink(target, &symlink_path)?;
+                symlink_relative(&target_file, &symlink_path)?;
+                created += 1;
             }
         }
 
-        Ok(())
+        Ok(created)
     }
 
     fn create_theme_files(&self) -> Result<()> {
         use crate::model::theme::{CursorTheme, IndexTheme};
 
+        let comment = match self.description.trim() {
+            "" => format!("{} cursor theme", self.theme_name),
+            description => description.to_string(),
+        };
+
         let index_theme = IndexTheme {
             name: self.theme_name.clone(),
-            comment: format!("{} cursor theme", self.theme_name),
-            inherits: "hicolor".to_string(),
+            comment: comment.clone(),
+            inherits: self.inherits.clone(),
             directories: vec!["cursors".to_string(), "hyprcursors".to_string()],
         };
 
@@ -109,7 +259,7 @@ impl XCursorThemeBuilder {
 
         let cursor_theme = CursorTheme {
             name: self.theme_name.clone(),
-            comment: format!("{} cursor theme", self.theme_name),
+            comment,
             inherits: self.theme_name.clone(),
         };
 
@@ -158,23 +308,323 @@ impl XCursorThemeBuilder {
     }
 }
 
-fn copy_dir_all(src: &Path, dst: &Path) -> std::io::Result<()> {
-    fs::create_dir_all(dst)?;
+// Removes whatever is at `dest` first: a rebuild would otherwise copy through a link left by
+// an earlier deduplicated build and overwrite the cursor it points at
+fn copy_replacing(source: &Path, dest: &Path) -> Result<()> {
+    if dest.symlink_metadata().is_ok() {
+        fs::remove_file(dest)?;
+    }
+    fs::copy(source, dest)?;
+    Ok(())
+}
 
-    for entry in fs::read_dir(src)? {
-        let entry = entry?;
-        let ty = entry.file_type()?;
-        let dst_path = dst.join(entry.file_name());
+/// Run before `name` is rewritten in place: cursors linked to it as duplicates get their own
+/// copy back, so they keep their current image. `aliases` are the mapping's links to `name`,
+/// which are meant to follow it and are left alone. Returns how many links were replaced.
+pub fn detach_duplicate_links(cursors_dir: &Path, name: &str, aliases: &[String]) -> Result<usize> {
+    let target = cursors_dir.join(name);
+    if !target.is_file() {
+        return Ok(0);
+    }
 
-        if ty.is_dir() {
-            copy_dir_all(&entry.path(), &dst_path)?;
-        } else if ty.is_symlink() {
-            let target = fs::read_link(entry.path())?;
-            unix_fs::symlink(target, dst_path)?;
-        } else {
-            fs::copy(entry.path(), dst_path)?;
+    let mut detached = 0;
+    for entry in fs::read_dir(cursors_dir)?.flatten() {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if aliases.contains(&file_name) {
+            continue;
         }
+        let Ok(link) = fs::read_link(&path) else {
+            continue;
+        };
+        if cursors_dir.join(link) != target {
+            continue;
+        }
+
+        let copy = path.with_file_name(format!(".{}.detached", file_name));
+        fs::copy(&target, &copy)?;
+        fs::rename(&copy, &path)?;
+        detached += 1;
     }
+    Ok(detached)
+}
 
-    Ok(())
+// What two cursor files must share to be linked: every image with its hotspot and delay.
+// Comments are left out, since builds name the source file in one. Files that don't parse
+// are compared as they are.
+fn image_signature(data: Vec<u8>, max_dimension: u32) -> Vec<u8> {
+    let Ok(xcursor) = XcursorFile::from_bytes(&data, max_dimension) else {
+        return data;
+    };
+    let mut signature = Vec::new();
+    for image in &xcursor.images {
+        for value in [
+            image.size,
+            image.width,
+            image.height,
+            image.xhot,
+            image.yhot,
+            image.delay,
+        ] {
+            signature.extend_from_slice(&value.to_le_bytes());
+        }
+        signature.extend_from_slice(image.pixels.as_raw());
+    }
+    signature
+}
+
+// Files are visited by name so the same cursor stays the real file from build to build
+fn link_identical_cursors(cursors_dir: &Path, max_dimension: u32) -> Result<usize> {
+    let mut names: Vec<PathBuf> = fs::read_dir(cursors_dir)?
+        .flatten()
+        .filter(|e| e.file_type().map(|t| t.is_file()).unwrap_or(false))
+        .map(|e| e.path())
+        .collect();
+    names.sort();
+
+    let mut originals: HashMap<u64, Vec<(PathBuf, Vec<u8>)>> = HashMap::new();
+    let mut linked = 0;
+    for path in names {
+        let data = image_signature(fs::read(&path)?, max_dimension);
+        let mut hasher = DefaultHasher::new();
+        data.hash(&mut hasher);
+        let candidates = originals.entry(hasher.finish()).or_default();
+
+        match candidates.iter().find(|(_, other)| *other == data) {
+            Some((original, _)) => {
+                fs::remove_file(&path)?;
+                symlink_relative(original, &path)?;
+                linked += 1;
+            }
+            None => candidates.push((path, data)),
+        }
+    }
+
+    Ok(linked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_check_essential_cursors() {
+        let temp = tempdir().unwrap();
+        let xcur_dir = temp.path().join("xcur");
+        let theme_dir = temp.path().join("theme");
+        fs::create_dir_all(&xcur_dir).unwrap();
+        fs::create_dir_all(theme_dir.join("cursors")).unwrap();
+
+        fs::write(xcur_dir.join("Normal"), b"normal").unwrap();
+        fs::write(xcur_dir.join("Busy"), b"busy").unwrap();
+        for name in ["left_ptr", "text", "wait", "default"] {
+            fs::write(theme_dir.join("cursors").join(name), b"x").unwrap();
+        }
+
+        let builder =
+            XCursorThemeBuilder::new(&theme_dir, "Test".to_string(), CursorMapping::default());
+        let essentials: Vec<String> = ["left_ptr", "default", "text", "wait", "pointer"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let warnings = builder.check_essential_cursors(&xcur_dir, &essentials);
+        assert_eq!(
+            warnings,
+            vec![
+                "essential cursor 'text' fell back to Normal".to_string(),
+                "essential cursor 'pointer' is missing".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_index_theme_inherits() {
+        let temp = tempdir().unwrap();
+        let builder =
+            XCursorThemeBuilder::new(temp.path(), "Test".to_string(), CursorMapping::default());
+        builder.create_theme_files().unwrap();
+        let index = fs::read_to_string(temp.path().join("index.theme")).unwrap();
+        assert!(index.contains("Inherits=hicolor\n"));
+
+        builder
+            .with_inherits("Adwaita")
+            .create_theme_files()
+            .unwrap();
+        let index = fs::read_to_string(temp.path().join("index.theme")).unwrap();
+        assert!(index.contains("Inherits=Adwaita\n"));
+        assert!(!index.contains("hicolor"));
+    }
+
+    #[test]
+    fn test_theme_files_use_description() {
+        let temp = tempdir().unwrap();
+        let builder =
+            XCursorThemeBuilder::new(temp.path(), "Test".to_string(), CursorMapping::default());
+        builder.create_theme_files().unwrap();
+        let index = fs::read_to_string(temp.path().join("index.theme")).unwrap();
+        assert!(index.contains("Comment=Test cursor theme\n"));
+
+        builder
+            .with_description("Test v2 by someone, https://example.com")
+            .create_theme_files()
+            .unwrap();
+        for file in ["index.theme", "cursor.theme"] {
+            let content = fs::read_to_string(temp.path().join(file)).unwrap();
+            assert!(content.contains("Comment=Test v2 by someone, https://example.com\n"));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_refresh_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let temp = tempdir().unwrap();
+        let cursors = temp.path().join("cursors");
+        fs::create_dir_all(&cursors).unwrap();
+        fs::write(cursors.join("left_ptr"), b"arrow").unwrap();
+        fs::write(cursors.join("xterm"), b"text").unwrap();
+        // Stale: no longer in the mapping, and pointing at the wrong cursor
+        symlink("left_ptr", cursors.join("gone")).unwrap();
+        symlink("left_ptr", cursors.join("text")).unwrap();
+        // Still correct
+        symlink("left_ptr", cursors.join("default")).unwrap();
+
+        let mapping = CursorMapping {
+            symlinks: [
+                (
+                    "left_ptr".to_string(),
+                    vec!["default".to_string(), "arrow".to_string()],
+                ),
+                ("xterm".to_string(), vec!["text".to_string()]),
+            ]
+            .into_iter()
+            .collect(),
+            ..CursorMapping::default()
+        };
+
+        let builder = XCursorThemeBuilder::new(temp.path(), "Test".to_string(), mapping);
+        assert_eq!(builder.refresh_symlinks().unwrap(), (2, 2));
+
+        assert!(!cursors.join("gone").exists());
+        assert_eq!(
+            fs::read_link(cursors.join("text")).unwrap(),
+            Path::new("xterm")
+        );
+        assert_eq!(
+            fs::read_link(cursors.join("arrow")).unwrap(),
+            Path::new("left_ptr")
+        );
+        assert_eq!(fs::read(cursors.join("left_ptr")).unwrap(), b"arrow");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_identical_sources_become_links() {
+        let temp = tempdir().unwrap();
+        let xcur_dir = temp.path().join("xcur");
+        fs::create_dir_all(&xcur_dir).unwrap();
+        fs::write(xcur_dir.join("Horizontal"), b"arrows").unwrap();
+        fs::write(xcur_dir.join("Vertical"), b"arrows").unwrap();
+        fs::write(xcur_dir.join("Normal"), b"pointer").unwrap();
+
+        let mapping = CursorMapping {
+            x11_to_win: [
+                ("left_ptr", "Normal"),
+                ("sb_h_double_arrow", "Horizontal"),
+                ("sb_v_double_arrow", "Vertical"),
+            ]
+            .into_iter()
+            .map(|(x11, win)| (x11.to_string(), win.to_string()))
+            .collect(),
+            symlinks: [(
+                "sb_v_double_arrow".to_string(),
+                vec!["ns-resize".to_string()],
+            )]
+            .into_iter()
+            .collect(),
+        };
+
+        let theme_dir = temp.path().join("theme");
+        let builder = XCursorThemeBuilder::new(&theme_dir, "Test".to_string(), mapping)
+            .with_install(false)
+            .with_link_duplicates(true);
+        assert_eq!(
+            builder
+                .build_from_xcur_files(&xcur_dir, &AtomicBool::new(false))
+                .unwrap(),
+            (3, 1)
+        );
+
+        let cursors = theme_dir.join("cursors");
+        assert!(!cursors.join("sb_h_double_arrow").is_symlink());
+        assert_eq!(
+            fs::read_link(cursors.join("sb_v_double_arrow")).unwrap(),
+            Path::new("sb_h_double_arrow")
+        );
+        assert!(!cursors.join("left_ptr").is_symlink());
+        // The mapping's own link still resolves through the deduplicated file
+        assert_eq!(fs::read(cursors.join("ns-resize")).unwrap(), b"arrows");
+
+        // Refreshing the mapping links leaves the duplicate link alone
+        assert_eq!(builder.refresh_symlinks().unwrap(), (0, 0));
+        assert!(cursors.join("sb_v_double_arrow").is_symlink());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rebuild_does_not_write_through_duplicate_links() {
+        let temp = tempdir().unwrap();
+        let xcur_dir = temp.path().join("xcur");
+        fs::create_dir_all(&xcur_dir).unwrap();
+        fs::write(xcur_dir.join("Horizontal"), b"arrows").unwrap();
+        fs::write(xcur_dir.join("Vertical"), b"arrows").unwrap();
+        fs::write(xcur_dir.join("Diagonal"), b"arrows").unwrap();
+
+        let mapping = CursorMapping {
+            x11_to_win: [
+                ("sb_h_double_arrow", "Horizontal"),
+                ("sb_v_double_arrow", "Vertical"),
+                ("fd_double_arrow", "Diagonal"),
+            ]
+            .into_iter()
+            .map(|(x11, win)| (x11.to_string(), win.to_string()))
+            .collect(),
+            symlinks: [(
+                "sb_v_double_arrow".to_string(),
+                vec!["ns-resize".to_string()],
+            )]
+            .into_iter()
+            .collect(),
+        };
+
+        let theme_dir = temp.path().join("theme");
+        let builder = XCursorThemeBuilder::new(&theme_dir, "Test".to_string(), mapping)
+            .with_install(false)
+            .with_link_duplicates(true);
+        let cancel = AtomicBool::new(false);
+        assert_eq!(
+            builder.build_from_xcur_files(&xcur_dir, &cancel).unwrap(),
+            (3, 2)
+        );
+
+        // Only the Vertical source changes before the second build
+        fs::write(xcur_dir.join("Vertical"), b"vertical").unwrap();
+        assert_eq!(
+            builder.build_from_xcur_files(&xcur_dir, &cancel).unwrap(),
+            (3, 1)
+        );
+
+        let cursors = theme_dir.join("cursors");
+        assert_eq!(
+            fs::read(cursors.join("sb_v_double_arrow")).unwrap(),
+            b"vertical"
+        );
+        assert_eq!(fs::read(cursors.join("ns-resize")).unwrap(), b"vertical");
+        for name in ["sb_h_double_arrow", "fd_double_arrow"] {
+            assert_eq!(fs::read(cursors.join(name)).unwrap(), b"arrows", "{name}");
+        }
+    }
 }
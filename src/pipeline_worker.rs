@@ -1,183 +1,336 @@
 // Pipeline worker for processing Windows cursors in a separate thread
 
 use anyhow::Result;
-use crossbeam_channel::Sender;
+use crossbeam_channel::{SendError, Sender};
+use image::imageops::FilterType;
 use rayon::prelude::*;
-use std::collections::HashMap;
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
+use std::panic::{self, AssertUnwindSafe};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::thread;
-use walkdir::WalkDir;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread::{self, JoinHandle};
 
 use crate::event::AppMsg;
 use crate::model::mapping::CursorMapping;
-use crate::pipeline::hyprcursor;
+use crate::pipeline::fs_ops::symlink_relative;
+use crate::pipeline::gallery::write_gallery;
+use crate::pipeline::hyprcursor::{
+    self, HlcCompression, HyprcursorOptions, ManifestFormat, ResizeAlgorithm,
+};
+use crate::pipeline::limits::{DEFAULT_MAX_DIMENSION, effective_max_dimension};
+use crate::pipeline::log::LogLevel;
+use crate::pipeline::theme_actions::archive_theme_tar_gz;
+use crate::pipeline::theme_build::{
+    BuildReporter, ThemeBuildOptions, check_cancelled, clean_intermediate, convert_batch,
+    find_cursor_files, log_target_sizes, run_full_theme_pipeline,
+};
 use crate::pipeline::win2xcur::converter::{ConversionOptions, convert_windows_cursor};
-use crate::pipeline::xcur2png::{ExtractOptions, extract_to_pngs};
-use crate::pipeline::xcursor_gen::XCursorThemeBuilder;
+use crate::pipeline::win2xcur::utils::ShadowConfig;
+use crate::pipeline::xcursor_gen::{DEFAULT_INHERITS, XCursorThemeBuilder, detach_duplicate_links};
+
+pub use crate::pipeline::theme_build::{BuildSummary, OutputLayout, ThemeVariant};
+
+// Sender handed to pipeline threads; drops log lines above the configured verbosity
+// and everything once a newer run has replaced this one
+#[derive(Clone)]
+struct PipelineTx {
+    tx: Sender<AppMsg>,
+    verbosity: LogLevel,
+    run_id: usize,
+    current_run: Arc<AtomicUsize>,
+}
+
+impl PipelineTx {
+    fn new(tx: Sender<AppMsg>, verbosity: LogLevel) -> Self {
+        Self {
+            tx,
+            verbosity,
+            run_id: 0,
+            current_run: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    fn is_stale(&self) -> bool {
+        self.current_run.load(Ordering::Relaxed) != self.run_id
+    }
+
+    // Unfiltered; used for control messages, stage transitions and errors
+    fn send(&self, msg: AppMsg) -> Result<(), SendError<AppMsg>> {
+        if self.is_stale() {
+            return Ok(());
+        }
+        self.tx.send(msg)
+    }
+
+    // Also the sink for library log callbacks, which pass their own level
+    fn log(&self, level: LogLevel, msg: String) {
+        if level >= self.verbosity {
+            let _ = self.send(AppMsg::LogMessageLevel(level, msg));
+        }
+    }
+
+    // Always shown regardless of verbosity
+    fn warn(&self, msg: String) {
+        self.log(LogLevel::Warn, msg);
+    }
+
+    fn error(&self, msg: String) {
+        self.log(LogLevel::Error, msg);
+    }
+}
+
+impl BuildReporter for PipelineTx {
+    fn log(&self, level: LogLevel, msg: String) {
+        PipelineTx::log(self, level, msg);
+    }
+
+    fn progress(&self, done: usize, total: usize) {
+        let _ = self.send(AppMsg::PipelineProgress(done, total));
+    }
+}
 
 pub struct PipelineWorker {
     tx: Sender<AppMsg>,
     thread_count: usize,
+    resize_filter: FilterType,
+    // Largest cursor image width or height the parsers accept
+    max_dimension: u32,
+    essential_cursors: Vec<String>,
+    cancel_flag: Arc<AtomicBool>,
+    verbosity: LogLevel,
+    hyprcursor_options: HyprcursorOptions,
+    // Full builds also replace ~/.icons/<theme> unless this is cleared
+    install_to_user_icons: bool,
+    // Leave the raw Xcursor binaries of full and PNG-only builds in the output directory
+    keep_intermediate: bool,
+    output_layout: OutputLayout,
+    // Full builds link byte-identical cursor files to one copy instead of keeping each
+    link_duplicates: bool,
+    // Inherits= line of the index.theme written by full builds
+    inherits: String,
+    // Theme comment of full builds, shared by index.theme and the hyprcursor manifest
+    description: String,
+    // Cursors, by X11 or source name, that full builds leave out of the theme
+    excluded_cursors: HashSet<String>,
+    shadow: Option<ShadowConfig>,
+    current_run: Arc<AtomicUsize>,
+    active_run: Option<JoinHandle<()>>,
 }
 
 impl PipelineWorker {
     pub fn new(tx: Sender<AppMsg>, thread_count: usize) -> Self {
-        Self { tx, thread_count }
+        Self {
+            tx,
+            thread_count,
+            resize_filter: FilterType::Lanczos3,
+            max_dimension: DEFAULT_MAX_DIMENSION,
+            essential_cursors: Vec::new(),
+            cancel_flag: Arc::new(AtomicBool::new(false)),
+            verbosity: LogLevel::default_verbosity(),
+            hyprcursor_options: HyprcursorOptions::default(),
+            install_to_user_icons: true,
+            keep_intermediate: false,
+            output_layout: OutputLayout::default(),
+            link_duplicates: false,
+            inherits: DEFAULT_INHERITS.to_string(),
+            description: String::new(),
+            excluded_cursors: HashSet::new(),
+            shadow: None,
+            current_run: Arc::new(AtomicUsize::new(0)),
+            active_run: None,
+        }
+    }
+
+    pub fn set_verbosity(&mut self, verbosity: LogLevel) {
+        self.verbosity = verbosity;
+    }
+
+    /// Ask the running pipeline to stop; output written so far is left in place
+    pub fn cancel(&self) {
+        self.cancel_flag.store(true, Ordering::Relaxed);
+    }
+
+    // Cancels and silences any previous run, then starts `job` on a new thread once the
+    // previous one has exited, so two runs never write to the output at the same time.
+    fn spawn_run<F>(&mut self, job: F)
+    where
+        F: FnOnce(&PipelineTx, &AtomicBool) + Send + 'static,
+    {
+        self.cancel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.cancel_flag = Arc::clone(&cancel);
+
+        let run_id = self.current_run.fetch_add(1, Ordering::Relaxed) + 1;
+        let tx = PipelineTx {
+            run_id,
+            current_run: Arc::clone(&self.current_run),
+            ..PipelineTx::new(self.tx.clone(), self.verbosity)
+        };
+
+        let previous = self.active_run.take();
+        self.active_run = Some(thread::spawn(move || {
+            if let Some(previous) = previous {
+                let _ = previous.join();
+            }
+            // A panic deep in a parser would otherwise end the thread without a word, and the
+            // Runner would show "Running" forever
+            if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| job(&tx, &cancel))) {
+                let _ = tx.send(AppMsg::PipelineFailed(format!(
+                    "worker panicked: {}",
+                    panic_message(payload.as_ref())
+                )));
+            }
+        }));
     }
 
     pub fn set_thread_count(&mut self, count: usize) {
         self.thread_count = count;
     }
 
-    pub fn start_ani_to_png_conversion(&self, input_dir: PathBuf, output_dir: PathBuf) {
-        let tx = self.tx.clone();
-        let thread_count = self.thread_count;
+    pub fn set_resize_filter(&mut self, filter: FilterType) {
+        self.resize_filter = filter;
+    }
 
-        thread::spawn(move || {
-            if let Err(e) =
-                Self::run_ani_to_png_pipeline(&input_dir, &output_dir, &tx, thread_count)
-            {
-                let _ = tx.send(AppMsg::PipelineFailed(format!("{}", e)));
-            }
-        });
+    pub fn set_max_dimension(&mut self, max: u32) {
+        self.max_dimension = effective_max_dimension(max);
     }
 
-    fn find_cursor_files(input_dir: &Path) -> Vec<PathBuf> {
-        WalkDir::new(input_dir)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .map(|e| e.path().to_path_buf())
-            .filter(|path| {
-                path.extension()
-                    .and_then(|ext| ext.to_str())
-                    .map(|s| {
-                        let s = s.to_lowercase();
-                        s == "ani" || s == "cur"
-                    })
-                    .unwrap_or(false)
-            })
-            .collect()
+    pub fn set_hlc_compression(&mut self, compression: HlcCompression) {
+        self.hyprcursor_options.compression = compression;
     }
 
-    fn convert_batch(
-        cursor_files: &[PathBuf],
-        xcur_dir: &Path,
-        png_dir: Option<&Path>,
-        target_sizes: Vec<u32>,
-        tx: &Sender<AppMsg>,
-        thread_count: usize,
-    ) -> Result<(usize, usize)> {
-        // (processed, failed)
-        let total_files = cursor_files.len();
-        let conversion_options = ConversionOptions::new().with_target_sizes(target_sizes);
+    pub fn set_hyprcursor_resize_algorithm(&mut self, algorithm: ResizeAlgorithm) {
+        self.hyprcursor_options.resize_algorithm = algorithm;
+    }
 
-        let processed = Arc::new(AtomicUsize::new(0));
-        let failed = Arc::new(AtomicUsize::new(0));
+    pub fn set_hyprcursor_resize_overrides(
+        &mut self,
+        overrides: BTreeMap<String, ResizeAlgorithm>,
+    ) {
+        self.hyprcursor_options.resize_overrides = overrides;
+    }
 
-        let pool = rayon::ThreadPoolBuilder::new()
-            .num_threads(thread_count)
-            .build()?;
+    pub fn hyprcursor_resize_overrides(&self) -> &BTreeMap<String, ResizeAlgorithm> {
+        &self.hyprcursor_options.resize_overrides
+    }
 
-        pool.install(|| {
-            cursor_files
-                .par_iter()
-                .enumerate()
-                .for_each(|(idx, cursor_file)| {
-                    let file_name = cursor_file
-                        .file_stem()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or("cursor");
+    pub fn set_hyprcursor_manifest_format(&mut self, format: ManifestFormat) {
+        self.hyprcursor_options.manifest_format = format;
+    }
 
-                    let _ = tx.send(AppMsg::LogMessage(format!(
-                        "Processing {}/{}: {}",
-                        idx + 1,
-                        total_files,
-                        file_name
-                    )));
+    pub fn set_install_to_user_icons(&mut self, install: bool) {
+        self.install_to_user_icons = install;
+    }
 
-                    let xcur_output = xcur_dir.join(file_name);
-                    match convert_windows_cursor(
-                        cursor_file,
-                        &xcur_output,
-                        &conversion_options,
-                        |msg| {
-                            let _ = tx.send(AppMsg::LogMessage(msg));
-                        },
-                    ) {
-                        Ok(_) => {
-                            if let Some(png_out) = png_dir {
-                                let png_output_dir = png_out.join(file_name);
-                                if let Err(e) = fs::create_dir_all(&png_output_dir) {
-                                    let _ = tx.send(AppMsg::LogMessage(format!(
-                                        "Failed to create dir: {}",
-                                        e
-                                    )));
-                                    failed.fetch_add(1, Ordering::Relaxed);
-                                    return;
-                                }
-
-                                let extract_options = ExtractOptions::new()
-                                    .with_prefix(file_name)
-                                    .with_config(true);
-
-                                match extract_to_pngs(
-                                    &xcur_output,
-                                    &png_output_dir,
-                                    &extract_options,
-                                ) {
-                                    Ok(_) => {
-                                        processed.fetch_add(1, Ordering::Relaxed);
-                                    }
-                                    Err(e) => {
-                                        let _ = tx.send(AppMsg::LogMessage(format!(
-                                            "Failed to extract PNGs: {}",
-                                            e
-                                        )));
-                                        failed.fetch_add(1, Ordering::Relaxed);
-                                    }
-                                }
-                            } else {
-                                processed.fetch_add(1, Ordering::Relaxed);
-                            }
-                        }
-                        Err(e) => {
-                            let _ =
-                                tx.send(AppMsg::LogMessage(format!("Failed to convert: {}", e)));
-                            failed.fetch_add(1, Ordering::Relaxed);
-                        }
-                    }
+    pub fn set_keep_intermediate(&mut self, keep: bool) {
+        self.keep_intermediate = keep;
+    }
 
-                    let current_processed = processed.load(Ordering::Relaxed);
-                    let current_failed = failed.load(Ordering::Relaxed);
-                    let _ = tx.send(AppMsg::PipelineProgress(
-                        current_processed + current_failed,
-                        total_files,
-                    ));
-                });
-        });
+    pub fn set_link_duplicates(&mut self, link: bool) {
+        self.link_duplicates = link;
+    }
+
+    pub fn set_output_layout(&mut self, layout: OutputLayout) {
+        self.output_layout = layout;
+    }
+
+    pub fn output_layout(&self) -> OutputLayout {
+        self.output_layout
+    }
+
+    pub fn set_inherits(&mut self, inherits: String) {
+        self.inherits = inherits;
+    }
+
+    pub fn set_description(&mut self, description: String) {
+        self.description = description;
+    }
+
+    pub fn set_excluded_cursors(&mut self, names: HashSet<String>) {
+        self.excluded_cursors = names;
+    }
+
+    // `mapping` minus the excluded cursors, logging which ones are left out
+    fn without_excluded(&self, mapping: CursorMapping) -> CursorMapping {
+        if self.excluded_cursors.is_empty() {
+            return mapping;
+        }
+        let mut names: Vec<&str> = self.excluded_cursors.iter().map(|s| s.as_str()).collect();
+        names.sort_unstable();
+        let _ = self.tx.send(AppMsg::LogMessage(format!(
+            "Leaving out {} excluded cursors: {}",
+            names.len(),
+            names.join(", ")
+        )));
+        mapping.excluding(&self.excluded_cursors)
+    }
+
+    pub fn set_shadow(&mut self, shadow: Option<ShadowConfig>) {
+        self.shadow = shadow.map(ShadowConfig::clamped);
+    }
+
+    pub fn set_essential_cursors(&mut self, names: Vec<String>) {
+        self.essential_cursors = names;
+    }
+
+    pub fn essential_cursors(&self) -> &[String] {
+        &self.essential_cursors
+    }
 
-        Ok((
-            processed.load(Ordering::Relaxed),
-            failed.load(Ordering::Relaxed),
-        ))
+    fn base_options(&self) -> ConversionOptions {
+        let options = ConversionOptions::new()
+            .with_filter(self.resize_filter)
+            .with_max_dimension(self.max_dimension);
+        match &self.shadow {
+            Some(shadow) => options.with_shadow_config(shadow.clone()),
+            None => options,
+        }
+    }
+
+    pub fn start_ani_to_png_conversion(
+        &mut self,
+        input_dir: PathBuf,
+        output_dir: PathBuf,
+        target_sizes: Vec<u32>,
+    ) {
+        let thread_count = self.thread_count;
+        let options = self.base_options().with_target_sizes(target_sizes);
+        let keep_intermediate = self.keep_intermediate;
+
+        self.spawn_run(move |tx, cancel| {
+            if let Err(e) = Self::run_ani_to_png_pipeline(
+                &input_dir,
+                &output_dir,
+                &options,
+                keep_intermediate,
+                tx,
+                thread_count,
+                cancel,
+            ) {
+                let _ = tx.send(AppMsg::PipelineFailed(format!("{}", e)));
+            }
+        });
     }
 
     fn run_ani_to_png_pipeline(
         input_dir: &Path,
         output_dir: &Path,
-        tx: &Sender<AppMsg>,
+        options: &ConversionOptions,
+        keep_intermediate: bool,
+        tx: &PipelineTx,
         thread_count: usize,
+        cancel: &AtomicBool,
     ) -> Result<()> {
         fs::create_dir_all(output_dir)?;
-        let _ = tx.send(AppMsg::LogMessage(format!(
-            "Created output directory: {}",
-            output_dir.display()
-        )));
+        tx.log(
+            LogLevel::Progress,
+            format!("Created output directory: {}", output_dir.display()),
+        );
 
-        let cursor_files = Self::find_cursor_files(input_dir);
+        let cursor_files = find_cursor_files(input_dir);
         let total_files = cursor_files.len();
 
         if total_files == 0 {
@@ -191,20 +344,23 @@ impl PipelineWorker {
             "Found {} cursor files to process",
             total_files
         )));
+        log_target_sizes(tx, options);
 
         let xcur_dir = output_dir.join("_xcur_intermediate");
+        let _ = fs::remove_dir_all(&xcur_dir);
         fs::create_dir_all(&xcur_dir)?;
 
-        let (processed, failed) = Self::convert_batch(
+        let (processed, failed) = convert_batch(
             &cursor_files,
             &xcur_dir,
             Some(output_dir),
-            Vec::new(),
+            options,
             tx,
             thread_count,
+            cancel,
         )?;
 
-        let _ = fs::remove_dir_all(&xcur_dir);
+        clean_intermediate(&xcur_dir, keep_intermediate, tx);
 
         if failed > 0 {
             let _ = tx.send(AppMsg::LogMessage(format!(
@@ -217,14 +373,24 @@ impl PipelineWorker {
         Ok(())
     }
 
-    pub fn start_ani_to_xcur_conversion(&self, input_dir: PathBuf, output_dir: PathBuf) {
-        let tx = self.tx.clone();
+    pub fn start_ani_to_xcur_conversion(
+        &mut self,
+        input_dir: PathBuf,
+        output_dir: PathBuf,
+        target_sizes: Vec<u32>,
+    ) {
         let thread_count = self.thread_count;
+        let options = self.base_options().with_target_sizes(target_sizes);
 
-        thread::spawn(move || {
-            if let Err(e) =
-                Self::run_ani_to_xcur_pipeline(&input_dir, &output_dir, &tx, thread_count)
-            {
+        self.spawn_run(move |tx, cancel| {
+            if let Err(e) = Self::run_ani_to_xcur_pipeline(
+                &input_dir,
+                &output_dir,
+                &options,
+                tx,
+                thread_count,
+                cancel,
+            ) {
                 let _ = tx.send(AppMsg::PipelineFailed(format!("{}", e)));
             }
         });
@@ -233,12 +399,14 @@ impl PipelineWorker {
     fn run_ani_to_xcur_pipeline(
         input_dir: &Path,
         output_dir: &Path,
-        tx: &Sender<AppMsg>,
+        options: &ConversionOptions,
+        tx: &PipelineTx,
         thread_count: usize,
+        cancel: &AtomicBool,
     ) -> Result<()> {
         fs::create_dir_all(output_dir)?;
 
-        let cursor_files = Self::find_cursor_files(input_dir);
+        let cursor_files = find_cursor_files(input_dir);
         let total_files = cursor_files.len();
 
         if total_files == 0 {
@@ -252,14 +420,16 @@ impl PipelineWorker {
             "Found {} cursor files",
             total_files
         )));
+        log_target_sizes(tx, options);
 
-        let (processed, _) = Self::convert_batch(
+        let (processed, _) = convert_batch(
             &cursor_files,
             output_dir,
             None,
-            Vec::new(),
+            options,
             tx,
             thread_count,
+            cancel,
         )?;
 
         let _ = tx.send(AppMsg::PipelineCompleted(processed));
@@ -267,33 +437,57 @@ impl PipelineWorker {
     }
 
     pub fn start_full_theme_conversion(
-        &self,
+        &mut self,
         input_dir: PathBuf,
         output_dir: PathBuf,
         theme_name: String,
         mapping: CursorMapping,
         target_sizes: Vec<u32>,
+        variants: Vec<ThemeVariant>,
     ) {
-        let tx = self.tx.clone();
-        let thread_count = self.thread_count;
-
-        thread::spawn(move || {
-            if let Err(e) = Self::run_full_theme_pipeline(
+        let options = ThemeBuildOptions {
+            conversion: self.base_options().with_target_sizes(target_sizes),
+            variants,
+            essential_cursors: self.essential_cursors.clone(),
+            hyprcursor: self.hyprcursor_options.clone(),
+            install: self.install_to_user_icons,
+            inherits: self.inherits.clone(),
+            description: self.description.clone(),
+            keep_intermediate: self.keep_intermediate,
+            layout: self.output_layout,
+            link_duplicates: self.link_duplicates,
+            thread_count: self.thread_count,
+        };
+        let mapping = self.without_excluded(mapping);
+
+        self.spawn_run(move |tx, cancel| {
+            match run_full_theme_pipeline(
                 &input_dir,
                 &output_dir,
                 &theme_name,
-                mapping,
-                target_sizes,
-                &tx,
-                thread_count,
+                &mapping,
+                &options,
+                tx,
+                cancel,
             ) {
-                let _ = tx.send(AppMsg::PipelineFailed(format!("{}", e)));
+                Ok(build) => {
+                    for summary in &build.summaries {
+                        let _ = tx.send(AppMsg::XCursorGenerated(
+                            summary.theme_dir.display().to_string(),
+                        ));
+                    }
+                    let _ = tx.send(AppMsg::BuildFinished(build.summaries));
+                    let _ = tx.send(AppMsg::PipelineCompleted(build.converted));
+                }
+                Err(e) => {
+                    let _ = tx.send(AppMsg::PipelineFailed(format!("{}", e)));
+                }
             }
         });
     }
 
     pub fn start_incremental_theme_update(
-        &self,
+        &mut self,
         input_dir: PathBuf,
         output_dir: PathBuf,
         theme_name: String,
@@ -301,10 +495,18 @@ impl PipelineWorker {
         modified_cursors: Vec<String>,
         hotspot_overrides: HashMap<String, HashMap<u32, (u32, u32)>>,
     ) {
-        let tx = self.tx.clone();
+        // Excluded cursors stay out of the theme, edited or not
+        let mapping = self.without_excluded(mapping);
+        let modified_cursors: Vec<String> = modified_cursors
+            .into_iter()
+            .filter(|name| !self.excluded_cursors.contains(name))
+            .collect();
         let thread_count = self.thread_count;
+        let default_options = self.base_options();
+        let hyprcursor_options = self.hyprcursor_options.clone();
+        let layout = self.output_layout;
 
-        thread::spawn(move || {
+        self.spawn_run(move |tx, cancel| {
             if let Err(e) = Self::run_incremental_theme_update(
                 &input_dir,
                 &output_dir,
@@ -312,14 +514,88 @@ impl PipelineWorker {
                 mapping,
                 modified_cursors,
                 hotspot_overrides,
-                &tx,
+                default_options,
+                hyprcursor_options,
+                layout,
+                tx,
                 thread_count,
+                cancel,
             ) {
                 let _ = tx.send(AppMsg::PipelineFailed(format!("{}", e)));
             }
         });
     }
 
+    /// Recreates the symlinks of `output_dir/theme_name/cursors` from `mapping`, leaving the
+    /// cursor files alone
+    pub fn start_symlink_refresh(
+        &mut self,
+        output_dir: PathBuf,
+        theme_name: String,
+        mapping: CursorMapping,
+    ) {
+        self.spawn_run(move |tx, _cancel| {
+            let theme_output = output_dir.join(&theme_name);
+            let builder = XCursorThemeBuilder::new(&theme_output, theme_name, mapping);
+            match builder.refresh_symlinks() {
+                Ok((created, removed)) => {
+                    let _ = tx.send(AppMsg::LogMessage(format!(
+                        "Symlinks refreshed in {}: {} created, {} removed",
+                        theme_output.display(),
+                        created,
+                        removed
+                    )));
+                    let _ = tx.send(AppMsg::PipelineCompleted(created));
+                }
+                Err(e) => {
+                    let _ = tx.send(AppMsg::PipelineFailed(format!("{}", e)));
+                }
+            }
+        });
+    }
+
+    /// Writes an HTML overview of the theme's `png_intermediate` into the theme directory
+    pub fn start_gallery(&mut self, output_dir: PathBuf, theme_name: String) {
+        let png_dir = self.output_layout.png_dir(&output_dir, &theme_name);
+        self.spawn_run(move |tx, _cancel| {
+            let theme_output = output_dir.join(&theme_name);
+            match write_gallery(&png_dir, &theme_output, &theme_name, |level, msg| {
+                tx.log(level, msg)
+            }) {
+                Ok(count) => {
+                    let _ = tx.send(AppMsg::LogMessage(format!(
+                        "Gallery of {} cursors written to {}",
+                        count,
+                        theme_output.join("index.html").display()
+                    )));
+                    let _ = tx.send(AppMsg::PipelineCompleted(count));
+                }
+                Err(e) => {
+                    let _ = tx.send(AppMsg::PipelineFailed(format!("{}", e)));
+                }
+            }
+        });
+    }
+
+    pub fn start_tar_gz(&mut self, output_dir: PathBuf, theme_name: String) {
+        self.spawn_run(move |tx, _cancel| {
+            match archive_theme_tar_gz(&output_dir.join(&theme_name)) {
+                Ok(archive) => {
+                    let size = fs::metadata(&archive).map(|m| m.len()).unwrap_or(0);
+                    let _ = tx.send(AppMsg::LogMessage(format!(
+                        "Packaged theme to {} ({})",
+                        archive.display(),
+                        format_size(size)
+                    )));
+                    let _ = tx.send(AppMsg::PipelineCompleted(1));
+                }
+                Err(e) => {
+                    let _ = tx.send(AppMsg::PipelineFailed(format!("{}", e)));
+                }
+            }
+        });
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn run_incremental_theme_update(
         input_dir: &Path,
@@ -328,8 +604,12 @@ impl PipelineWorker {
         mapping: CursorMapping,
         modified_cursors: Vec<String>,
         hotspot_overrides: HashMap<String, HashMap<u32, (u32, u32)>>,
-        tx: &Sender<AppMsg>,
+        default_options: ConversionOptions,
+        hyprcursor_options: HyprcursorOptions,
+        layout: OutputLayout,
+        tx: &PipelineTx,
         thread_count: usize,
+        cancel: &AtomicBool,
     ) -> Result<()> {
         let count = modified_cursors.len();
         let _ = tx.send(AppMsg::LogMessage(format!(
@@ -340,13 +620,18 @@ impl PipelineWorker {
         let theme_output = output_dir.join(theme_name);
         let cursors_dir = theme_output.join("cursors");
         let hyprcursors_dir = theme_output.join("hyprcursors");
-        let png_dir = output_dir.join("png_intermediate");
 
         fs::create_dir_all(&cursors_dir)?;
         fs::create_dir_all(&hyprcursors_dir)?;
-        fs::create_dir_all(&png_dir)?;
+        // A theme-only layout promises nothing beside the theme directory
+        if layout.keeps_png() {
+            fs::create_dir_all(layout.png_dir(output_dir, theme_name))?;
+        }
 
-        let default_options = ConversionOptions::new();
+        // Done up front, as the parallel rewrites below may touch both ends of a link
+        for x11_name in &modified_cursors {
+            detach_duplicate_links(&cursors_dir, x11_name, &mapping.get_symlinks(x11_name))?;
+        }
 
         let pool = rayon::ThreadPoolBuilder::new()
             .num_threads(thread_count)
@@ -354,11 +639,15 @@ impl PipelineWorker {
 
         pool.install(|| {
             modified_cursors.par_iter().for_each(|x11_name| {
+                if cancel.load(Ordering::Relaxed) {
+                    return;
+                }
+
                 if let Some(win_name) = mapping.get_win_name(x11_name) {
-                    let _ = tx.send(AppMsg::LogMessage(format!(
-                        "Updating {} -> {}",
-                        x11_name, win_name
-                    )));
+                    tx.log(
+                        LogLevel::Progress,
+                        format!("Updating {} -> {}", x11_name, win_name),
+                    );
 
                     // Find source file
                     let mut source_file = None;
@@ -378,22 +667,20 @@ impl PipelineWorker {
                         // Convert to XCursor
                         let xcur_output = cursors_dir.join(x11_name);
 
-                        let mut options = default_options.clone();
+                        let mut options = default_options.clone().with_comment(x11_name);
                         if let Some(overrides) = hotspot_overrides.get(x11_name) {
                             for (size, (x, y)) in overrides {
                                 options = options.with_hotspot_override(*size, *x, *y);
                             }
                         }
 
-                        if let Err(e) =
-                            convert_windows_cursor(&source_path, &xcur_output, &options, |msg| {
-                                let _ = tx.send(AppMsg::LogMessage(msg));
-                            })
-                        {
-                            let _ = tx.send(AppMsg::LogMessage(format!(
-                                "Failed to convert XCursor: {}",
-                                e
-                            )));
+                        if let Err(e) = convert_windows_cursor(
+                            &source_path,
+                            &xcur_output,
+                            &options,
+                            |level, msg| tx.log(level, msg),
+                        ) {
+                            tx.error(format!("Failed to convert XCursor: {}", e));
                             return;
                         }
 
@@ -404,16 +691,10 @@ impl PipelineWorker {
                             if link_path.exists() {
                                 let _ = fs::remove_file(&link_path);
                             }
-                            // Create relative symlink
-                            #[cfg(unix)]
+                            if let Err(e) =
+                                symlink_relative(&cursors_dir.join(x11_name), &link_path)
                             {
-                                use std::os::unix::fs::symlink;
-                                if let Err(e) = symlink(x11_name, &link_path) {
-                                    let _ = tx.send(AppMsg::LogMessage(format!(
-                                        "Failed to symlink {}: {}",
-                                        link, e
-                                    )));
-                                }
+                                tx.error(format!("Failed to symlink {}: {}", link, e));
                             }
                         }
 
@@ -422,10 +703,7 @@ impl PipelineWorker {
                         let temp_dir = match tempfile::tempdir() {
                             Ok(d) => d,
                             Err(e) => {
-                                let _ = tx.send(AppMsg::LogMessage(format!(
-                                    "Failed to create temp dir: {}",
-                                    e
-                                )));
+                                tx.error(format!("Failed to create temp dir: {}", e));
                                 return;
                             }
                         };
@@ -435,13 +713,12 @@ impl PipelineWorker {
                         if let Err(e) = hyprcursor::extract_xcursor_to_hypr_source(
                             &xcur_output,
                             working_state_dir,
-                            None,
+                            &hyprcursor_options,
                             symlinks.clone(),
+                            Some(&hyprcursors_dir.join(format!("{}.hlc", x11_name))),
+                            default_options.max_dimension,
                         ) {
-                            let _ = tx.send(AppMsg::LogMessage(format!(
-                                "Failed to extract for Hyprcursor: {}",
-                                e
-                            )));
+                            tx.error(format!("Failed to extract for Hyprcursor: {}", e));
                             return;
                         }
 
@@ -452,182 +729,229 @@ impl PipelineWorker {
                             &shape_dir,
                             &hyprcursors_dir,
                             x11_name,
-                            |msg| {
-                                let _ = tx.send(AppMsg::LogMessage(msg));
-                            },
+                            hyprcursor_options.compression,
+                            |level, msg| tx.log(level, msg),
                         ) {
-                            let _ = tx.send(AppMsg::LogMessage(format!(
-                                "Failed to compile Hyprcursor: {}",
-                                e
-                            )));
+                            tx.error(format!("Failed to compile Hyprcursor: {}", e));
                         } else {
-                            let _ = tx.send(AppMsg::LogMessage(format!("Updated {}", x11_name)));
+                            tx.log(LogLevel::Progress, format!("Updated {}", x11_name));
                         }
                     } else {
-                        let _ = tx.send(AppMsg::LogMessage(format!(
-                            "Source file not found for {}",
-                            win_name
-                        )));
+                        tx.warn(format!("Source file not found for {}", win_name));
                     }
                 }
             });
         });
 
+        check_cancelled(cancel)?;
+
         let _ = tx.send(AppMsg::LogMessage(
             "Incremental update completed.".to_string(),
         ));
         Ok(())
     }
+}
 
-    fn run_full_theme_pipeline(
-        input_dir: &Path,
-        output_dir: &Path,
-        theme_name: &str,
-        mapping: CursorMapping,
-        target_sizes: Vec<u32>,
-        tx: &Sender<AppMsg>,
-        thread_count: usize,
-    ) -> Result<()> {
-        // ANI to XCursor binaries
-        let _ = tx.send(AppMsg::LogMessage(
-            "Converting ANI files to X11 cursor format...".to_string(),
-        ));
-
-        let xcur_dir = output_dir.join("xcur_intermediate");
-        fs::create_dir_all(&xcur_dir)?;
-
-        let png_dir = output_dir.join("png_intermediate");
-        fs::create_dir_all(&png_dir)?;
-
-        let cursor_files = Self::find_cursor_files(input_dir);
-        let total_files = cursor_files.len();
-
-        if total_files == 0 {
-            let _ = tx.send(AppMsg::PipelineFailed(
-                "No .ani or .cur files found".to_string(),
-            ));
-            return Ok(());
-        }
-
-        let (processed, _) = Self::convert_batch(
-            &cursor_files,
-            &xcur_dir,
-            Some(&png_dir),
-            target_sizes,
-            tx,
-            thread_count,
-        )?;
-
-        if processed == 0 {
-            let _ = tx.send(AppMsg::PipelineFailed(
-                "Failed to convert any cursor files".to_string(),
-            ));
-            return Ok(());
-        }
-
-        let _ = tx.send(AppMsg::LogMessage(format!(
-            "Converted {}/{} cursor files",
-            processed, total_files
-        )));
-
-        // Organize into theme with mapping
-        let _ = tx.send(AppMsg::LogMessage(
-            "Building XCursor theme with mapping...".to_string(),
-        ));
-
-        let theme_output = output_dir.join(theme_name);
-        let builder =
-            XCursorThemeBuilder::new(theme_output.clone(), theme_name.to_string(), mapping);
-
-        let theme_count = builder.build_from_xcur_files(&xcur_dir)?;
-
-        let _ = tx.send(AppMsg::LogMessage(format!(
-            "Created theme with {} cursors and symlinks",
-            theme_count
-        )));
-
-        let _ = fs::remove_dir_all(&xcur_dir);
-
-        // Generate Hyprcursor theme
-        let _ = tx.send(AppMsg::LogMessage(
-            "Generating Hyprcursor theme...".to_string(),
-        ));
-
-        let temp_dir = tempfile::tempdir()?;
-        let working_state_dir = temp_dir.path();
-
-        // Extract XCursor theme to working state
-        let _ = tx.send(AppMsg::LogMessage(
-            "Extracting XCursor theme to working state...".to_string(),
-        ));
-
-        hyprcursor::extract_xcursor_theme(
-            &theme_output,
-            Some(working_state_dir),
-            None,
-            true,
-            |msg| {
-                let _ = tx.send(AppMsg::LogMessage(msg));
-            },
-        )?;
-
-        // Compile Hyprcursor theme back into the theme directory
-        let _ = tx.send(AppMsg::LogMessage(
-            "Compiling Hyprcursor theme...".to_string(),
-        ));
-
-        hyprcursor::create_cursor_theme(working_state_dir, Some(&theme_output), true, |msg| {
-            let _ = tx.send(AppMsg::LogMessage(msg));
-        })?;
-
-        let _ = tx.send(AppMsg::LogMessage(format!(
-            "Generated Hyprcursor files in {}",
-            theme_output.display()
-        )));
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown cause"
+    }
+}
 
-        let _ = tx.send(AppMsg::XCursorGenerated(theme_output.display().to_string()));
-        let _ = tx.send(AppMsg::PipelineCompleted(processed));
-        Ok(())
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1024 * 1024 => format!("{:.1} MiB", b as f64 / (1024.0 * 1024.0)),
+        b if b >= 1024 => format!("{:.1} KiB", b as f64 / 1024.0),
+        b => format!("{} B", b),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pipeline::theme_build::tests::png_cur_bytes;
     use crossbeam_channel::unbounded;
-    use std::fs::File;
     use tempfile::tempdir;
 
     #[test]
-    fn test_convert_batch_threading() {
-        let (tx, rx) = unbounded();
-        let temp_dir = tempdir().unwrap();
-        let input_dir = temp_dir.path().join("input");
-        let xcur_dir = temp_dir.path().join("xcur");
-        let png_dir = temp_dir.path().join("png");
-
-        fs::create_dir_all(&input_dir).unwrap();
-        fs::create_dir_all(&xcur_dir).unwrap();
-        fs::create_dir_all(&png_dir).unwrap();
-
-        let mut files = Vec::new();
-        for i in 0..10 {
-            let path = input_dir.join(format!("cursor_{}.cur", i));
-            File::create(&path).unwrap();
-            files.push(path);
-        }
+    fn test_restart_supersedes_previous_run() {
+        let (sender, rx) = unbounded();
+        let mut worker = PipelineWorker::new(sender, 1);
+
+        worker.spawn_run(|tx, cancel| {
+            while !cancel.load(Ordering::Relaxed) {
+                thread::sleep(std::time::Duration::from_millis(1));
+            }
+            let _ = tx.send(AppMsg::LogMessage("stale".to_string()));
+        });
+        worker.spawn_run(|tx, _| {
+            let _ = tx.send(AppMsg::LogMessage("fresh".to_string()));
+        });
+        worker.active_run.take().unwrap().join().unwrap();
 
-        let result =
-            PipelineWorker::convert_batch(&files, &xcur_dir, Some(&png_dir), Vec::new(), &tx, 4);
+        let messages: Vec<String> = rx
+            .try_iter()
+            .filter_map(|msg| match msg {
+                AppMsg::LogMessage(text) => Some(text),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(messages, vec!["fresh"]);
+    }
 
-        assert!(result.is_ok());
-        let (processed, failed) = result.unwrap();
-        assert_eq!(processed + failed, 10);
+    #[test]
+    fn test_panicking_run_reports_failure() {
+        let (sender, rx) = unbounded();
+        let mut worker = PipelineWorker::new(sender, 1);
 
-        let mut msg_count = 0;
-        while let Ok(_) = rx.try_recv() {
-            msg_count += 1;
+        worker.spawn_run(|_, _| {
+            let frames: Vec<u32> = Vec::new();
+            let _ = frames[0];
+        });
+        worker.active_run.take().unwrap().join().unwrap();
+
+        let failures: Vec<String> = rx
+            .try_iter()
+            .filter_map(|msg| match msg {
+                AppMsg::PipelineFailed(text) => Some(text),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].starts_with("worker panicked: index out of bounds"));
+    }
+
+    #[test]
+    fn test_pipeline_tx_verbosity() {
+        let (sender, rx) = unbounded();
+        let tx = PipelineTx::new(sender, LogLevel::Info);
+
+        tx.log(LogLevel::Progress, "Processing 1/200: arrow".to_string());
+        tx.log(LogLevel::Detail, "Created arrow.hlc".to_string());
+        tx.log(LogLevel::Warn, "Warning: Very short delay".to_string());
+        tx.log(LogLevel::Info, "Building XCursor theme...".to_string());
+
+        let messages: Vec<(LogLevel, String)> = rx
+            .try_iter()
+            .filter_map(|msg| match msg {
+                AppMsg::LogMessage(text) => Some((LogLevel::Info, text)),
+                AppMsg::LogMessageLevel(level, text) => Some((level, text)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (LogLevel::Warn, "Warning: Very short delay".to_string()),
+                (LogLevel::Info, "Building XCursor theme...".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_incremental_update_theme_only_writes_no_pngs() {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join("src");
+        fs::create_dir_all(&input).unwrap();
+        fs::write(input.join("Normal.cur"), png_cur_bytes(32, (0, 0))).unwrap();
+        let output = dir.path().join("out");
+        let (sender, _rx) = crossbeam_channel::unbounded();
+        let tx = PipelineTx::new(sender, LogLevel::Progress);
+
+        PipelineWorker::run_incremental_theme_update(
+            &input,
+            &output,
+            "Test",
+            CursorMapping::default(),
+            vec!["left_ptr".to_string()],
+            HashMap::new(),
+            ConversionOptions::new(),
+            HyprcursorOptions::default(),
+            OutputLayout::ThemeOnly,
+            &tx,
+            1,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        let top: Vec<_> = fs::read_dir(&output)
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(top, vec!["Test"]);
+        assert!(
+            output
+                .join("Test")
+                .join("cursors")
+                .join("left_ptr")
+                .exists()
+        );
+    }
+
+    #[test]
+    fn test_incremental_update_keeps_linked_duplicates_apart() {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join("src");
+        fs::create_dir_all(&input).unwrap();
+        fs::write(input.join("Horizontal.cur"), png_cur_bytes(32, (0, 0))).unwrap();
+        fs::write(input.join("Vertical.cur"), png_cur_bytes(32, (0, 0))).unwrap();
+        let mapping = CursorMapping {
+            x11_to_win: [("size_hor", "Horizontal"), ("size_ver", "Vertical")]
+                .into_iter()
+                .map(|(x11, win)| (x11.to_string(), win.to_string()))
+                .collect(),
+            symlinks: BTreeMap::new(),
+        };
+
+        // Edits the original of the pair, then the link, each in a fresh build
+        for (edited, other) in [("size_hor", "size_ver"), ("size_ver", "size_hor")] {
+            let output = dir.path().join(format!("out_{}", edited));
+            let (sender, _rx) = crossbeam_channel::unbounded();
+            let tx = PipelineTx::new(sender, LogLevel::Progress);
+            let options = ThemeBuildOptions {
+                link_duplicates: true,
+                thread_count: 1,
+                ..ThemeBuildOptions::default()
+            };
+            run_full_theme_pipeline(
+                &input,
+                &output,
+                "Test",
+                &mapping,
+                &options,
+                &tx,
+                &AtomicBool::new(false),
+            )
+            .unwrap();
+            let cursors = output.join("Test").join("cursors");
+            assert!(cursors.join("size_ver").is_symlink());
+            let before = fs::read(cursors.join(other)).unwrap();
+
+            let hotspots = HashMap::from([(edited.to_string(), HashMap::from([(32, (9, 9))]))]);
+            PipelineWorker::run_incremental_theme_update(
+                &input,
+                &output,
+                "Test",
+                mapping.clone(),
+                vec![edited.to_string()],
+                hotspots,
+                ConversionOptions::new(),
+                HyprcursorOptions::default(),
+                OutputLayout::default(),
+                &tx,
+                1,
+                &AtomicBool::new(false),
+            )
+            .unwrap();
+
+            assert_eq!(fs::read(cursors.join(other)).unwrap(), before, "{edited}");
+            assert_ne!(fs::read(cursors.join(edited)).unwrap(), before, "{edited}");
+            assert!(!cursors.join(edited).is_symlink());
+            assert!(!cursors.join(other).is_symlink());
         }
-        assert!(msg_count > 0);
     }
 }
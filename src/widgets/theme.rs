@@ -1,7 +1,8 @@
 use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
 use std::sync::{LazyLock, RwLock};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ThemeType {
     CatppuccinMocha,
     CatppuccinMacchiato,
@@ -58,6 +59,20 @@ impl ThemeType {
         }
     }
 
+    /// Looks a theme up by `name()`, ignoring case and spaces
+    pub fn from_name(name: &str) -> Option<ThemeType> {
+        let normalize = |s: &str| -> String {
+            s.chars()
+                .filter(|c| !c.is_whitespace())
+                .flat_map(char::to_lowercase)
+                .collect()
+        };
+        let wanted = normalize(name);
+        Self::all()
+            .into_iter()
+            .find(|t| normalize(t.name()) == wanted)
+    }
+
     pub fn next(&self) -> ThemeType {
         let all = Self::all();
         let idx = all.iter().position(|t| t == self).unwrap_or(0);
@@ -354,3 +369,21 @@ pub fn set_theme(theme_type: ThemeType) {
 pub fn get_current_theme_type() -> ThemeType {
     *CURRENT_THEME.read().unwrap_or_else(|e| e.into_inner())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_name_ignores_case_and_spaces() {
+        assert_eq!(ThemeType::from_name("Nord"), Some(ThemeType::Nord));
+        assert_eq!(ThemeType::from_name("nord"), Some(ThemeType::Nord));
+        assert_eq!(
+            ThemeType::from_name("solarizedlight"),
+            Some(ThemeType::SolarizedLight)
+        );
+        assert_eq!(ThemeType::from_name("ONE DARK"), Some(ThemeType::OneDark));
+        assert_eq!(ThemeType::from_name("Solarized"), None);
+        assert_eq!(ThemeType::from_name(""), None);
+    }
+}